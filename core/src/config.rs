@@ -21,38 +21,73 @@ use super::IValue;
 use super::IType;
 use crate::HostImportError;
 
+use marine_module_info_parser::effects::EffectKind;
+use marine_module_info_parser::signature::ModuleSigner;
 use marine_wasm_backend_traits::WasiParameters;
 use marine_wasm_backend_traits::WasmBackend;
 
+use futures::future::BoxFuture;
+
 use std::path::PathBuf;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub type ErrorHandler =
-    Option<Box<dyn Fn(&HostImportError) -> Option<IValue> + Sync + Send + 'static>>;
+    Option<Box<dyn Fn(&HostImportError) -> Vec<IValue> + Sync + Send + 'static>>;
 pub type HostExportedFunc<WB> = Box<
-    dyn for<'c> Fn(&mut <WB as WasmBackend>::ImportCallContext<'c>, Vec<IValue>) -> Option<IValue>
+    dyn for<'c> Fn(
+            &mut <WB as WasmBackend>::ImportCallContext<'c>,
+            Vec<IValue>,
+        ) -> Result<Vec<IValue>, HostImportError>
+        + Sync
+        + Send
+        + 'static,
+>;
+
+pub type AsyncHostExportedFunc = Box<
+    dyn Fn(Vec<IValue>) -> BoxFuture<'static, Result<Vec<IValue>, HostImportError>>
         + Sync
         + Send
         + 'static,
 >;
 
+/// A host closure behind a host import.
+pub enum HostImportFunc<WB: WasmBackend> {
+    /// Runs in place, it has access to the calling module through the import call context.
+    Sync(HostExportedFunc<WB>),
+
+    /// Returns a future, which is awaited without blocking the executor,
+    /// so it suits long operations like running external processes.
+    Async(AsyncHostExportedFunc),
+}
+
+/// Allows host imports written before async closures appeared to be migrated with `.into()`.
+impl<WB: WasmBackend> From<HostExportedFunc<WB>> for HostImportFunc<WB> {
+    fn from(func: HostExportedFunc<WB>) -> Self {
+        Self::Sync(func)
+    }
+}
+
 pub type RawImportCreator<WB> = Arc<
     dyn Fn(<WB as WasmBackend>::ContextMut<'_>) -> <WB as WasmBackend>::HostFunction + Send + Sync,
 >;
 
 pub struct HostImportDescriptor<WB: WasmBackend> {
     /// This closure will be invoked for corresponding import.
-    pub host_exported_func: HostExportedFunc<WB>,
+    /// If it returns an error, the calling Wasm module traps with this error.
+    pub host_exported_func: HostImportFunc<WB>,
 
     /// Type of the closure arguments.
     pub argument_types: Vec<IType>,
 
     /// Types of output of the closure.
-    pub output_type: Option<IType>,
+    /// A single string, array or record output is passed back to a Wasm module through
+    /// set_result_ptr/set_result_size, several outputs are passed back as Wasm multi-values,
+    /// where strings and arrays are represented by a pointer and a size and records by a pointer.
+    pub output_types: Vec<IType>,
 
     /// If Some, this closure is called with error when errors is encountered while lifting.
-    /// If None, panic will occur.
+    /// If None, the calling Wasm module traps with this error.
     pub error_handler: ErrorHandler,
 }
 
@@ -116,6 +151,17 @@ impl<WB: WasmBackend> MModuleConfig<WB> {
 pub struct MarineCoreConfig<WB: WasmBackend> {
     pub(crate) total_memory_limit: u64,
     pub(crate) wasm_backend: WB,
+    pub(crate) trusted_signers: Option<Vec<ModuleSigner>>,
+    pub(crate) effects_policy: Option<EffectsPolicy>,
+}
+
+/// Restricts effects of modules, they are derived from module imports and checked before instantiation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EffectsPolicy {
+    /// If set, modules could have only effects of these kinds.
+    pub allowed: Option<Vec<EffectKind>>,
+    /// Modules couldn't have effects of these kinds, even if they are allowed.
+    pub denied: Vec<EffectKind>,
 }
 
 pub const INFINITE_MEMORY_LIMIT: u64 = u64::MAX;
@@ -125,6 +171,31 @@ impl<WB: WasmBackend> MarineCoreConfig<WB> {
         Self {
             total_memory_limit: total_memory_limit.unwrap_or(INFINITE_MEMORY_LIMIT),
             wasm_backend,
+            trusted_signers: None,
+            effects_policy: None,
         }
     }
+
+    /// Allows loading only modules signed by one of these signers.
+    pub fn with_trusted_signers(mut self, trusted_signers: Option<Vec<ModuleSigner>>) -> Self {
+        self.trusted_signers = trusted_signers;
+        self
+    }
+
+    /// Allows loading only modules which effects satisfy the policy.
+    pub fn with_effects_policy(mut self, effects_policy: Option<EffectsPolicy>) -> Self {
+        self.effects_policy = effects_policy;
+        self
+    }
+}
+
+impl EffectsPolicy {
+    pub fn is_allowed(&self, kind: EffectKind) -> bool {
+        let allowed = match &self.allowed {
+            Some(allowed) => allowed.contains(&kind),
+            None => true,
+        };
+
+        allowed && !self.denied.contains(&kind)
+    }
 }
@@ -25,6 +25,9 @@ use marine_it_parser::ITParserError;
 use marine_module_interface::it_interface::ITInterfaceError;
 use marine_wasm_backend_traits::errors::*;
 
+use wasmer_it::errors::InstructionError;
+use wasmer_it::errors::InstructionErrorKind;
+use anyhow::anyhow;
 use thiserror::Error as ThisError;
 
 // TODO: refactor errors
@@ -46,7 +49,7 @@ pub enum MError {
 
     /// Error arisen during execution of Wasm modules (especially, interface types).
     #[error("Execution error: {0}")]
-    ITInstructionError(#[from] wasmer_it::errors::InstructionError),
+    ITInstructionError(InstructionError),
 
     /// Error that raises on the preparation step.
     #[error(transparent)]
@@ -115,3 +118,62 @@ impl From<RuntimeError> for MError {
         Into::<WasmBackendError>::into(value).into()
     }
 }
+
+impl From<InstructionError> for MError {
+    fn from(error: InstructionError) -> Self {
+        match extract_host_import_error(error) {
+            Ok(error) => MError::HostImportError(error),
+            Err(error) => MError::ITInstructionError(error),
+        }
+    }
+}
+
+/// A failed host import traps the calling Wasm module with HostImportError, which then comes
+/// back wrapped into errors of a Wasm backend and the IT interpreter.
+fn extract_host_import_error(error: InstructionError) -> Result<HostImportError, InstructionError> {
+    let InstructionError {
+        instruction,
+        error_kind,
+    } = error;
+
+    match error_kind {
+        InstructionErrorKind::LocalOrImportCall {
+            function_name,
+            reason,
+        } => downcast_host_import_error(reason).map_err(|reason| InstructionError {
+            instruction,
+            error_kind: InstructionErrorKind::LocalOrImportCall {
+                function_name,
+                reason,
+            },
+        }),
+        error_kind => Err(InstructionError {
+            instruction,
+            error_kind,
+        }),
+    }
+}
+
+fn downcast_host_import_error(reason: anyhow::Error) -> Result<HostImportError, anyhow::Error> {
+    let reason = match reason.downcast::<HostImportError>() {
+        Ok(error) => return Ok(error),
+        Err(reason) => reason,
+    };
+
+    // a host import called from an export of the same module
+    let reason = match reason.downcast::<RuntimeError>() {
+        Ok(RuntimeError::Other(reason)) => {
+            return downcast_host_import_error(reason)
+                .map_err(|reason| anyhow!(RuntimeError::Other(reason)))
+        }
+        Ok(error) => return Err(anyhow!(error)),
+        Err(reason) => reason,
+    };
+
+    // a host import called from another module
+    match reason.downcast::<MError>() {
+        Ok(MError::HostImportError(error)) => Ok(error),
+        Ok(error) => Err(anyhow!(error)),
+        Err(reason) => Err(reason),
+    }
+}
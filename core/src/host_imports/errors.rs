@@ -23,6 +23,8 @@ use super::WValue;
 use it_lilo::lifter::LiError;
 use it_lilo::lowerer::LoError;
 use it_lilo::traits::RecordResolvableError;
+use marine_wasm_backend_traits::errors::ResolveError;
+use marine_wasm_backend_traits::errors::RuntimeError;
 use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
@@ -50,4 +52,32 @@ pub enum HostImportError {
 
     #[error(transparent)]
     InvalidUTF8String(#[from] std::string::FromUtf8Error),
+
+    /// A host import was called not from a Wasm module, so there is no memory to work with.
+    #[error("host import is called not from a Wasm module, the Wasm memory is missing")]
+    MissingMemory,
+
+    /// A Wasm module doesn't export a function needed to pass results of a host import back.
+    #[error(
+        "failed to get `{func_name}` export needed to pass host import results back: {reason}"
+    )]
+    MissingExport {
+        func_name: &'static str,
+        reason: ResolveError,
+    },
+
+    /// A function exported by a Wasm module failed while results of a host import were being passed back.
+    #[error("`{func_name}` failed while host import results were being passed back: {reason}")]
+    ExportCallFailed {
+        func_name: &'static str,
+        reason: RuntimeError,
+    },
+
+    /// A host closure returned a number of results different from the one stated in its descriptor.
+    #[error("host closure returned {actual} values, but {expected} values are expected")]
+    OutputsCountMismatch { expected: usize, actual: usize },
+
+    /// A host closure failed to produce its result.
+    #[error("host closure failed: {0}")]
+    HostClosureError(anyhow::Error),
 }
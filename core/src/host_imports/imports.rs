@@ -31,6 +31,7 @@ use crate::MRecordTypes;
 use crate::init_wasm_func;
 use crate::call_wasm_func;
 use crate::generic::HostImportDescriptor;
+use crate::generic::HostImportFunc;
 
 use marine_wasm_backend_traits::prelude::*;
 
@@ -49,8 +50,7 @@ pub(crate) fn create_host_import_func<WB: WasmBackend>(
     record_types: Arc<MRecordTypes>,
 ) -> <WB as WasmBackend>::HostFunction {
     let raw_args = itypes_args_to_wtypes(&descriptor.argument_types);
-    let raw_output =
-        itypes_output_to_wtypes(&output_type_to_types(descriptor.output_type.as_ref()));
+    let raw_output = itypes_output_to_wtypes(&descriptor.output_types);
 
     let descriptor = Arc::new(descriptor);
     let func = create_host_import_closure(descriptor, record_types);
@@ -71,13 +71,13 @@ async fn call_host_import<'args, WB: WasmBackend>(
     let HostImportDescriptor {
         host_exported_func,
         argument_types,
+        output_types,
         error_handler,
-        ..
     } = descriptor.as_ref();
 
     let memory = caller
         .memory(STANDARD_MEMORY_INDEX)
-        .unwrap_or_else(|| panic!("Host import called directly, not from wasm"));
+        .ok_or(HostImportError::MissingMemory)?;
 
     let inputs = lift_inputs::<WB>(
         &mut caller,
@@ -86,17 +86,31 @@ async fn call_host_import<'args, WB: WasmBackend>(
         inputs,
         argument_types,
     );
-    let output = match inputs {
-        Ok(ivalues) => host_exported_func(&mut caller, ivalues),
+    let outputs = match inputs {
+        Ok(ivalues) => match host_exported_func {
+            HostImportFunc::Sync(func) => func(&mut caller, ivalues)?,
+            HostImportFunc::Async(func) => func(ivalues).await?,
+        },
         Err(e) => {
             log::error!("error occurred while lifting values in host import: {}", e);
-            error_handler
-                .as_ref()
-                .map_or_else(|| default_error_handler(&e), |h| h(&e))
+            match error_handler {
+                Some(handler) => handler(&e),
+                // the error traps the calling Wasm module
+                None => return Err(e.into()),
+            }
         }
     };
 
-    Ok(lower_outputs::<WB>(caller, memory, output).await)
+    if outputs.len() != output_types.len() {
+        return Err(HostImportError::OutputsCountMismatch {
+            expected: output_types.len(),
+            actual: outputs.len(),
+        }
+        .into());
+    }
+
+    let outputs = lower_outputs::<WB>(caller, memory, outputs).await?;
+    Ok(outputs)
 }
 
 fn lift_inputs<WB: WasmBackend>(
@@ -120,110 +134,78 @@ fn lift_inputs<WB: WasmBackend>(
 async fn lower_outputs<WB: WasmBackend>(
     mut caller: <WB as WasmBackend>::ImportCallContext<'_>,
     memory: <WB as WasmBackend>::Memory,
-    output: Option<IValue>,
-) -> Vec<WValue> {
-    init_wasm_func!(
-        allocate_func,
-        caller,
-        (i32, i32),
-        i32,
-        ALLOCATE_FUNC_NAME,
-        2
-    );
-
-    let is_record = matches!(&output, Some(IValue::Record(_)));
+    mut outputs: Vec<IValue>,
+) -> HostImportResult<Vec<WValue>> {
+    init_wasm_func!(allocate_func, caller, (i32, i32), i32, ALLOCATE_FUNC_NAME);
 
     let memory_view = memory.view();
     let mut lo_helper = LoHelper::new(allocate_func.clone(), memory);
-    let lowerer =
-        ILowerer::<'_, _, _, DelayedContextLifetime<WB>>::new(memory_view, &mut lo_helper)
-            .map_err(HostImportError::LowererError);
-    let lowering_result = match lowerer {
-        Ok(mut lowerer) => {
-            ivalue_to_wvalues(&mut caller.as_context_mut(), &mut lowerer, output).await
+    let mut lowerer =
+        ILowerer::<'_, _, _, DelayedContextLifetime<WB>>::new(memory_view, &mut lo_helper)?;
+
+    let output = match outputs.len() {
+        0 => return Ok(vec![]),
+        1 => outputs.remove(0),
+        // several outputs are passed back as is, so strings, arrays and records
+        // are represented by their pointers and sizes
+        _ => {
+            let mut wvalues = Vec::with_capacity(outputs.len());
+            for output in outputs {
+                let output_wvalues =
+                    ivalue_to_wvalues(&mut caller.as_context_mut(), &mut lowerer, output).await?;
+                wvalues.extend(output_wvalues);
+            }
+
+            return Ok(wvalues);
         }
-        Err(e) => Err(e),
     };
 
-    let wvalues = match lowering_result {
-        Ok(wvalues) => wvalues,
-        Err(e) => {
-            log::error!("host closure failed: {}", e);
-
-            // returns 0 to a Wasm module in case of errors
-            init_wasm_func!(set_result_ptr_func, caller, i32, (), SET_PTR_FUNC_NAME, 4);
-            init_wasm_func!(set_result_size_func, caller, i32, (), SET_SIZE_FUNC_NAME, 4);
-
-            let mut store_ctx = caller.as_context_mut();
-            call_wasm_func!(set_result_ptr_func, &mut store_ctx, 0);
-            call_wasm_func!(set_result_size_func, &mut store_ctx, 0);
-
-            return vec![WValue::I32(0)];
-        }
-    };
+    let is_record = matches!(&output, IValue::Record(_));
+    let wvalues = ivalue_to_wvalues(&mut caller.as_context_mut(), &mut lowerer, output).await?;
 
-    // TODO: refactor this when multi-value is supported
-    match wvalues.len() {
+    match wvalues.as_slice() {
         // strings and arrays are passed back to the Wasm module by pointer and size
         // values used and consumed by set_result_ptr and set_result_size
-        2 => {
-            init_wasm_func!(set_result_ptr_func, caller, i32, (), SET_PTR_FUNC_NAME, 4);
-            init_wasm_func!(set_result_size_func, caller, i32, (), SET_SIZE_FUNC_NAME, 4);
+        [offset, size] => {
+            init_wasm_func!(set_result_ptr_func, caller, i32, (), SET_PTR_FUNC_NAME);
+            init_wasm_func!(set_result_size_func, caller, i32, (), SET_SIZE_FUNC_NAME);
 
             let mut store_ctx = caller.as_context_mut();
             call_wasm_func!(
                 set_result_ptr_func,
+                SET_PTR_FUNC_NAME,
                 &mut store_ctx,
-                wvalues[0].to_u128() as _
+                offset.to_u128() as _
             );
             call_wasm_func!(
                 set_result_size_func,
+                SET_SIZE_FUNC_NAME,
                 &mut store_ctx,
-                wvalues[1].to_u128() as _
+                size.to_u128() as _
             );
-            vec![]
+            Ok(vec![])
         }
 
         // records lowerer returns only pointer which has to be used and consumed via set_result_ptr
-        1 if is_record => {
-            init_wasm_func!(set_result_ptr_func, caller, i32, (), SET_PTR_FUNC_NAME, 3);
+        [offset] if is_record => {
+            init_wasm_func!(set_result_ptr_func, caller, i32, (), SET_PTR_FUNC_NAME);
 
             let mut store_ctx = caller.as_context_mut();
             call_wasm_func!(
                 set_result_ptr_func,
+                SET_PTR_FUNC_NAME,
                 &mut store_ctx,
-                wvalues[0].to_u128() as _
+                offset.to_u128() as _
             );
 
-            vec![]
+            Ok(vec![])
         }
 
         // primitive values are passed as is
-        1 => vec![wvalues[0].clone()],
-
-        // when None is passed
-        0 => vec![],
-
-        // at now while multi-values aren't supported ivalue_to_wvalues returns only Vec with
-        // 0, 1, 2 values
-        _ => unimplemented!(),
-    }
-}
-
-fn output_type_to_types(output_type: Option<&IType>) -> Vec<IType> {
-    match output_type {
-        Some(ty) => vec![ty.clone()],
-        None => vec![],
+        _ => Ok(wvalues),
     }
 }
 
-fn default_error_handler(err: &HostImportError) -> Option<crate::IValue> {
-    panic!(
-        "an error is occurred while lifting values to interface values: {}",
-        err
-    )
-}
-
 fn create_host_import_closure<WB: WasmBackend>(
     descriptor: Arc<HostImportDescriptor<WB>>,
     record_types: Arc<MRecordTypes>,
@@ -32,41 +32,40 @@ pub(crate) async fn ivalue_to_wvalues<
 >(
     store: &mut <Store as it_memory_traits::Store>::ActualStore<'_>,
     lowerer: &mut ILowerer<'_, A, MV, Store>,
-    ivalue: Option<IValue>,
+    ivalue: IValue,
 ) -> HostImportResult<Vec<WValue>> {
     let result = match ivalue {
-        Some(IValue::Boolean(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::S8(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::S16(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::S32(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::S64(v)) => vec![WValue::I64(v as _)],
-        Some(IValue::U8(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::U16(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::U32(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::U64(v)) => vec![WValue::I64(v as _)],
-        Some(IValue::I32(v)) => vec![WValue::I32(v as _)],
-        Some(IValue::I64(v)) => vec![WValue::I64(v as _)],
-        Some(IValue::F32(v)) => vec![WValue::F32(v)],
-        Some(IValue::F64(v)) => vec![WValue::F64(v)],
-        Some(IValue::String(str)) => {
+        IValue::Boolean(v) => vec![WValue::I32(v as _)],
+        IValue::S8(v) => vec![WValue::I32(v as _)],
+        IValue::S16(v) => vec![WValue::I32(v as _)],
+        IValue::S32(v) => vec![WValue::I32(v as _)],
+        IValue::S64(v) => vec![WValue::I64(v as _)],
+        IValue::U8(v) => vec![WValue::I32(v as _)],
+        IValue::U16(v) => vec![WValue::I32(v as _)],
+        IValue::U32(v) => vec![WValue::I32(v as _)],
+        IValue::U64(v) => vec![WValue::I64(v as _)],
+        IValue::I32(v) => vec![WValue::I32(v as _)],
+        IValue::I64(v) => vec![WValue::I64(v as _)],
+        IValue::F32(v) => vec![WValue::F32(v)],
+        IValue::F64(v) => vec![WValue::F64(v)],
+        IValue::String(str) => {
             let offset = lowerer.writer.write_bytes(store, str.as_bytes()).await?;
 
             vec![WValue::I32(offset as _), WValue::I32(str.len() as _)]
         }
-        Some(IValue::ByteArray(array)) => {
+        IValue::ByteArray(array) => {
             let offset = lowerer.writer.write_bytes(store, &array).await?;
 
             vec![WValue::I32(offset as _), WValue::I32(array.len() as _)]
         }
-        Some(IValue::Array(values)) => {
+        IValue::Array(values) => {
             let LoweredArray { offset, size } = array_lower_memory(store, lowerer, values).await?;
             vec![WValue::I32(offset as _), WValue::I32(size as _)]
         }
-        Some(IValue::Record(values)) => {
+        IValue::Record(values) => {
             let offset = record_lower_memory(store, lowerer, values).await?;
             vec![WValue::I32(offset as i32)]
         }
-        None => vec![],
     };
 
     Ok(result)
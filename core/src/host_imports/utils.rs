@@ -21,47 +21,51 @@ use super::WType;
 use crate::IType;
 
 pub(super) fn itypes_args_to_wtypes(itypes: &[IType]) -> Vec<WType> {
-    itypes
-        .iter()
-        .flat_map(|itype| match itype {
-            IType::F32 => vec![WType::F32],
-            IType::F64 => vec![WType::F64],
-            IType::I64 | IType::U64 => vec![WType::I64],
-            IType::String | IType::Array(_) => vec![WType::I32, WType::I32],
-            _ => vec![WType::I32],
-        })
-        .collect()
+    itypes.iter().flat_map(itype_to_raw_wtypes).collect()
 }
 
 pub(super) fn itypes_output_to_wtypes(itypes: &[IType]) -> Vec<WType> {
-    itypes
-        .iter()
-        .flat_map(|itype| match itype {
-            IType::F32 => vec![WType::F32],
-            IType::F64 => vec![WType::F64],
-            IType::I64 | IType::U64 => vec![WType::I64],
-            IType::String | IType::Array(_) | IType::Record(_) => vec![],
-            _ => vec![WType::I32],
-        })
-        .collect()
+    match itypes {
+        // a single string, array or record is passed back through set_result_ptr/set_result_size
+        [IType::String | IType::ByteArray | IType::Array(_) | IType::Record(_)] => vec![],
+        // several outputs are passed back as is, in the same way as arguments
+        itypes => itypes_args_to_wtypes(itypes),
+    }
+}
+
+fn itype_to_raw_wtypes(itype: &IType) -> Vec<WType> {
+    match itype {
+        IType::F32 => vec![WType::F32],
+        IType::F64 => vec![WType::F64],
+        IType::I64 | IType::U64 | IType::S64 => vec![WType::I64],
+        IType::String | IType::ByteArray | IType::Array(_) => vec![WType::I32, WType::I32],
+        _ => vec![WType::I32],
+    }
 }
 
 #[macro_export]
 /// Initialize Wasm function in form of Box<RefCell<Option<Func<'static, args, rets>>>>.
 /// This macro does not cache result.
 macro_rules! init_wasm_func {
-    ($func:ident, $ctx:expr, $args:ty, $rets:ty, $func_name:ident, $ret_error_code: expr) => {
-        let $func: TypedFunc<WB, $args, $rets> = match { $ctx.get_func($func_name) } {
-            Ok(func) => func,
-            Err(_) => return vec![WValue::I32($ret_error_code)],
-        };
+    ($func:ident, $ctx:expr, $args:ty, $rets:ty, $func_name:ident) => {
+        let $func: TypedFunc<WB, $args, $rets> =
+            $ctx.get_func($func_name)
+                .map_err(|reason| $crate::HostImportError::MissingExport {
+                    func_name: $func_name,
+                    reason,
+                })?;
     };
 }
 
 #[macro_export]
 /// Call Wasm function that have Box<RefCell<Option<Func<'static, args, rets>>>> type.
 macro_rules! call_wasm_func {
-    ($func:expr, $store:expr, $($arg:expr),*) => {
-        $func($store, ($($arg),*)).await.unwrap()
+    ($func:expr, $func_name:ident, $store:expr, $($arg:expr),*) => {
+        $func($store, ($($arg),*))
+            .await
+            .map_err(|reason| $crate::HostImportError::ExportCallFailed {
+                func_name: $func_name,
+                reason,
+            })?
     };
 }
@@ -40,8 +40,10 @@ mod memory_statistic;
 
 pub use crate::marine_core::MModuleInterface;
 pub use config::MarineCoreConfig;
+pub use config::EffectsPolicy;
 pub use config::INFINITE_MEMORY_LIMIT;
 pub use config::HostAPIVersion;
+pub use config::AsyncHostExportedFunc;
 pub use errors::MError;
 pub use host_imports::HostImportError;
 pub use module::IValue;
@@ -54,6 +56,10 @@ pub use module::from_interface_values;
 pub use module::to_interface_value;
 pub use memory_statistic::ModuleMemoryStat;
 pub use memory_statistic::MemoryStats;
+pub use marine_module_info_parser::log_targets::LogTargets;
+pub use marine_module_info_parser::signature::ModuleSigner;
+pub use marine_module_info_parser::effects::EffectKind;
+pub use marine_module_info_parser::effects::WasmEffect;
 
 pub use wasmer_it::IRecordFieldType;
 pub mod ne_vec {
@@ -65,8 +71,10 @@ pub(crate) type MResult<T> = std::result::Result<T, MError>;
 pub mod generic {
     pub use crate::config::MModuleConfig;
     pub use crate::config::HostExportedFunc;
+    pub use crate::config::HostImportFunc;
     pub use crate::config::HostImportDescriptor;
     pub use crate::marine_core::MarineCore;
+    pub use crate::marine_core::MCompiledModule;
 }
 
 #[cfg(feature = "default")]
@@ -75,8 +83,10 @@ pub mod wasmtime {
 
     pub type MModuleConfig = crate::config::MModuleConfig<WasmBackend>;
     pub type HostExportedFunc = crate::config::HostExportedFunc<WasmBackend>;
+    pub type HostImportFunc = crate::config::HostImportFunc<WasmBackend>;
     pub type HostImportDescriptor = crate::config::HostImportDescriptor<WasmBackend>;
     pub type MarineCore = crate::marine_core::MarineCore<WasmBackend>;
+    pub type MCompiledModule = crate::marine_core::MCompiledModule<WasmBackend>;
 }
 
 #[cfg(feature = "default")]
@@ -18,11 +18,16 @@
  */
 
 use super::generic::*;
+use crate::config::EffectsPolicy;
 use crate::config::MarineCoreConfig;
+use crate::misc::PrepareError;
 use crate::module::MModule;
 use crate::module::MRecordTypes;
 use crate::{IRecordType, IValue, MemoryStats, MError, MFunctionSignature, ModuleMemoryStat, MResult};
 
+use marine_module_info_parser::log_targets;
+use marine_module_info_parser::log_targets::LogTargets;
+use marine_module_info_parser::signature::ModuleSigner;
 use marine_wasm_backend_traits::AsContextMut;
 use marine_wasm_backend_traits::Store;
 use marine_wasm_backend_traits::WasiState;
@@ -42,6 +47,43 @@ pub struct MModuleInterface<'a> {
     pub function_signatures: Vec<MFunctionSignature>,
 }
 
+/// A module compiled once, which could be loaded into several Marine instances
+/// sharing the same Wasm backend without compiling it again.
+pub struct MCompiledModule<WB: WasmBackend> {
+    module: <WB as WasmBackend>::Module,
+    /// Signer of the module, if its signature was verified during compilation.
+    signer: Option<ModuleSigner>,
+}
+
+impl<WB: WasmBackend> MCompiledModule<WB> {
+    /// Compiles a module and checks that its SDK and IT versions are supported,
+    /// and that it's signed by one of the trusted signers if they are set.
+    pub fn new(
+        backend: &WB,
+        name: impl AsRef<str>,
+        wasm_bytes: &[u8],
+        trusted_signers: Option<&[ModuleSigner]>,
+    ) -> MResult<Self> {
+        let mut store = <WB as WasmBackend>::Store::new(backend);
+        let (module, signer) =
+            MModule::<WB>::compile(name.as_ref(), &mut store, wasm_bytes, trusted_signers)?;
+
+        Ok(Self { module, signer })
+    }
+
+    /// Returns the verified signer of the module.
+    pub fn signer(&self) -> Option<&ModuleSigner> {
+        self.signer.as_ref()
+    }
+
+    /// Returns logging targets the module declares in its custom section.
+    pub fn log_targets(&self) -> MResult<LogTargets> {
+        let targets = log_targets::extract_from_compiled_module::<WB>(&self.module)
+            .map_err(PrepareError::from)?;
+        Ok(targets)
+    }
+}
+
 /// # Description
 ///
 /// The base struct of Marine, the Fluence compute runtime.
@@ -61,6 +103,10 @@ pub struct MarineCore<WB: WasmBackend> {
     wasm_backend: WB,
     /// Container for all objects created by a Wasm backend.
     store: RefCell<<WB as WasmBackend>::Store>,
+    /// If set, only modules signed by one of these signers could be loaded.
+    trusted_signers: Option<Vec<ModuleSigner>>,
+    /// If set, only modules which effects satisfy it could be loaded.
+    effects_policy: Option<EffectsPolicy>,
 }
 
 impl<WB: WasmBackend> MarineCore<WB> {
@@ -71,6 +117,8 @@ impl<WB: WasmBackend> MarineCore<WB> {
             modules: HashMap::new(),
             wasm_backend: config.wasm_backend,
             store: RefCell::new(store),
+            trusted_signers: config.trusted_signers,
+            effects_policy: config.effects_policy,
         })
     }
 
@@ -105,21 +153,65 @@ impl<WB: WasmBackend> MarineCore<WB> {
         wasm_bytes: &[u8],
         config: MModuleConfig<WB>,
     ) -> MResult<()> {
-        self.load_module_(name.into(), wasm_bytes, config).await
+        let name = name.into();
+        let (module, _) = MModule::<WB>::compile(
+            &name,
+            self.store.get_mut(),
+            wasm_bytes,
+            self.trusted_signers.as_deref(),
+        )?;
+
+        self.load_module_(name, &module, config).await
+    }
+
+    /// Compile a module with the store of this Marine, so it could be loaded later.
+    pub fn compile_module(
+        &mut self,
+        name: impl AsRef<str>,
+        wasm_bytes: &[u8],
+    ) -> MResult<MCompiledModule<WB>> {
+        let (module, signer) = MModule::<WB>::compile(
+            name.as_ref(),
+            self.store.get_mut(),
+            wasm_bytes,
+            self.trusted_signers.as_deref(),
+        )?;
+
+        Ok(MCompiledModule { module, signer })
+    }
+
+    /// Load a new module compiled beforehand inside Marine.
+    /// The module must be compiled with the same Wasm backend this Marine is created with,
+    /// and with the same trusted signers, if they are set.
+    pub async fn load_compiled_module(
+        &mut self,
+        name: impl Into<String>,
+        module: &MCompiledModule<WB>,
+        config: MModuleConfig<WB>,
+    ) -> MResult<()> {
+        let name = name.into();
+        crate::misc::check_signer(
+            &name,
+            module.signer.as_ref(),
+            self.trusted_signers.as_deref(),
+        )?;
+
+        self.load_module_(name, &module.module, config).await
     }
 
     async fn load_module_(
         &mut self,
         name: String,
-        wasm_bytes: &[u8],
+        wasm_module: &<WB as WasmBackend>::Module,
         config: MModuleConfig<WB>,
     ) -> MResult<()> {
         let module = MModule::new(
             &name,
             self.store.get_mut(),
-            wasm_bytes,
+            wasm_module,
             config,
             &self.modules,
+            self.effects_policy.as_ref(),
         )
         .await?;
 
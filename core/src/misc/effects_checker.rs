@@ -0,0 +1,61 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::PrepareResult;
+use super::PrepareError;
+use crate::EffectsPolicy;
+
+use marine_module_info_parser::effects;
+use marine_wasm_backend_traits::Module;
+use marine_wasm_backend_traits::WasmBackend;
+
+use std::collections::BTreeMap;
+
+/// Checks effects of a module derived from its imports against the policy, if it is set.
+pub(crate) fn check_effects<WB: WasmBackend>(
+    name: &str,
+    wasm_module: &<WB as WasmBackend>::Module,
+    effects_policy: Option<&EffectsPolicy>,
+) -> PrepareResult<()> {
+    let effects_policy = match effects_policy {
+        Some(effects_policy) => effects_policy,
+        None => return Ok(()),
+    };
+
+    // effects are sorted by kinds the same way as effects::extract_from_imports does it
+    let mut forbidden_effects = BTreeMap::<_, Vec<_>>::new();
+    for (namespace, import_name) in wasm_module.function_imports() {
+        let effect = effects::effect_of_import(&namespace, &import_name);
+        if !effects_policy.is_allowed(effect.kind()) {
+            forbidden_effects
+                .entry(effect)
+                .or_default()
+                .push((namespace, import_name));
+        }
+    }
+
+    if forbidden_effects.is_empty() {
+        return Ok(());
+    }
+
+    Err(PrepareError::ForbiddenEffects {
+        module_name: name.to_string(),
+        effects: forbidden_effects.into_iter().collect(),
+    })
+}
@@ -17,6 +17,7 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use marine_module_info_parser::effects::WasmEffect;
 use marine_module_info_parser::ModuleInfoError;
 
 use thiserror::Error as ThisError;
@@ -52,4 +53,46 @@ pub enum PrepareError {
         required: semver::Version,
         provided: semver::Version,
     },
+
+    /// Trusted signers are set, but the module isn't signed.
+    #[error("module with name '{0}' isn't signed, but only signed modules are allowed")]
+    UnsignedModule(String),
+
+    /// Module signature is corrupted or doesn't match the module.
+    #[error("module with name '{module_name}' has an invalid signature: {error}")]
+    InvalidModuleSignature {
+        module_name: String,
+        error: ModuleInfoError,
+    },
+
+    /// Module is signed by a key which isn't among the trusted signers.
+    #[error(
+        "module with name '{module_name}' is signed by {signer}, which isn't a trusted signer"
+    )]
+    UntrustedModuleSigner { module_name: String, signer: String },
+
+    /// Module imports functions with effects forbidden by the effects policy.
+    #[error(
+        "module with name '{module_name}' has effects forbidden by the policy: {}",
+        format_effects(.effects)
+    )]
+    ForbiddenEffects {
+        module_name: String,
+        /// Forbidden effects with (namespace, name) pairs of the imports causing them.
+        effects: Vec<(WasmEffect, Vec<(String, String)>)>,
+    },
+}
+
+fn format_effects(effects: &[(WasmEffect, Vec<(String, String)>)]) -> String {
+    effects
+        .iter()
+        .map(|(effect, imports)| {
+            let imports = imports
+                .iter()
+                .map(|(namespace, name)| format!("{}.{}", namespace, name))
+                .collect::<Vec<_>>();
+            format!("{} ({})", effect, imports.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
@@ -17,11 +17,16 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+mod effects_checker;
 mod errors;
+mod signature_checker;
 mod version_checker;
 
+pub(crate) use effects_checker::check_effects;
 pub(crate) use errors::PrepareError;
 pub(crate) use version_checker::check_sdk_version;
 pub(crate) use version_checker::check_it_version;
+pub(crate) use signature_checker::check_signature;
+pub(crate) use signature_checker::check_signer;
 
 type PrepareResult<T> = std::result::Result<T, PrepareError>;
@@ -0,0 +1,76 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::PrepareResult;
+use super::PrepareError;
+
+use marine_module_info_parser::signature;
+use marine_module_info_parser::signature::ModuleSigner;
+use marine_module_info_parser::ModuleInfoError;
+
+/// Verifies the module signature if trusted signers are set, returns the signer.
+/// Without trusted signers, modules are loaded regardless of their signatures.
+pub(crate) fn check_signature(
+    name: &str,
+    wasm_bytes: &[u8],
+    trusted_signers: Option<&[ModuleSigner]>,
+) -> PrepareResult<Option<ModuleSigner>> {
+    let trusted_signers = match trusted_signers {
+        Some(trusted_signers) => trusted_signers,
+        None => return Ok(None),
+    };
+
+    let signer = match signature::verify(wasm_bytes) {
+        Ok(signer) => signer,
+        Err(ModuleInfoError::NoCustomSection(_)) => {
+            return Err(PrepareError::UnsignedModule(name.to_string()))
+        }
+        Err(error) => {
+            return Err(PrepareError::InvalidModuleSignature {
+                module_name: name.to_string(),
+                error,
+            })
+        }
+    };
+
+    check_signer(name, Some(&signer), Some(trusted_signers))?;
+
+    Ok(Some(signer))
+}
+
+/// Checks that a module compiled beforehand is signed by one of the trusted signers.
+pub(crate) fn check_signer(
+    name: &str,
+    signer: Option<&ModuleSigner>,
+    trusted_signers: Option<&[ModuleSigner]>,
+) -> PrepareResult<()> {
+    let trusted_signers = match trusted_signers {
+        Some(trusted_signers) => trusted_signers,
+        None => return Ok(()),
+    };
+
+    match signer {
+        Some(signer) if trusted_signers.contains(signer) => Ok(()),
+        Some(signer) => Err(PrepareError::UntrustedModuleSigner {
+            module_name: name.to_string(),
+            signer: signer.to_string(),
+        }),
+        None => Err(PrepareError::UnsignedModule(name.to_string())),
+    }
+}
@@ -30,10 +30,12 @@ use crate::MResult;
 use crate::generic::MModuleConfig;
 use crate::config::HostAPIVersion;
 use crate::config::RawImportCreator;
+use crate::config::EffectsPolicy;
 
 use marine_wasm_backend_traits::prelude::*;
 
 use marine_it_interfaces::MITInterfaces;
+use marine_module_info_parser::signature::ModuleSigner;
 use marine_it_parser::extract_it_from_module;
 use marine_utils::SharedString;
 use wasmer_it::interpreter::Interpreter;
@@ -104,19 +106,37 @@ pub(crate) struct MModule<WB: WasmBackend> {
 }
 
 impl<WB: WasmBackend> MModule<WB> {
-    pub(crate) async fn new(
+    /// Checks that a module is signed by a trusted signer if they are set, then compiles it
+    /// and checks that its SDK and IT versions are supported. Returns the verified signer.
+    pub(crate) fn compile(
         name: &str,
         store: &mut <WB as WasmBackend>::Store,
         wasm_bytes: &[u8],
-        config: MModuleConfig<WB>,
-        modules: &HashMap<String, MModule<WB>>,
-    ) -> MResult<Self> {
+        trusted_signers: Option<&[ModuleSigner]>,
+    ) -> MResult<(<WB as WasmBackend>::Module, Option<ModuleSigner>)> {
+        // untrusted bytes are never handed to the compiler
+        let signer = crate::misc::check_signature(name, wasm_bytes, trusted_signers)?;
+
         let wasm_module = <WB as WasmBackend>::Module::new(store, wasm_bytes)?;
         crate::misc::check_sdk_version::<WB>(name.to_string(), &wasm_module)?;
 
         let it = extract_it_from_module::<WB>(&wasm_module)?;
         crate::misc::check_it_version(name, &it.version)?;
 
+        Ok((wasm_module, signer))
+    }
+
+    pub(crate) async fn new(
+        name: &str,
+        store: &mut <WB as WasmBackend>::Store,
+        wasm_module: &<WB as WasmBackend>::Module,
+        config: MModuleConfig<WB>,
+        modules: &HashMap<String, MModule<WB>>,
+        effects_policy: Option<&EffectsPolicy>,
+    ) -> MResult<Self> {
+        crate::misc::check_effects::<WB>(name, wasm_module, effects_policy)?;
+
+        let it = extract_it_from_module::<WB>(wasm_module)?;
         let mit = MITInterfaces::new(it);
 
         let mut wit_instance = Arc::new_uninit();
@@ -0,0 +1,142 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine_core::HostAPIVersion;
+use marine_core::HostImportDescriptor;
+use marine_core::HostImportFunc;
+use marine_core::MModuleConfig;
+use marine_core::MarineCore;
+use marine_core::MarineCoreConfig;
+use marine_core::IType;
+use marine_core::IValue;
+use marine_wasm_backend_traits::WasmBackend;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+
+use once_cell::sync::Lazy;
+
+use std::collections::HashMap;
+
+static MULTI_VALUE_WASM_BYTES: Lazy<Vec<u8>> = Lazy::new(|| {
+    let wasm = wat::parse_file("./tests/wasm_tests/multi_value/multi_value.wat")
+        .expect("./tests/wasm_tests/multi_value/multi_value.wat should be a valid wat");
+    let it = std::fs::read_to_string("./tests/wasm_tests/multi_value/multi_value.it")
+        .expect("./tests/wasm_tests/multi_value/multi_value.it should presence");
+
+    let module = walrus::ModuleConfig::new()
+        .parse(&wasm)
+        .expect("wasm should be parsed by walrus");
+    let buffer = wasmer_it::decoders::wat::Buffer::new(&it).expect("it should be valid");
+    let interfaces = wasmer_it::decoders::wat::parse(&buffer).expect("it should be valid");
+    let module = marine_it_parser::embed_it(module, &interfaces);
+
+    let sdk_version = marine_min_it_version::min_sdk_version();
+    let mut module = marine_module_info_parser::sdk_version::embed_from_module(module, sdk_version);
+
+    module.emit_wasm()
+});
+
+fn host_import(output_types: Vec<IType>, outputs: Vec<IValue>) -> HostImportDescriptor {
+    HostImportDescriptor {
+        host_exported_func: HostImportFunc::Sync(Box::new(move |_, _| Ok(outputs.clone()))),
+        argument_types: vec![],
+        output_types,
+        error_handler: None,
+    }
+}
+
+async fn load_multi_value_module() -> MarineCore {
+    let host_imports = HashMap::from([
+        (
+            "primitives".to_string(),
+            host_import(
+                vec![IType::I32, IType::I64],
+                vec![IValue::I32(1), IValue::I64(2)],
+            ),
+        ),
+        (
+            "string_and_u32".to_string(),
+            host_import(
+                vec![IType::String, IType::U32],
+                vec![IValue::String(String::from("Fluence")), IValue::U32(3)],
+            ),
+        ),
+    ]);
+    let config = MModuleConfig {
+        host_imports: HashMap::from([(HostAPIVersion::V0, host_imports)]),
+        ..<_>::default()
+    };
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let mut marine_core = MarineCore::new(MarineCoreConfig::new(backend, None)).unwrap();
+    marine_core
+        .load_module("multi_value", &MULTI_VALUE_WASM_BYTES, config)
+        .await
+        .unwrap_or_else(|e| panic!("can't load a module into Marine: {:?}", e));
+
+    marine_core
+}
+
+#[tokio::test]
+pub async fn export_with_several_results() {
+    let mut marine_core = load_multi_value_module().await;
+
+    let result = marine_core
+        .call_async("multi_value", "pair", &[])
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke pair: {:?}", e));
+
+    assert_eq!(result, vec![IValue::I32(42), IValue::I64(1337)]);
+
+    let interface = marine_core
+        .module_interface("multi_value")
+        .expect("module should be loaded");
+    let signature = interface
+        .function_signatures
+        .iter()
+        .find(|signature| signature.name.as_str() == "pair")
+        .expect("pair should be exported");
+    assert_eq!(signature.outputs.as_slice(), &[IType::I32, IType::I64]);
+}
+
+#[tokio::test]
+pub async fn host_import_with_several_primitive_results() {
+    let mut marine_core = load_multi_value_module().await;
+
+    let result = marine_core
+        .call_async("multi_value", "host_primitives", &[])
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke host_primitives: {:?}", e));
+
+    assert_eq!(result, vec![IValue::I32(1), IValue::I64(2)]);
+}
+
+#[tokio::test]
+pub async fn host_import_with_several_results() {
+    let mut marine_core = load_multi_value_module().await;
+
+    let result = marine_core
+        .call_async("multi_value", "host_string_and_u32", &[])
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke host_string_and_u32: {:?}", e));
+
+    assert_eq!(
+        result,
+        vec![IValue::I32(3), IValue::String(String::from("Fluence"))]
+    );
+}
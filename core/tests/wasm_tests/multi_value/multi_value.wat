@@ -0,0 +1,40 @@
+;; A module that returns several values from its exports and receives several values
+;; from host imports, marine-rs-sdk can't produce such functions yet.
+(module
+  (import "host" "primitives" (func $primitives (result i32 i64)))
+  (import "host" "string_and_u32" (func $string_and_u32 (result i32 i32 i32)))
+
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+
+  ;; a bump allocator is enough for tests
+  (func (export "allocate") (param $size i32) (param $type_tag i32) (result i32)
+    global.get $heap
+    global.get $heap
+    local.get $size
+    i32.add
+    global.set $heap)
+
+  (func (export "release_objects"))
+  (func (export "get_result_size") (result i32) i32.const 0)
+  (func (export "get_result_ptr") (result i32) i32.const 0)
+  (func (export "set_result_size") (param i32))
+  (func (export "set_result_ptr") (param i32))
+
+  (func (export "pair") (result i32 i64)
+    i32.const 42
+    i64.const 1337)
+
+  (func (export "host_primitives") (result i32 i64)
+    call $primitives)
+
+  ;; reorders results so that the string offset and size are on the top of the IT stack
+  (func (export "host_string_and_u32") (result i32 i32 i32)
+    (local $offset i32) (local $size i32) (local $value i32)
+    call $string_and_u32
+    local.set $value
+    local.set $size
+    local.set $offset
+    local.get $value
+    local.get $offset
+    local.get $size))
@@ -19,17 +19,24 @@
 
 use crate::generic::AppService;
 use crate::generic::AppServiceConfig;
+use crate::generic::AppServicePool;
+use crate::AppServicePoolConfig;
 use crate::AppServiceError;
 
+use marine::generic::HostImportProviders;
+use marine::HostImportProvider;
 use marine_wasm_backend_traits::WasmBackend;
 use marine_wasmtime_backend::WasmtimeConfig;
 use marine_wasmtime_backend::WasmtimeWasmBackend;
 
 use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppServiceFactory<WB: WasmBackend> {
     backend: WB,
+    host_import_providers: HostImportProviders<WB>,
 }
 
 #[derive(Clone)]
@@ -45,6 +52,7 @@ impl<WB: WasmBackend> AppServiceFactory<WB> {
     where
         S: Into<String>,
     {
+        let config = self.with_host_import_providers(config);
         AppService::new_with_backend(self.backend.clone(), config, service_id, envs).await
     }
 
@@ -58,12 +66,74 @@ impl<WB: WasmBackend> AppServiceFactory<WB> {
     where
         S: Into<String>,
     {
+        let config = self.with_host_import_providers(config);
         AppService::new_with_empty_facade(self.backend.clone(), config, service_id, envs).await
     }
 
+    /// Creates a pool of instances of the same service, its modules are compiled only once.
+    pub async fn new_app_service_pool<C, S>(
+        &self,
+        config: C,
+        service_id: S,
+        envs: HashMap<String, String>,
+        pool_config: AppServicePoolConfig,
+    ) -> crate::Result<AppServicePool<WB>>
+    where
+        C: TryInto<AppServiceConfig<WB>> + Clone + Send + Sync + 'static,
+        S: Into<String>,
+        AppServiceError: From<C::Error>,
+    {
+        let host_import_providers = self.host_import_providers.clone();
+        let config_factory = Box::new(move || {
+            let config = config.clone().try_into()?;
+            Ok(add_host_import_providers(&host_import_providers, config))
+        });
+
+        AppServicePool::with_config_factory(
+            self.backend.clone(),
+            config_factory,
+            service_id,
+            envs,
+            pool_config,
+        )
+        .await
+    }
+
+    /// Registers a host import provider, services created by this factory
+    /// could enable it by the name with `host_imports = ["name"]` in their module configs.
+    pub fn register_host_import_provider<S>(
+        &mut self,
+        name: S,
+        provider: impl HostImportProvider<WB> + 'static,
+    ) where
+        S: Into<String>,
+    {
+        self.host_import_providers
+            .insert(name.into(), Arc::new(provider));
+    }
+
     pub fn backend(&self) -> WB {
         self.backend.clone()
     }
+
+    fn with_host_import_providers(&self, config: AppServiceConfig<WB>) -> AppServiceConfig<WB> {
+        add_host_import_providers(&self.host_import_providers, config)
+    }
+}
+
+fn add_host_import_providers<WB: WasmBackend>(
+    providers: &HostImportProviders<WB>,
+    mut config: AppServiceConfig<WB>,
+) -> AppServiceConfig<WB> {
+    for (name, provider) in providers {
+        config
+            .marine_config
+            .host_import_providers
+            .entry(name.clone())
+            .or_insert_with(|| provider.clone());
+    }
+
+    config
 }
 
 impl AppServiceFactory<WasmtimeWasmBackend> {
@@ -76,7 +146,10 @@ impl AppServiceFactory<WasmtimeWasmBackend> {
             WasmtimeWasmBackend::new(config).map_err(AppServiceError::WasmBackendError)?;
 
         let ticker = EpochTicker(backend.clone());
-        let factory = Self { backend };
+        let factory = Self {
+            backend,
+            host_import_providers: HashMap::new(),
+        };
         Ok((factory, ticker))
     }
 }
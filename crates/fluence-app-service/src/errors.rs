@@ -19,6 +19,7 @@
 
 use marine_wasm_backend_traits::WasmBackendError;
 use marine::MarineError;
+use marine_service_bundle::BundleError;
 
 use std::io::Error as IOError;
 use std::error::Error;
@@ -46,6 +47,15 @@ pub enum AppServiceError {
 
     /// Errors related to malformed config.
     ConfigParseError(String),
+
+    /// A service bundle is malformed or its files don't match their hashes.
+    BundleError(BundleError),
+
+    /// A call to a service pool was rejected because too many calls are already waiting
+    /// for a free instance.
+    PoolQueueIsFull {
+        max_queue_len: usize,
+    },
 }
 
 impl Error for AppServiceError {}
@@ -63,6 +73,12 @@ impl std::fmt::Display for AppServiceError {
             AppServiceError::WasmBackendError(err) => {
                 write!(f, "{}", err)
             }
+            AppServiceError::BundleError(err) => write!(f, "{}", err),
+            AppServiceError::PoolQueueIsFull { max_queue_len } => write!(
+                f,
+                "service pool queue is full: {} calls are already waiting for a free instance",
+                max_queue_len
+            ),
         }
     }
 }
@@ -73,6 +89,12 @@ impl From<MarineError> for AppServiceError {
     }
 }
 
+impl From<BundleError> for AppServiceError {
+    fn from(err: BundleError) -> Self {
+        AppServiceError::BundleError(err)
+    }
+}
+
 impl From<IOError> for AppServiceError {
     fn from(err: IOError) -> Self {
         AppServiceError::IOError(err)
@@ -30,6 +30,7 @@
 mod config;
 mod errors;
 mod service;
+mod service_pool;
 mod service_interface;
 mod raw_toml_config;
 mod app_service_factory;
@@ -40,9 +41,16 @@ pub use errors::AppServiceError;
 pub use service_interface::FunctionSignature;
 pub use service_interface::RecordType;
 pub use service_interface::ServiceInterface;
+pub use marine_module_interface::json_schema::FunctionSchema;
+pub use service_pool::AppServicePoolConfig;
+pub use service_pool::PoolInstanceState;
 
 pub use raw_toml_config::TomlAppServiceConfig;
 
+pub use marine_service_bundle::ServiceBundle;
+pub use marine_service_bundle::BundleError;
+pub use marine_service_bundle::BundleManifest;
+
 pub use marine::ConfigContext;
 pub use marine::WithContext;
 pub use marine::TomlMarineConfig;
@@ -51,6 +59,7 @@ pub use marine::TomlMarineNamedModuleConfig;
 pub use marine::TomlValue;
 pub use marine::TomlValueTable;
 pub use marine::TomlWASIConfig;
+pub use marine::TomlLoggerLimits;
 
 pub use marine::MarineError;
 pub use marine::MError;
@@ -60,11 +69,18 @@ pub use marine::IRecordType;
 pub use marine::IFunctionArg;
 pub use marine::IType;
 pub use marine::HostImportError;
+pub use marine::HostImportContext;
+pub use marine::HostImportProvider;
 pub use marine::to_interface_value;
 pub use marine::from_interface_values;
+pub use marine::ModuleLogRecord;
+pub use marine::LogSuppressionStats;
 pub use marine::ModuleMemoryStat;
 pub use marine::MemoryStats;
 pub use marine::ne_vec;
+pub use marine::BytesRepresentation;
+pub use marine::Int64Representation;
+pub use marine::JsonOptions;
 
 pub use marine_min_it_version::min_sdk_version;
 pub use marine_min_it_version::min_it_version;
@@ -75,6 +91,7 @@ pub use marine::SecurityTetraplet;
 
 pub mod generic {
     pub use crate::service::AppService;
+    pub use crate::service_pool::AppServicePool;
     pub use crate::app_service_factory::AppServiceFactory;
     pub use crate::config::AppServiceConfig;
 
@@ -82,6 +99,8 @@ pub mod generic {
     pub use marine::generic::MarineModuleConfig;
     pub use marine::generic::ModuleDescriptor;
     pub use marine::generic::HostImportDescriptor;
+    pub use marine::generic::HostImportFunc;
+    pub use marine::generic::HostImportProviders;
 }
 
 #[cfg(feature = "wasmtime")]
@@ -91,6 +110,7 @@ pub mod wasmtime {
     pub use marine_wasmtime_backend::WasmtimeConfig;
 
     pub type AppService = crate::service::AppService<WasmBackend>;
+    pub type AppServicePool = crate::service_pool::AppServicePool<WasmBackend>;
     pub type AppServiceFactory = crate::app_service_factory::AppServiceFactory<WasmBackend>;
     pub type AppServiceConfig = crate::config::AppServiceConfig<WasmBackend>;
     pub use crate::app_service_factory::EpochTicker;
@@ -100,6 +120,8 @@ pub mod wasmtime {
     pub use marine::MarineWASIConfig;
     pub use marine::ModuleDescriptor;
     pub use marine::HostImportDescriptor;
+    pub use marine::HostImportFunc;
+    pub use marine::HostImportProviders;
 }
 
 #[cfg(feature = "wasmtime")]
@@ -21,6 +21,7 @@ use crate::Result;
 use crate::AppServiceError;
 use crate::config::AppServiceConfig;
 
+use marine::resolve_toml_config;
 use marine::TomlMarineConfig;
 use marine_wasm_backend_traits::WasmBackend;
 
@@ -39,11 +40,12 @@ pub struct TomlAppServiceConfig {
 }
 
 impl TomlAppServiceConfig {
-    /// Load config from filesystem.
+    /// Load config from filesystem, resolving includes and, where it's enabled,
+    /// environment variables references.
     pub fn load<P: Into<PathBuf>>(path: P) -> Result<Self> {
         let path = path.into();
-        let file_content = std::fs::read(&path)?;
-        toml::from_slice(&file_content).map_err(|e| {
+        let config = resolve_toml_config(&path)?;
+        toml::Value::Table(config).try_into().map_err(|e| {
             AppServiceError::ConfigParseError(format!("Error parsing config {:?}: {:?}", path, e))
         })
     }
@@ -28,15 +28,22 @@ use marine_wasm_backend_traits::WasiState;
 use marine_wasm_backend_traits::WasmBackend;
 use marine::generic::Marine;
 use marine::generic::MarineModuleConfig;
+use marine::generic::MCompiledModule;
 use marine::MarineError;
 use marine::MError;
 use marine::IValue;
+use marine::LogSuppressionStats;
+use marine::ModuleLogRecord;
+use marine_module_interface::json_schema::FunctionSchema;
+use marine_service_bundle::BundleData;
+use marine_service_bundle::ServiceBundle;
 
 use serde_json::Value as JValue;
 
 use std::convert::TryInto;
 use std::collections::HashMap;
 use std::path::Path;
+use std::path::PathBuf;
 use std::io::ErrorKind;
 
 const SERVICE_ID_ENV_NAME: &str = "service_id";
@@ -72,17 +79,7 @@ impl<WB: WasmBackend> AppService<WB> {
         AppServiceError: From<C::Error>,
     {
         let mut config: AppServiceConfig<WB> = config.try_into()?;
-        let facade_module_name = config
-            .marine_config
-            .modules_config
-            .last()
-            .ok_or_else(|| {
-                AppServiceError::ConfigParseError(String::from(
-                    "config should contain at least one module",
-                ))
-            })?
-            .import_name
-            .clone();
+        let facade_module_name = Self::facade_module_name(&config)?;
 
         let service_id = service_id.into();
         Self::set_env_and_dirs(&mut config, service_id, envs)?;
@@ -95,6 +92,77 @@ impl<WB: WasmBackend> AppService<WB> {
         })
     }
 
+    /// Create Service from a bundle, its modules are loaded from the bundle itself,
+    /// and its initial data is written into the service working dir if it isn't there yet.
+    /// The working dir is chosen by the host, since bundles don't depend on a filesystem layout.
+    pub async fn from_bundle<P, S>(
+        bundle: ServiceBundle,
+        service_working_dir: P,
+        service_id: S,
+        envs: HashMap<String, String>,
+    ) -> Result<Self>
+    where
+        P: Into<PathBuf>,
+        S: Into<String>,
+    {
+        let backend = <WB as WasmBackend>::new_async()
+            .map_err(|e| MarineError::EngineError(MError::WasmBackendError(e)))?;
+
+        Self::from_bundle_with_backend(backend, bundle, service_working_dir, service_id, envs).await
+    }
+
+    pub async fn from_bundle_with_backend<P, S>(
+        backend: WB,
+        bundle: ServiceBundle,
+        service_working_dir: P,
+        service_id: S,
+        envs: HashMap<String, String>,
+    ) -> Result<Self>
+    where
+        P: Into<PathBuf>,
+        S: Into<String>,
+    {
+        bundle.verify()?;
+        let (config, modules, data) = bundle.into_parts();
+
+        let mut config: crate::TomlAppServiceConfig = toml::Value::Table(config).try_into()?;
+        // bundles created by older versions could still contain the working dir of their creator
+        let service_working_dir = service_working_dir.into();
+        config.service_working_dir = Some(service_working_dir.to_string_lossy().into_owned());
+        let mut config: AppServiceConfig<WB> = config.try_into()?;
+        let facade_module_name = Self::facade_module_name(&config)?;
+
+        write_initial_data(&service_working_dir, data)?;
+        Self::set_env_and_dirs(&mut config, service_id.into(), envs)?;
+
+        let marine = Marine::with_modules(backend, modules, config.marine_config).await?;
+
+        Ok(Self {
+            marine,
+            facade_module_name,
+        })
+    }
+
+    /// Create Service from modules already compiled by [`Marine::compile_modules`],
+    /// used to spawn several instances of the same service without recompiling it.
+    pub(crate) async fn new_with_compiled_modules(
+        backend: WB,
+        modules: &HashMap<String, MCompiledModule<WB>>,
+        mut config: AppServiceConfig<WB>,
+        service_id: String,
+        envs: HashMap<String, String>,
+    ) -> Result<Self> {
+        let facade_module_name = Self::facade_module_name(&config)?;
+        Self::set_env_and_dirs(&mut config, service_id, envs)?;
+
+        let marine = Marine::with_compiled_modules(backend, modules, config.marine_config).await?;
+
+        Ok(Self {
+            marine,
+            facade_module_name,
+        })
+    }
+
     /// Call a specified function of loaded module by its name with arguments in json format.
     pub async fn call_async(
         &mut self,
@@ -113,6 +181,86 @@ impl<WB: WasmBackend> AppService<WB> {
             .map_err(Into::into)
     }
 
+    /// Call a specified function like [`Self::call_async`], with byte arrays and 64-bit integers
+    /// of arguments and results represented according to the supplied options.
+    pub async fn call_with_json_options_async(
+        &mut self,
+        func_name: impl AsRef<str>,
+        arguments: JValue,
+        json_options: &crate::JsonOptions,
+        call_parameters: crate::CallParameters,
+    ) -> Result<JValue> {
+        self.marine
+            .call_with_json_options_async(
+                &self.facade_module_name,
+                func_name,
+                arguments,
+                json_options,
+                call_parameters,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Call a specified function like [`Self::call_async`] and return the log records
+    /// emitted by all modules of this service during the call next to its result.
+    pub async fn call_capturing_logs_async(
+        &mut self,
+        func_name: impl AsRef<str>,
+        arguments: JValue,
+        call_parameters: crate::CallParameters,
+    ) -> (Result<JValue>, Vec<ModuleLogRecord>) {
+        let (result, logs) = self
+            .marine
+            .call_with_json_capturing_logs_async(
+                &self.facade_module_name,
+                func_name,
+                arguments,
+                call_parameters,
+            )
+            .await;
+
+        (result.map_err(Into::into), logs)
+    }
+
+    /// Call a specified function of loaded module by its name with arguments in MessagePack
+    /// format, results are returned in MessagePack as well.
+    pub async fn call_with_msgpack_async(
+        &mut self,
+        func_name: impl AsRef<str>,
+        arguments: &[u8],
+        call_parameters: crate::CallParameters,
+    ) -> Result<Vec<u8>> {
+        self.marine
+            .call_with_msgpack_async(
+                &self.facade_module_name,
+                func_name,
+                arguments,
+                call_parameters,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Call a specified function of loaded module by its name with arguments in CBOR
+    /// format, results are returned in CBOR as well.
+    pub async fn call_with_cbor_async(
+        &mut self,
+        func_name: impl AsRef<str>,
+        arguments: &[u8],
+        call_parameters: crate::CallParameters,
+    ) -> Result<Vec<u8>> {
+        self.marine
+            .call_with_cbor_async(
+                &self.facade_module_name,
+                func_name,
+                arguments,
+                call_parameters,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
     /// Call a specified function of loaded module by its name with arguments in IValue format.
     pub async fn call_with_ivalues_async(
         &mut self,
@@ -146,8 +294,50 @@ impl<WB: WasmBackend> AppService<WB> {
         into_service_interface(marine_facade_interface)
     }
 
+    /// Return JSON Schemas of arguments and results of all functions of this service.
+    pub fn get_json_schema(&self) -> Vec<FunctionSchema> {
+        use marine_module_interface::json_schema::function_json_schema;
+
+        let marine_facade_interface = self
+            .marine
+            .get_interface()
+            .modules
+            .remove(self.facade_module_name.as_str())
+            // facade module must be loaded into FaaS, so unwrap is safe here
+            .unwrap();
+
+        marine_facade_interface
+            .function_signatures
+            .iter()
+            .map(|signature| {
+                function_json_schema(
+                    &signature.name,
+                    &signature.arguments,
+                    &signature.outputs,
+                    marine_facade_interface.record_types,
+                )
+                // record types of a loaded module are checked while loading, so unwrap is safe here
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn facade_module_name(config: &AppServiceConfig<WB>) -> Result<String> {
+        config
+            .marine_config
+            .modules_config
+            .last()
+            .map(|module| module.import_name.clone())
+            .ok_or_else(|| {
+                AppServiceError::ConfigParseError(String::from(
+                    "config should contain at least one module",
+                ))
+            })
+    }
+
     /// Prepare service before starting by:
-    ///  1. rooting all mapped directories at service_working_dir, keeping absolute paths as-is
+    ///  1. rooting all mapped directories and working dirs of mounted binaries at service_working_dir,
+    ///     keeping absolute paths as-is
     ///  2. adding service_id to environment variables
     fn set_env_and_dirs(
         config: &mut AppServiceConfig<WB>,
@@ -162,6 +352,8 @@ impl<WB: WasmBackend> AppService<WB> {
             module.config.extend_wasi_envs(envs.clone());
             // Moves relative paths in mapped dirs to the &working dir, keeping old aliases.
             module.config.root_wasi_files_at(working_dir);
+            // Mounted binaries with relative working dirs are run inside the service working dir.
+            module.config.root_mounted_binaries_at(working_dir);
 
             // Create all mapped directories if they do not exist
             // Needed to provide ability to run the same services both in mrepl and rust-peer
@@ -170,6 +362,29 @@ impl<WB: WasmBackend> AppService<WB> {
         Ok(())
     }
 
+    /// Change log directives of all modules of this service, see [`Marine::set_log_directives`].
+    /// Levels could be raised at runtime too, except for modules with WASM_LOG set explicitly
+    /// in their wasi config, which never log more verbosely than that level.
+    pub fn set_log_directives(&mut self, log_directives: Option<&str>) {
+        self.marine.set_log_directives(log_directives)
+    }
+
+    /// Change the mask of logging targets enabled for a module of this service.
+    pub fn set_logging_mask(
+        &mut self,
+        module_name: impl AsRef<str>,
+        logging_mask: i32,
+    ) -> Result<()> {
+        self.marine
+            .set_logging_mask(module_name, logging_mask)
+            .map_err(Into::into)
+    }
+
+    /// Return numbers of log messages dropped or truncated because of logger limits by module names.
+    pub fn log_suppression_stats(&self) -> HashMap<String, LogSuppressionStats> {
+        self.marine.log_suppression_stats()
+    }
+
     /// Return statistics of Wasm modules heap footprint.
     /// This operation is cheap.
     pub fn module_memory_stats(&self) -> MemoryStats<'_> {
@@ -253,6 +468,24 @@ impl<WB: WasmBackend> AppService<WB> {
     }
 }
 
+/// Writes files of a bundle into the working dir, keeping existing ones,
+/// so that the data changed by a service survives its restarts.
+fn write_initial_data(working_dir: &Path, data: BundleData) -> Result<()> {
+    for (relative_path, content) in data {
+        let path = working_dir.join(relative_path);
+        if path.exists() {
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            create(parent)?;
+        }
+        std::fs::write(&path, content)?;
+    }
+
+    Ok(())
+}
+
 fn create_wasi_dirs<WB: WasmBackend>(config: &MarineModuleConfig<WB>) -> Result<()> {
     if let Some(wasi_config) = &config.wasi {
         for dir in wasi_config.mapped_dirs.values() {
@@ -273,3 +506,63 @@ fn create(dir: &Path) -> Result<()> {
         _ => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::wasmtime::AppService;
+    use crate::ServiceBundle;
+
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn from_bundle() {
+        let dir = std::env::temp_dir().join(format!("app_service_bundle_{}", std::process::id()));
+        let working_dir = dir.join("working_dir");
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        std::fs::write(dir.join("data/state"), "initial").unwrap();
+        let modules_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../examples/greeting/artifacts");
+        let config = format!(
+            r#"
+            modules_dir = {:?}
+            service_working_dir = "creator_working_dir"
+            total_memory_limit = "Infinity"
+
+            [[module]]
+            name = "greeting"
+            "#,
+            modules_dir.display().to_string(),
+        );
+        std::fs::write(dir.join("Config.toml"), config).unwrap();
+
+        let bundle =
+            ServiceBundle::create(&dir.join("Config.toml"), Some(&dir.join("data"))).unwrap();
+        let bundle = ServiceBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+        assert!(bundle.config().get("service_working_dir").is_none());
+
+        let mut service =
+            AppService::from_bundle(bundle.clone(), &working_dir, "bundle", HashMap::new())
+                .await
+                .unwrap_or_else(|e| panic!("can't create service from bundle: {}", e));
+        let result = service
+            .call_async("greeting", serde_json::json!(["bundle"]), <_>::default())
+            .await;
+        assert_eq!(result.unwrap(), serde_json::json!("Hi, bundle"));
+        assert_eq!(
+            std::fs::read(working_dir.join("state")).unwrap(),
+            b"initial"
+        );
+
+        // data changed by a service isn't overwritten on restart
+        std::fs::write(working_dir.join("state"), "changed").unwrap();
+        AppService::from_bundle(bundle, &working_dir, "bundle", HashMap::new())
+            .await
+            .unwrap_or_else(|e| panic!("can't restart service from bundle: {}", e));
+        assert_eq!(
+            std::fs::read(working_dir.join("state")).unwrap(),
+            b"changed"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,484 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::Result;
+use crate::config::AppServiceConfig;
+use crate::service::AppService;
+use crate::service_interface::ServiceInterface;
+use crate::AppServiceError;
+
+use marine_wasm_backend_traits::WasmBackend;
+use marine::generic::Marine;
+use marine::generic::MCompiledModule;
+use marine::IValue;
+
+use serde_json::Value as JValue;
+use tokio::sync::Semaphore;
+use tokio::sync::SemaphorePermit;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+pub(crate) type ConfigFactory<WB> =
+    Box<dyn Fn() -> Result<AppServiceConfig<WB>> + Send + Sync + 'static>;
+
+/// Defines what a call to a pool could observe from the previous calls.
+///
+/// Whatever is chosen, all instances of a pool share the same service id, environment
+/// and working dir, so files written into mapped dirs are visible to every instance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PoolInstanceState {
+    /// Instances are kept between calls, so a call could observe a memory state
+    /// left by any previous call dispatched to the same instance, and it's unspecified
+    /// which instance serves a call. Suits only services keeping no state in memory.
+    #[default]
+    Stateless,
+
+    /// An instance is thrown away after each call and recreated from the already compiled
+    /// modules before the next one, so calls never observe a memory state of each other.
+    ResetAfterCall,
+}
+
+#[derive(Clone, Debug)]
+pub struct AppServicePoolConfig {
+    /// Number of service instances, each of them serves one call at a time.
+    pub size: usize,
+
+    /// Max number of calls waiting for a free instance, calls beyond it are rejected
+    /// with [`AppServiceError::PoolQueueIsFull`]. Unlimited if not set.
+    pub max_queue_len: Option<usize>,
+
+    pub instance_state: PoolInstanceState,
+}
+
+/// Several isolated instances of the same service created from modules compiled once.
+/// Calls are dispatched to free instances, waiting ones are served in FIFO order.
+pub struct AppServicePool<WB: WasmBackend> {
+    /// Free instances, None marks a slot whose instance has to be created before use.
+    instances: Mutex<Vec<Option<AppService<WB>>>>,
+    free_instances: Semaphore,
+    waiting_calls: AtomicUsize,
+    factory: InstanceFactory<WB>,
+    interface: ServiceInterface,
+    config: AppServicePoolConfig,
+}
+
+struct InstanceFactory<WB: WasmBackend> {
+    backend: WB,
+    modules: HashMap<String, MCompiledModule<WB>>,
+    config_factory: ConfigFactory<WB>,
+    service_id: String,
+    envs: HashMap<String, String>,
+}
+
+/// An instance taken from a pool, it's returned back on drop.
+struct PooledInstance<'pool, WB: WasmBackend> {
+    pool: &'pool AppServicePool<WB>,
+    instance: Option<AppService<WB>>,
+    /// Set only after a call completed, so an instance dropped in the middle of a call
+    /// (e.g. the call future was cancelled) is never reused.
+    completed: bool,
+    _permit: SemaphorePermit<'pool>,
+}
+
+/// Tracks a call waiting for a free instance, works correctly with cancellation.
+struct WaitingCall<'pool>(&'pool AtomicUsize);
+
+impl<WB: WasmBackend> AppServicePool<WB> {
+    /// Create a pool of service instances, modules are compiled only once
+    /// and config is converted anew for each instance.
+    pub async fn new<C, S>(
+        backend: WB,
+        config: C,
+        service_id: S,
+        envs: HashMap<String, String>,
+        pool_config: AppServicePoolConfig,
+    ) -> Result<Self>
+    where
+        C: TryInto<AppServiceConfig<WB>> + Clone + Send + Sync + 'static,
+        S: Into<String>,
+        AppServiceError: From<C::Error>,
+    {
+        let config_factory = Box::new(move || config.clone().try_into().map_err(Into::into));
+        Self::with_config_factory(backend, config_factory, service_id, envs, pool_config).await
+    }
+
+    pub(crate) async fn with_config_factory<S>(
+        backend: WB,
+        config_factory: ConfigFactory<WB>,
+        service_id: S,
+        envs: HashMap<String, String>,
+        pool_config: AppServicePoolConfig,
+    ) -> Result<Self>
+    where
+        S: Into<String>,
+    {
+        if pool_config.size == 0 {
+            return Err(AppServiceError::InvalidConfig(String::from(
+                "service pool should contain at least one instance",
+            )));
+        }
+
+        let config = config_factory()?;
+        let modules = Marine::compile_modules(&backend, &config.marine_config)?;
+        let factory = InstanceFactory {
+            backend,
+            modules,
+            config_factory,
+            service_id: service_id.into(),
+            envs,
+        };
+
+        let mut instances = Vec::with_capacity(pool_config.size);
+        instances.push(Some(factory.create_with(config).await?));
+        for _ in 1..pool_config.size {
+            instances.push(Some(factory.create().await?));
+        }
+        let interface = instances[0].as_ref().unwrap().get_interface();
+
+        Ok(Self {
+            instances: Mutex::new(instances),
+            free_instances: Semaphore::new(pool_config.size),
+            waiting_calls: AtomicUsize::new(0),
+            factory,
+            interface,
+            config: pool_config,
+        })
+    }
+
+    /// Call a specified function of a free instance by its name with arguments in json format.
+    pub async fn call_async(
+        &self,
+        func_name: impl AsRef<str>,
+        arguments: JValue,
+        call_parameters: crate::CallParameters,
+    ) -> Result<JValue> {
+        let mut pooled = self.acquire().await?;
+        let result = pooled
+            .instance()
+            .call_async(func_name, arguments, call_parameters)
+            .await;
+        pooled.completed = true;
+
+        result
+    }
+
+    /// Call a specified function of a free instance by its name with arguments in IValue format.
+    pub async fn call_with_ivalues_async(
+        &self,
+        func_name: impl AsRef<str>,
+        arguments: &[IValue],
+        call_parameters: crate::CallParameters,
+    ) -> Result<Vec<IValue>> {
+        let mut pooled = self.acquire().await?;
+        let result = pooled
+            .instance()
+            .call_with_ivalues_async(func_name, arguments, call_parameters)
+            .await;
+        pooled.completed = true;
+
+        result
+    }
+
+    /// Return interface (function signatures and record types) of the pooled service.
+    pub fn get_interface(&self) -> &ServiceInterface {
+        &self.interface
+    }
+
+    /// Return number of instances in this pool.
+    pub fn size(&self) -> usize {
+        self.config.size
+    }
+
+    /// Return number of calls currently waiting for a free instance.
+    pub fn waiting_calls(&self) -> usize {
+        self.waiting_calls.load(Ordering::Acquire)
+    }
+
+    async fn acquire(&self) -> Result<PooledInstance<'_, WB>> {
+        let permit = match self.free_instances.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => self.wait_for_instance().await?,
+        };
+
+        let slot = self
+            .lock_instances()
+            .pop()
+            .expect("a permit guarantees that there is a free instance");
+
+        let mut pooled = PooledInstance {
+            pool: self,
+            instance: None,
+            completed: false,
+            _permit: permit,
+        };
+        // if creation fails, the slot is returned back empty and will be retried by the next call
+        let instance = match slot {
+            Some(instance) => instance,
+            None => self.factory.create().await?,
+        };
+        pooled.instance = Some(instance);
+
+        Ok(pooled)
+    }
+
+    async fn wait_for_instance(&self) -> Result<SemaphorePermit<'_>> {
+        let waiting = WaitingCall::new(&self.waiting_calls);
+        if let Some(max_queue_len) = self.config.max_queue_len {
+            if waiting.position() >= max_queue_len {
+                return Err(AppServiceError::PoolQueueIsFull { max_queue_len });
+            }
+        }
+
+        let permit = self
+            .free_instances
+            .acquire()
+            .await
+            .expect("pool semaphore is never closed");
+
+        Ok(permit)
+    }
+
+    fn lock_instances(&self) -> MutexGuard<'_, Vec<Option<AppService<WB>>>> {
+        // instances are only pushed and popped under the lock, so they are consistent even if poisoned
+        self.instances
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<WB: WasmBackend> InstanceFactory<WB> {
+    async fn create(&self) -> Result<AppService<WB>> {
+        let config = (self.config_factory)()?;
+        self.create_with(config).await
+    }
+
+    async fn create_with(&self, config: AppServiceConfig<WB>) -> Result<AppService<WB>> {
+        AppService::new_with_compiled_modules(
+            self.backend.clone(),
+            &self.modules,
+            config,
+            self.service_id.clone(),
+            self.envs.clone(),
+        )
+        .await
+    }
+}
+
+impl<WB: WasmBackend> PooledInstance<'_, WB> {
+    fn instance(&mut self) -> &mut AppService<WB> {
+        // instance is set right after creation of PooledInstance and taken only on drop
+        self.instance.as_mut().unwrap()
+    }
+}
+
+impl<WB: WasmBackend> Drop for PooledInstance<'_, WB> {
+    fn drop(&mut self) {
+        let reusable =
+            self.completed && self.pool.config.instance_state == PoolInstanceState::Stateless;
+        let instance = self.instance.take().filter(|_| reusable);
+
+        // the permit is released after this push, because fields are dropped after drop()
+        self.pool.lock_instances().push(instance);
+    }
+}
+
+impl<'pool> WaitingCall<'pool> {
+    fn new(waiting_calls: &'pool AtomicUsize) -> Self {
+        waiting_calls.fetch_add(1, Ordering::AcqRel);
+        Self(waiting_calls)
+    }
+
+    /// Number of calls that had been waiting before this one.
+    fn position(&self) -> usize {
+        self.0.load(Ordering::Acquire) - 1
+    }
+}
+
+impl Drop for WaitingCall<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasmtime::WasmBackend;
+    use crate::TomlAppServiceConfig;
+
+    use marine::TomlMarineConfig;
+    use marine::TomlMarineNamedModuleConfig;
+    use marine_wasm_backend_traits::WasmBackend as _;
+
+    use std::path::PathBuf;
+
+    fn greeting_config() -> TomlAppServiceConfig {
+        let modules_dir =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../examples/greeting/artifacts");
+        let toml_marine_config = TomlMarineConfig {
+            modules_dir: Some(modules_dir),
+            module: vec![TomlMarineNamedModuleConfig {
+                name: String::from("greeting"),
+                ..<_>::default()
+            }],
+            ..<_>::default()
+        };
+
+        TomlAppServiceConfig {
+            toml_marine_config,
+            service_working_dir: Some(std::env::temp_dir().display().to_string()),
+        }
+    }
+
+    /// A module which leaks memory on each call, so the address of a new allocation
+    /// reveals whether the instance has served calls before.
+    fn allocating_config() -> TomlAppServiceConfig {
+        let modules_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../marine/tests/wasm_tests/memory_limiting/artifacts");
+        let toml_marine_config = TomlMarineConfig {
+            modules_dir: Some(modules_dir),
+            module: vec![TomlMarineNamedModuleConfig {
+                name: String::from("memory_limiting_effector"),
+                ..<_>::default()
+            }],
+            ..<_>::default()
+        };
+
+        TomlAppServiceConfig {
+            toml_marine_config,
+            service_working_dir: Some(std::env::temp_dir().display().to_string()),
+        }
+    }
+
+    async fn greeting_pool(pool_config: AppServicePoolConfig) -> AppServicePool<WasmBackend> {
+        create_pool(greeting_config(), pool_config).await
+    }
+
+    async fn create_pool(
+        config: TomlAppServiceConfig,
+        pool_config: AppServicePoolConfig,
+    ) -> AppServicePool<WasmBackend> {
+        let backend = WasmBackend::new_async().unwrap();
+        AppServicePool::new(
+            backend,
+            config,
+            "service_pool_test",
+            HashMap::new(),
+            pool_config,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't create service pool: {}", e))
+    }
+
+    #[tokio::test]
+    async fn concurrent_calls() {
+        for instance_state in [
+            PoolInstanceState::Stateless,
+            PoolInstanceState::ResetAfterCall,
+        ] {
+            let pool = greeting_pool(AppServicePoolConfig {
+                size: 2,
+                max_queue_len: None,
+                instance_state,
+            })
+            .await;
+
+            let calls = (0..8).map(|id| {
+                pool.call_async(
+                    "greeting",
+                    serde_json::json!([id.to_string()]),
+                    <_>::default(),
+                )
+            });
+            let results = futures::future::join_all(calls).await;
+
+            for (id, result) in results.into_iter().enumerate() {
+                assert_eq!(result.unwrap(), serde_json::json!(format!("Hi, {}", id)));
+            }
+            assert_eq!(pool.lock_instances().len(), 2);
+            assert_eq!(pool.waiting_calls(), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn full_queue_rejects_calls() {
+        let pool = greeting_pool(AppServicePoolConfig {
+            size: 1,
+            max_queue_len: Some(0),
+            instance_state: PoolInstanceState::Stateless,
+        })
+        .await;
+
+        let busy = pool.acquire().await.unwrap();
+        let result = pool
+            .call_async("greeting", serde_json::json!(["pool"]), <_>::default())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppServiceError::PoolQueueIsFull { max_queue_len: 0 })
+        ));
+        drop(busy);
+
+        // the instance dropped without completed call is recreated on the next use
+        let result = pool
+            .call_async("greeting", serde_json::json!(["pool"]), <_>::default())
+            .await;
+        assert_eq!(result.unwrap(), serde_json::json!("Hi, pool"));
+    }
+
+    #[tokio::test]
+    async fn reset_after_call_isolates_calls() {
+        async fn allocation_addresses(instance_state: PoolInstanceState) -> (JValue, JValue) {
+            let pool = create_pool(
+                allocating_config(),
+                AppServicePoolConfig {
+                    size: 1,
+                    max_queue_len: None,
+                    instance_state,
+                },
+            )
+            .await;
+
+            let allocate = || {
+                pool.call_async(
+                    "allocate_single_module_64KB_pieces",
+                    serde_json::json!([1]),
+                    <_>::default(),
+                )
+            };
+            let first = allocate().await.unwrap();
+            let second = allocate().await.unwrap();
+
+            (first, second)
+        }
+
+        // the memory leaked by the first call is seen by the second one on the same instance
+        let (first, second) = allocation_addresses(PoolInstanceState::Stateless).await;
+        assert_ne!(first, second);
+
+        // while a recreated instance starts from the same clean state
+        let (first, second) = allocation_addresses(PoolInstanceState::ResetAfterCall).await;
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,272 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::BinaryValue;
+use super::ValueView;
+use crate::IValue;
+use crate::IType;
+use crate::MRecordTypes;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+use crate::errors::ExpectedType;
+
+use wasmer_it::NEVec;
+
+use std::iter::ExactSizeIterator;
+
+type ConversionResult<T> = Result<T, ConversionError>;
+
+/// Convert a binary value to an array of ivalues according to the supplied argument types,
+/// arguments are accepted in the same forms as json ones.
+pub(crate) fn binary_to_ivalues<'a, V: BinaryValue>(
+    args: V,
+    arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
+    record_types: &MRecordTypes,
+) -> ConversionResult<Vec<IValue>> {
+    match args.into_view() {
+        ValueView::Map(fields) => map_to_ivalues(fields, arg_types, record_types),
+        ValueView::Array(values) => {
+            array_to_ivalues(values, arg_types.map(|arg| arg.1), record_types)
+        }
+        ValueView::Null if arg_types.len() == 0 => Ok(vec![]),
+        ValueView::Null => Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: arg_types.len(),
+                actual: 0,
+            },
+        )),
+        view => {
+            let mut arg_types = arg_types;
+            if arg_types.len() != 1 {
+                return Err(ConversionError::new(
+                    ConversionErrorKind::ValuesCountMismatch {
+                        expected: arg_types.len(),
+                        actual: 1,
+                    },
+                ));
+            }
+
+            // unwrap is safe here because iterator size's been checked
+            let arg_type = arg_types.next().unwrap().1;
+            let ivalue = view_to_ivalue(view, arg_type, record_types)?;
+
+            Ok(vec![ivalue])
+        }
+    }
+}
+
+fn map_to_ivalues<'a, V: BinaryValue>(
+    mut fields: Vec<(String, V)>,
+    arg_types: impl Iterator<Item = (&'a String, &'a IType)>,
+    record_types: &MRecordTypes,
+) -> ConversionResult<Vec<IValue>> {
+    let mut iargs = Vec::new();
+
+    for (arg_name, arg_type) in arg_types {
+        let position = fields
+            .iter()
+            .position(|(name, _)| name == arg_name)
+            .ok_or_else(|| {
+                ConversionError::new(ConversionErrorKind::MissingValue {
+                    expected: ExpectedType::new(arg_type, record_types),
+                })
+                .in_field(arg_name)
+            })?;
+        let (_, value) = fields.swap_remove(position);
+        let iarg = view_to_ivalue(value.into_view(), arg_type, record_types)
+            .map_err(|e| e.in_field(arg_name))?;
+        iargs.push(iarg);
+    }
+
+    if let Some((unexpected_name, _)) = fields.first() {
+        return Err(
+            ConversionError::new(ConversionErrorKind::UnexpectedValue).in_field(unexpected_name)
+        );
+    }
+
+    Ok(iargs)
+}
+
+fn array_to_ivalues<'a, V: BinaryValue>(
+    values: Vec<V>,
+    arg_types: impl Iterator<Item = &'a IType> + ExactSizeIterator,
+    record_types: &MRecordTypes,
+) -> ConversionResult<Vec<IValue>> {
+    if values.len() != arg_types.len() {
+        return Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: arg_types.len(),
+                actual: values.len(),
+            },
+        ));
+    }
+
+    values
+        .into_iter()
+        .zip(arg_types)
+        .enumerate()
+        .map(|(id, (value, arg_type))| {
+            view_to_ivalue(value.into_view(), arg_type, record_types).map_err(|e| e.in_element(id))
+        })
+        .collect()
+}
+
+fn view_to_ivalue<V: BinaryValue>(
+    view: ValueView<V>,
+    ty: &IType,
+    record_types: &MRecordTypes,
+) -> ConversionResult<IValue> {
+    let view = match view {
+        // like in json, an array with only one element is implicitly flattened to a scalar
+        ValueView::Array(mut values) if values.len() == 1 && is_scalar(ty) => {
+            values.remove(0).into_view()
+        }
+        view => view,
+    };
+
+    macro_rules! to_integer(
+        ($ty:ident, $rust_ty:ty) => {
+            match view {
+                ValueView::Integer(value) => <$rust_ty>::try_from(value)
+                    .map(IValue::$ty)
+                    .map_err(|_| invalid_value(ty, format!("integer {} is out of range", value), record_types)),
+                view => Err(type_mismatch(ty, &view, record_types)),
+            }
+        }
+    );
+
+    match ty {
+        IType::Boolean => match view {
+            ValueView::Bool(value) => Ok(IValue::Boolean(value)),
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::S8 => to_integer!(S8, i8),
+        IType::S16 => to_integer!(S16, i16),
+        IType::S32 => to_integer!(S32, i32),
+        IType::S64 => to_integer!(S64, i64),
+        IType::U8 => to_integer!(U8, u8),
+        IType::U16 => to_integer!(U16, u16),
+        IType::U32 => to_integer!(U32, u32),
+        IType::U64 => to_integer!(U64, u64),
+        IType::I32 => to_integer!(I32, i32),
+        IType::I64 => to_integer!(I64, i64),
+        IType::F32 => match view {
+            ValueView::Float(value) => Ok(IValue::F32(value as f32)),
+            ValueView::Integer(value) => match value as f32 {
+                float if float as i128 == value => Ok(IValue::F32(float)),
+                _ => Err(not_representable(ty, value, record_types)),
+            },
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::F64 => match view {
+            ValueView::Float(value) => Ok(IValue::F64(value)),
+            ValueView::Integer(value) => match value as f64 {
+                float if float as i128 == value => Ok(IValue::F64(float)),
+                _ => Err(not_representable(ty, value, record_types)),
+            },
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::String => match view {
+            ValueView::String(value) => Ok(IValue::String(value)),
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::ByteArray => match view {
+            ValueView::Bytes(value) => Ok(IValue::ByteArray(value)),
+            ValueView::Array(values) => values_to_ivalues(values, &IType::U8, record_types),
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::Array(value_type) => match view {
+            ValueView::Bytes(value) if **value_type == IType::U8 => Ok(IValue::ByteArray(value)),
+            ValueView::Array(values) => values_to_ivalues(values, value_type, record_types),
+            view => Err(type_mismatch(ty, &view, record_types)),
+        },
+        IType::Record(record_type_id) => {
+            let record_type = record_types.get(record_type_id).ok_or_else(|| {
+                ConversionError::new(ConversionErrorKind::RecordTypeNotFound(*record_type_id))
+            })?;
+            let fields = match view {
+                ValueView::Map(fields) => map_to_ivalues(
+                    fields,
+                    record_type
+                        .fields
+                        .iter()
+                        .map(|field| (&field.name, &field.ty)),
+                    record_types,
+                )?,
+                ValueView::Array(values) => array_to_ivalues(
+                    values,
+                    record_type.fields.iter().map(|field| &field.ty),
+                    record_types,
+                )?,
+                view => return Err(type_mismatch(ty, &view, record_types)),
+            };
+
+            // unwrap is safe here because records have at least one field
+            // and the number of fields has been checked
+            Ok(IValue::Record(NEVec::new(fields).unwrap()))
+        }
+    }
+}
+
+fn is_scalar(ty: &IType) -> bool {
+    !matches!(ty, IType::ByteArray | IType::Array(_) | IType::Record(_))
+}
+
+fn not_representable(ty: &IType, value: i128, record_types: &MRecordTypes) -> ConversionError {
+    invalid_value(
+        ty,
+        format!("integer {} isn't exactly representable", value),
+        record_types,
+    )
+}
+
+fn values_to_ivalues<V: BinaryValue>(
+    values: Vec<V>,
+    value_type: &IType,
+    record_types: &MRecordTypes,
+) -> ConversionResult<IValue> {
+    let values = values
+        .into_iter()
+        .enumerate()
+        .map(|(id, value)| {
+            view_to_ivalue(value.into_view(), value_type, record_types)
+                .map_err(|e| e.in_element(id))
+        })
+        .collect::<ConversionResult<Vec<_>>>()?;
+
+    Ok(IValue::Array(values))
+}
+
+fn type_mismatch<V>(
+    expected: &IType,
+    actual: &ValueView<V>,
+    record_types: &MRecordTypes,
+) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::TypeMismatch {
+        expected: ExpectedType::new(expected, record_types),
+        actual: actual.type_name().to_string(),
+    })
+}
+
+fn invalid_value(expected: &IType, reason: String, record_types: &MRecordTypes) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::InvalidValue {
+        expected: ExpectedType::new(expected, record_types),
+        reason,
+    })
+}
@@ -0,0 +1,75 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+/// A value of a binary encoding, which IValues are converted from and to.
+/// It allows to share the conversion driven by ITypes between encodings.
+pub(crate) trait BinaryValue: Sized {
+    fn null() -> Self;
+
+    fn from_bool(value: bool) -> Self;
+
+    fn from_i64(value: i64) -> Self;
+
+    fn from_u64(value: u64) -> Self;
+
+    fn from_f32(value: f32) -> Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    fn from_string(value: String) -> Self;
+
+    fn from_bytes(value: Vec<u8>) -> Self;
+
+    fn from_array(values: Vec<Self>) -> Self;
+
+    fn from_map(fields: Vec<(String, Self)>) -> Self;
+
+    fn into_view(self) -> ValueView<Self>;
+}
+
+/// Encoding independent view of a binary value.
+pub(crate) enum ValueView<V> {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<V>),
+    /// Only maps with string keys are represented as maps.
+    Map(Vec<(String, V)>),
+    /// A value that doesn't correspond to any IType, with its type name.
+    Other(&'static str),
+}
+
+impl<V> ValueView<V> {
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            ValueView::Null => "null",
+            ValueView::Bool(_) => "bool",
+            ValueView::Integer(_) => "integer",
+            ValueView::Float(_) => "float",
+            ValueView::String(_) => "string",
+            ValueView::Bytes(_) => "bytes",
+            ValueView::Array(_) => "array",
+            ValueView::Map(_) => "map",
+            ValueView::Other(type_name) => type_name,
+        }
+    }
+}
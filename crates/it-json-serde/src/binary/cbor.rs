@@ -0,0 +1,191 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::binary_to_ivalues::binary_to_ivalues;
+use super::ivalues_to_binary::ivalues_to_binary;
+use super::BinaryValue;
+use super::ValueView;
+use crate::IValue;
+use crate::IType;
+use crate::ITJsonSeDeError;
+use crate::JsonResult;
+use crate::MRecordTypes;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+
+use ciborium::Value;
+
+use std::iter::ExactSizeIterator;
+
+/// Convert CBOR encoded arguments to an array of ivalues according to the supplied argument types.
+pub fn cbor_to_ivalues<'a>(
+    args: &[u8],
+    arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
+    record_types: &MRecordTypes,
+) -> JsonResult<Vec<IValue>> {
+    let mut reader = args;
+    let value: Value = ciborium::de::from_reader(&mut reader)
+        .map_err(|e| encoding_error(e.to_string()))
+        .map_err(ITJsonSeDeError::De)?;
+    if !reader.is_empty() {
+        return Err(ITJsonSeDeError::De(encoding_error(format!(
+            "{} trailing bytes after arguments",
+            reader.len()
+        ))));
+    }
+
+    binary_to_ivalues(value, arg_types, record_types).map_err(ITJsonSeDeError::De)
+}
+
+/// Convert results of a function to CBOR.
+pub fn ivalues_to_cbor(
+    ivalues: Vec<IValue>,
+    outputs: &[IType],
+    record_types: &MRecordTypes,
+) -> JsonResult<Vec<u8>> {
+    let value: Value =
+        ivalues_to_binary(ivalues, outputs, record_types).map_err(ITJsonSeDeError::Se)?;
+
+    let mut result = Vec::new();
+    ciborium::ser::into_writer(&value, &mut result)
+        .map_err(|e| ITJsonSeDeError::Se(encoding_error(e.to_string())))?;
+
+    Ok(result)
+}
+
+fn encoding_error(reason: String) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::EncodingError(reason))
+}
+
+impl BinaryValue for Value {
+    fn null() -> Self {
+        Value::Null
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Value::Bool(value)
+    }
+
+    fn from_i64(value: i64) -> Self {
+        Value::Integer(value.into())
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Value::Integer(value.into())
+    }
+
+    fn from_f32(value: f32) -> Self {
+        Value::Float(value as f64)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Value::Float(value)
+    }
+
+    fn from_string(value: String) -> Self {
+        Value::Text(value)
+    }
+
+    fn from_bytes(value: Vec<u8>) -> Self {
+        Value::Bytes(value)
+    }
+
+    fn from_array(values: Vec<Self>) -> Self {
+        Value::Array(values)
+    }
+
+    fn from_map(fields: Vec<(String, Self)>) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(name, value)| (Value::Text(name), value))
+            .collect();
+        Value::Map(fields)
+    }
+
+    fn into_view(self) -> ValueView<Self> {
+        match self {
+            Value::Null => ValueView::Null,
+            Value::Bool(value) => ValueView::Bool(value),
+            Value::Integer(value) => ValueView::Integer(value.into()),
+            Value::Float(value) => ValueView::Float(value),
+            Value::Text(value) => ValueView::String(value),
+            Value::Bytes(value) => ValueView::Bytes(value),
+            Value::Array(values) => ValueView::Array(values),
+            Value::Map(fields) => fields
+                .into_iter()
+                .map(|(name, value)| match name {
+                    Value::Text(name) => Some((name, value)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map_or(ValueView::Other("map with non-string keys"), ValueView::Map),
+            Value::Tag(..) => ValueView::Other("tag"),
+            _ => ValueView::Other("unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn round_trip() {
+        let record_type = IRecordType {
+            name: String::from("Blob"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("id"),
+                    ty: IType::S64,
+                },
+                IRecordFieldType {
+                    name: String::from("data"),
+                    ty: IType::ByteArray,
+                },
+            ])
+            .unwrap(),
+        };
+        let record_types = MRecordTypes::from([(0, Arc::new(record_type))]);
+        let types = [IType::Record(0), IType::F32];
+        let ivalues = vec![
+            IValue::Record(
+                NEVec::new(vec![IValue::S64(i64::MIN), IValue::ByteArray(vec![1, 2])]).unwrap(),
+            ),
+            IValue::F32(0.5),
+        ];
+
+        let encoded = ivalues_to_cbor(ivalues.clone(), &types, &record_types).unwrap();
+
+        let value: Value = ciborium::de::from_reader(encoded.as_slice()).unwrap();
+        let blob = value.as_array().unwrap()[0].as_map().unwrap();
+        assert_eq!(blob[0], (Value::from("id"), Value::from(i64::MIN)));
+        assert_eq!(blob[1], (Value::from("data"), Value::Bytes(vec![1, 2])));
+
+        let names = [String::from("blob"), String::from("ratio")];
+        let decoded =
+            cbor_to_ivalues(&encoded, names.iter().zip(types.iter()), &record_types).unwrap();
+        assert_eq!(decoded, ivalues);
+    }
+}
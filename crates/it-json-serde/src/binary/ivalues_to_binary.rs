@@ -0,0 +1,160 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::BinaryValue;
+use crate::IValue;
+use crate::IType;
+use crate::MRecordTypes;
+use crate::errors::ivalue_type_name;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+use crate::errors::ExpectedType;
+
+type ConversionResult<T> = Result<T, ConversionError>;
+
+/// Convert results of a function to a binary value, the same way as they're converted to json:
+/// no results are represented as null, multi-values are represented as an array.
+pub(crate) fn ivalues_to_binary<V: BinaryValue>(
+    mut ivalues: Vec<IValue>,
+    outputs: &[IType],
+    record_types: &MRecordTypes,
+) -> ConversionResult<V> {
+    if outputs.len() != ivalues.len() {
+        return Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: outputs.len(),
+                actual: ivalues.len(),
+            },
+        ));
+    }
+
+    match ivalues.len() {
+        0 => Ok(V::null()),
+        1 => ivalue_to_binary(ivalues.remove(0), outputs.first().unwrap(), record_types),
+        _ => ivalues
+            .into_iter()
+            .zip(outputs.iter())
+            .enumerate()
+            .map(|(id, (ivalue, output))| {
+                ivalue_to_binary(ivalue, output, record_types).map_err(|e| e.in_element(id))
+            })
+            .collect::<ConversionResult<Vec<_>>>()
+            .map(V::from_array),
+    }
+}
+
+fn ivalue_to_binary<V: BinaryValue>(
+    ivalue: IValue,
+    output: &IType,
+    record_types: &MRecordTypes,
+) -> ConversionResult<V> {
+    match (ivalue, output) {
+        (IValue::Boolean(value), IType::Boolean) => Ok(V::from_bool(value)),
+        (IValue::S8(value), IType::S8) => Ok(V::from_i64(value as i64)),
+        (IValue::S16(value), IType::S16) => Ok(V::from_i64(value as i64)),
+        (IValue::S32(value), IType::S32) => Ok(V::from_i64(value as i64)),
+        (IValue::S64(value), IType::S64) => Ok(V::from_i64(value)),
+        (IValue::U8(value), IType::U8) => Ok(V::from_u64(value as u64)),
+        (IValue::U16(value), IType::U16) => Ok(V::from_u64(value as u64)),
+        (IValue::U32(value), IType::U32) => Ok(V::from_u64(value as u64)),
+        (IValue::U64(value), IType::U64) => Ok(V::from_u64(value)),
+        (IValue::I32(value), IType::I32) => Ok(V::from_i64(value as i64)),
+        (IValue::I64(value), IType::I64) => Ok(V::from_i64(value)),
+        (IValue::F32(value), IType::F32) => Ok(V::from_f32(value)),
+        (IValue::F64(value), IType::F64) => Ok(V::from_f64(value)),
+        (IValue::String(value), IType::String) => Ok(V::from_string(value)),
+        // unlike json, binary encodings have a native representation for byte arrays
+        (IValue::ByteArray(value), IType::ByteArray) => Ok(V::from_bytes(value)),
+        (IValue::Array(value), IType::ByteArray) => {
+            let bytes = value
+                .into_iter()
+                .enumerate()
+                .map(|(id, value)| match value {
+                    IValue::U8(byte) => Ok(byte),
+                    value => Err(type_mismatch(&IType::U8, &value, record_types).in_element(id)),
+                })
+                .collect::<ConversionResult<Vec<_>>>()?;
+
+            Ok(V::from_bytes(bytes))
+        }
+        (IValue::ByteArray(value), IType::Array(array_ty)) => {
+            let value = value.into_iter().map(IValue::U8).collect();
+            array_to_binary(value, array_ty, record_types)
+        }
+        (IValue::Array(value), IType::Array(array_ty)) => {
+            array_to_binary(value, array_ty, record_types)
+        }
+        (IValue::Record(field_values), IType::Record(record_id)) => {
+            let record_type = record_types.get(record_id).ok_or_else(|| {
+                ConversionError::new(ConversionErrorKind::RecordTypeNotFound(*record_id))
+            })?;
+            let field_types = &record_type.fields;
+
+            if field_values.len() != field_types.len() {
+                return Err(ConversionError::new(
+                    ConversionErrorKind::ValuesCountMismatch {
+                        expected: field_types.len(),
+                        actual: field_values.len(),
+                    },
+                ));
+            }
+
+            let fields = field_values
+                .into_vec()
+                .into_iter()
+                .zip(field_types.iter())
+                .map(|(field_value, field_type)| {
+                    let value = ivalue_to_binary(field_value, &field_type.ty, record_types)
+                        .map_err(|e| e.in_field(&field_type.name))?;
+                    Ok((field_type.name.clone(), value))
+                })
+                .collect::<ConversionResult<Vec<_>>>()?;
+
+            Ok(V::from_map(fields))
+        }
+        (ivalue, itype) => Err(type_mismatch(itype, &ivalue, record_types)),
+    }
+}
+
+fn array_to_binary<V: BinaryValue>(
+    values: Vec<IValue>,
+    value_type: &IType,
+    record_types: &MRecordTypes,
+) -> ConversionResult<V> {
+    let values = values
+        .into_iter()
+        .enumerate()
+        .map(|(id, value)| {
+            ivalue_to_binary(value, value_type, record_types).map_err(|e| e.in_element(id))
+        })
+        .collect::<ConversionResult<Vec<_>>>()?;
+
+    Ok(V::from_array(values))
+}
+
+fn type_mismatch(
+    expected: &IType,
+    actual: &IValue,
+    record_types: &MRecordTypes,
+) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::TypeMismatch {
+        expected: ExpectedType::new(expected, record_types),
+        actual: ivalue_type_name(actual).to_string(),
+    })
+}
@@ -0,0 +1,32 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+mod binary_to_ivalues;
+mod binary_value;
+mod cbor;
+mod ivalues_to_binary;
+mod msgpack;
+
+pub use cbor::cbor_to_ivalues;
+pub use cbor::ivalues_to_cbor;
+pub use msgpack::ivalues_to_msgpack;
+pub use msgpack::msgpack_to_ivalues;
+
+pub(crate) use binary_value::BinaryValue;
+pub(crate) use binary_value::ValueView;
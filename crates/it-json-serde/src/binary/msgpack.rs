@@ -0,0 +1,267 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::binary_to_ivalues::binary_to_ivalues;
+use super::ivalues_to_binary::ivalues_to_binary;
+use super::BinaryValue;
+use super::ValueView;
+use crate::IValue;
+use crate::IType;
+use crate::ITJsonSeDeError;
+use crate::JsonResult;
+use crate::MRecordTypes;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+
+use rmpv::Value;
+
+use std::iter::ExactSizeIterator;
+
+/// Convert MessagePack encoded arguments to an array of ivalues according to the supplied argument types.
+pub fn msgpack_to_ivalues<'a>(
+    args: &[u8],
+    arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
+    record_types: &MRecordTypes,
+) -> JsonResult<Vec<IValue>> {
+    let mut reader = args;
+    let value = rmpv::decode::read_value(&mut reader)
+        .map_err(|e| encoding_error(e.to_string()))
+        .map_err(ITJsonSeDeError::De)?;
+    if !reader.is_empty() {
+        return Err(ITJsonSeDeError::De(encoding_error(format!(
+            "{} trailing bytes after arguments",
+            reader.len()
+        ))));
+    }
+
+    binary_to_ivalues(value, arg_types, record_types).map_err(ITJsonSeDeError::De)
+}
+
+/// Convert results of a function to MessagePack.
+pub fn ivalues_to_msgpack(
+    ivalues: Vec<IValue>,
+    outputs: &[IType],
+    record_types: &MRecordTypes,
+) -> JsonResult<Vec<u8>> {
+    let value: Value =
+        ivalues_to_binary(ivalues, outputs, record_types).map_err(ITJsonSeDeError::Se)?;
+
+    let mut result = Vec::new();
+    rmpv::encode::write_value(&mut result, &value)
+        .map_err(|e| ITJsonSeDeError::Se(encoding_error(e.to_string())))?;
+
+    Ok(result)
+}
+
+fn encoding_error(reason: String) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::EncodingError(reason))
+}
+
+impl BinaryValue for Value {
+    fn null() -> Self {
+        Value::Nil
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Value::Boolean(value)
+    }
+
+    fn from_i64(value: i64) -> Self {
+        Value::from(value)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Value::from(value)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        Value::F32(value)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Value::F64(value)
+    }
+
+    fn from_string(value: String) -> Self {
+        Value::String(value.into())
+    }
+
+    fn from_bytes(value: Vec<u8>) -> Self {
+        Value::Binary(value)
+    }
+
+    fn from_array(values: Vec<Self>) -> Self {
+        Value::Array(values)
+    }
+
+    fn from_map(fields: Vec<(String, Self)>) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(name, value)| (Value::String(name.into()), value))
+            .collect();
+        Value::Map(fields)
+    }
+
+    fn into_view(self) -> ValueView<Self> {
+        match self {
+            Value::Nil => ValueView::Null,
+            Value::Boolean(value) => ValueView::Bool(value),
+            Value::Integer(value) => match (value.as_u64(), value.as_i64()) {
+                (Some(value), _) => ValueView::Integer(value as i128),
+                (_, Some(value)) => ValueView::Integer(value as i128),
+                (None, None) => ValueView::Other("integer"),
+            },
+            Value::F32(value) => ValueView::Float(value as f64),
+            Value::F64(value) => ValueView::Float(value),
+            Value::String(value) => match value.into_str() {
+                Some(value) => ValueView::String(value),
+                None => ValueView::Other("non UTF-8 string"),
+            },
+            Value::Binary(value) => ValueView::Bytes(value),
+            Value::Array(values) => ValueView::Array(values),
+            Value::Map(fields) => fields
+                .into_iter()
+                .map(|(name, value)| match name {
+                    Value::String(name) => name.into_str().map(|name| (name, value)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map_or(ValueView::Other("map with non-string keys"), ValueView::Map),
+            Value::Ext(..) => ValueView::Other("ext"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    fn record_types() -> MRecordTypes {
+        let record_type = IRecordType {
+            name: String::from("Blob"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("id"),
+                    ty: IType::U64,
+                },
+                IRecordFieldType {
+                    name: String::from("data"),
+                    ty: IType::ByteArray,
+                },
+            ])
+            .unwrap(),
+        };
+
+        MRecordTypes::from([(0, Arc::new(record_type))])
+    }
+
+    fn blob(id: u64, data: Vec<u8>) -> IValue {
+        IValue::Record(NEVec::new(vec![IValue::U64(id), IValue::ByteArray(data)]).unwrap())
+    }
+
+    #[test]
+    fn round_trip() {
+        let record_types = record_types();
+        let types = [IType::Array(Box::new(IType::Record(0))), IType::String];
+        let ivalues = vec![
+            IValue::Array(vec![blob(u64::MAX, vec![0x13, 0x37])]),
+            IValue::String(String::from("fluence")),
+        ];
+
+        let encoded = ivalues_to_msgpack(ivalues.clone(), &types, &record_types).unwrap();
+
+        let value = rmpv::decode::read_value(&mut encoded.as_slice()).unwrap();
+        assert_eq!(value[0][0]["id"], Value::from(u64::MAX));
+        assert_eq!(value[0][0]["data"], Value::Binary(vec![0x13, 0x37]));
+
+        let names = [String::from("blobs"), String::from("name")];
+        let decoded =
+            msgpack_to_ivalues(&encoded, names.iter().zip(types.iter()), &record_types).unwrap();
+        assert_eq!(decoded, ivalues);
+    }
+
+    #[test]
+    fn error_path() {
+        let record_types = record_types();
+        let types = [IType::Array(Box::new(IType::Record(0)))];
+        let names = [String::from("blobs")];
+
+        let args = Value::Map(vec![(
+            Value::from("blobs"),
+            Value::Array(vec![Value::Map(vec![
+                (Value::from("id"), Value::from(-1)),
+                (Value::from("data"), Value::Binary(vec![])),
+            ])]),
+        )]);
+        let mut encoded = Vec::new();
+        rmpv::encode::write_value(&mut encoded, &args).unwrap();
+
+        let error = msgpack_to_ivalues(&encoded, names.iter().zip(types.iter()), &record_types)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "at /blobs/0/id: invalid value for u64: integer -1 is out of range"
+        );
+
+        let error =
+            msgpack_to_ivalues(&encoded[..4], names.iter().zip(types.iter()), &record_types)
+                .unwrap_err();
+        assert!(matches!(
+            error.conversion_error().kind,
+            ConversionErrorKind::EncodingError(_)
+        ));
+    }
+
+    #[test]
+    fn scalar_arguments() {
+        let record_types = MRecordTypes::new();
+        let types = [IType::F32, IType::String];
+        let names = [String::from("weight"), String::from("name")];
+        let decode = |args: Value| {
+            let mut encoded = Vec::new();
+            rmpv::encode::write_value(&mut encoded, &args).unwrap();
+            msgpack_to_ivalues(&encoded, names.iter().zip(types.iter()), &record_types)
+        };
+
+        // single element arrays are flattened like json ones
+        let args = Value::Array(vec![
+            Value::Array(vec![Value::from(16_777_216)]),
+            Value::Array(vec![Value::from("fluence")]),
+        ]);
+        assert_eq!(
+            decode(args).unwrap(),
+            vec![
+                IValue::F32(16_777_216.0),
+                IValue::String(String::from("fluence"))
+            ]
+        );
+
+        let args = Value::Array(vec![Value::from(16_777_217), Value::from("fluence")]);
+        assert_eq!(
+            decode(args).unwrap_err().to_string(),
+            "at /0: invalid value for f32: integer 16777217 isn't exactly representable"
+        );
+    }
+}
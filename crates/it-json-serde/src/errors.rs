@@ -17,14 +17,184 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::IValue;
+use crate::IType;
+use crate::MRecordTypes;
+
+use serde_json::Value as JValue;
 use thiserror::Error;
 
-//TODO: use enum with strict errors instead of String
 #[derive(Debug, Error)]
 pub enum ITJsonSeDeError {
+    /// Error occurred while converting IValues to json.
     #[error("{0}")]
-    Se(String),
+    Se(ConversionError),
 
+    /// Error occurred while converting json to IValues.
     #[error("{0}")]
-    De(String),
+    De(ConversionError),
+}
+
+impl ITJsonSeDeError {
+    pub fn conversion_error(&self) -> &ConversionError {
+        match self {
+            ITJsonSeDeError::Se(error) | ITJsonSeDeError::De(error) => error,
+        }
+    }
+
+    /// JSON pointer to the value that failed to convert.
+    pub fn path(&self) -> &str {
+        &self.conversion_error().path
+    }
+}
+
+/// Conversion error of a value located by a JSON pointer (RFC 6901) inside the converted json,
+/// the empty pointer refers to the whole json.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{}{kind}", display_path(.path))]
+pub struct ConversionError {
+    pub path: String,
+    pub kind: ConversionErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConversionErrorKind {
+    /// A value has a type different from the expected one,
+    /// the actual type is a json type for json and an IType for IValues.
+    #[error("expected {expected}, got {actual}")]
+    TypeMismatch {
+        expected: ExpectedType,
+        actual: String,
+    },
+
+    /// A value has the expected json type, but can't be represented by the expected IType.
+    #[error("invalid value for {expected}: {reason}")]
+    InvalidValue {
+        expected: ExpectedType,
+        reason: String,
+    },
+
+    /// An argument or a record field is missing.
+    #[error("missing value of type {expected}")]
+    MissingValue { expected: ExpectedType },
+
+    /// An argument or a record field that isn't present in the function signature or the record type.
+    #[error("unexpected value")]
+    UnexpectedValue,
+
+    /// A number of values differs from a number of arguments, results or record fields.
+    #[error("expected {expected} values, got {actual}")]
+    ValuesCountMismatch { expected: usize, actual: usize },
+
+    #[error("record with type id {0} wasn't found")]
+    RecordTypeNotFound(u64),
+
+    /// A binary input can't be decoded or an output can't be encoded.
+    #[error("encoding error: {0}")]
+    EncodingError(String),
+}
+
+/// IType of a value with its text view, where records are represented by their names.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{name}")]
+pub struct ExpectedType {
+    pub ty: IType,
+    pub name: String,
+}
+
+impl ConversionError {
+    pub(crate) fn new(kind: ConversionErrorKind) -> Self {
+        Self {
+            path: String::new(),
+            kind,
+        }
+    }
+
+    /// Prepends a path segment while the error propagates from a nested value to its parent,
+    /// so paths are built only for failed conversions.
+    pub(crate) fn in_field(mut self, field_name: &str) -> Self {
+        let segment = field_name.replace('~', "~0").replace('/', "~1");
+        self.path = format!("/{}{}", segment, self.path);
+        self
+    }
+
+    pub(crate) fn in_element(mut self, index: usize) -> Self {
+        self.path = format!("/{}{}", index, self.path);
+        self
+    }
+}
+
+impl ExpectedType {
+    pub(crate) fn new(ty: &IType, record_types: &MRecordTypes) -> Self {
+        Self {
+            ty: ty.clone(),
+            name: itype_name(ty, record_types),
+        }
+    }
+}
+
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("at {}: ", path)
+    }
+}
+
+fn itype_name(ty: &IType, record_types: &MRecordTypes) -> String {
+    match ty {
+        IType::Record(record_type_id) => match record_types.get(record_type_id) {
+            Some(record_type) => record_type.name.clone(),
+            None => format!("record with type id {}", record_type_id),
+        },
+        IType::Array(array_ty) => format!("[]{}", itype_name(array_ty, record_types)),
+        IType::Boolean => "bool".to_string(),
+        IType::S8 => "i8".to_string(),
+        IType::S16 => "i16".to_string(),
+        IType::S32 => "i32".to_string(),
+        IType::S64 => "i64".to_string(),
+        IType::U8 => "u8".to_string(),
+        IType::U16 => "u16".to_string(),
+        IType::U32 => "u32".to_string(),
+        IType::U64 => "u64".to_string(),
+        IType::F32 => "f32".to_string(),
+        IType::F64 => "f64".to_string(),
+        IType::String => "string".to_string(),
+        IType::ByteArray => "[]u8".to_string(),
+        IType::I32 => "i32".to_string(),
+        IType::I64 => "i64".to_string(),
+    }
+}
+
+pub(crate) fn ivalue_type_name(ivalue: &IValue) -> &'static str {
+    match ivalue {
+        IValue::Boolean(_) => "bool",
+        IValue::S8(_) => "i8",
+        IValue::S16(_) => "i16",
+        IValue::S32(_) => "i32",
+        IValue::S64(_) => "i64",
+        IValue::U8(_) => "u8",
+        IValue::U16(_) => "u16",
+        IValue::U32(_) => "u32",
+        IValue::U64(_) => "u64",
+        IValue::F32(_) => "f32",
+        IValue::F64(_) => "f64",
+        IValue::String(_) => "string",
+        IValue::ByteArray(_) => "[]u8",
+        IValue::Array(_) => "array",
+        IValue::I32(_) => "i32",
+        IValue::I64(_) => "i64",
+        IValue::Record(_) => "record",
+    }
+}
+
+pub(crate) fn json_type_name(jvalue: &JValue) -> &'static str {
+    match jvalue {
+        JValue::Null => "null",
+        JValue::Bool(_) => "bool",
+        JValue::Number(_) => "number",
+        JValue::String(_) => "string",
+        JValue::Array(_) => "array",
+        JValue::Object(_) => "object",
+    }
 }
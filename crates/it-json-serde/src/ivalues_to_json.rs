@@ -19,37 +19,74 @@
 
 use crate::IValue;
 use crate::IType;
-use crate::ITJsonSeDeError::Se;
+use crate::ITJsonSeDeError;
 use crate::JsonResult;
 use crate::MRecordTypes;
+use crate::errors::ivalue_type_name;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+use crate::errors::ExpectedType;
+use crate::json_options::BytesRepresentation;
+use crate::json_options::Int64Representation;
+use crate::json_options::JsonOptions;
 
 use serde_json::Value as JValue;
 
+type ConversionResult<T> = Result<T, ConversionError>;
+
 pub fn ivalues_to_json(
+    ivalues: Vec<IValue>,
+    outputs: &[IType],
+    record_types: &MRecordTypes,
+) -> JsonResult<JValue> {
+    ivalues_to_json_with_options(ivalues, outputs, record_types, &JsonOptions::default())
+}
+
+/// Convert results of a function to json like [`ivalues_to_json`],
+/// with the supplied representation of byte arrays and 64-bit integers.
+pub fn ivalues_to_json_with_options(
     mut ivalues: Vec<IValue>,
     outputs: &[IType],
     record_types: &MRecordTypes,
+    options: &JsonOptions,
 ) -> JsonResult<JValue> {
     if outputs.len() != ivalues.len() {
-        return Err(Se(format!(
-            "resulted values {:?} and function signature {:?} aren't compatible",
-            ivalues, outputs
+        return Err(ITJsonSeDeError::Se(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: outputs.len(),
+                actual: ivalues.len(),
+            },
         )));
     }
-    match ivalues.len() {
+    let result = match ivalues.len() {
         0 => Ok(JValue::Null),
-        1 => ivalue_to_json(ivalues.remove(0), outputs.first().unwrap(), record_types),
-        _ => unimplemented!(
-            "multi-values aren't supported now - more then one result values aren't possible"
+        1 => ivalue_to_json(
+            ivalues.remove(0),
+            outputs.first().unwrap(),
+            record_types,
+            options,
         ),
-    }
+        // multi-values are represented as an array
+        _ => ivalues
+            .into_iter()
+            .zip(outputs.iter())
+            .enumerate()
+            .map(|(id, (ivalue, output))| {
+                ivalue_to_json(ivalue, output, record_types, options).map_err(|e| e.in_element(id))
+            })
+            .collect::<ConversionResult<Vec<_>>>()
+            .map(JValue::Array),
+    };
+
+    result.map_err(ITJsonSeDeError::Se)
 }
 
 fn ivalue_to_json(
     ivalue: IValue,
     output: &IType,
     record_types: &MRecordTypes,
-) -> JsonResult<JValue> {
+    options: &JsonOptions,
+) -> ConversionResult<JValue> {
     use serde_json::json;
 
     // clone here needed because binding by-value and by-ref in the same pattern in unstable
@@ -58,73 +95,195 @@ fn ivalue_to_json(
         (IValue::S8(value), IType::S8) => Ok(json!(value)),
         (IValue::S16(value), IType::S16) => Ok(json!(value)),
         (IValue::S32(value), IType::S32) => Ok(json!(value)),
-        (IValue::S64(value), IType::S64) => Ok(json!(value)),
+        (IValue::S64(value), IType::S64) => Ok(int64_to_json(value, options)),
         (IValue::U8(value), IType::U8) => Ok(json!(value)),
         (IValue::U16(value), IType::U16) => Ok(json!(value)),
         (IValue::U32(value), IType::U32) => Ok(json!(value)),
-        (IValue::U64(value), IType::U64) => Ok(json!(value)),
+        (IValue::U64(value), IType::U64) => Ok(int64_to_json(value, options)),
         (IValue::I32(value), IType::I32) => Ok(json!(value)),
-        (IValue::I64(value), IType::I64) => Ok(json!(value)),
+        (IValue::I64(value), IType::I64) => Ok(int64_to_json(value, options)),
         (IValue::F32(value), IType::F32) => Ok(json!(value)),
         (IValue::F64(value), IType::F64) => Ok(json!(value)),
         (IValue::String(value), IType::String) => Ok(json!(value)),
-        (IValue::ByteArray(value), IType::ByteArray) => {
-            let result = value.into_iter().map(|v| json!(v)).collect();
-            Ok(JValue::Array(result))
-        }
-        (IValue::Array(value), IType::ByteArray) => {
-            let result: JsonResult<Vec<_>> = value
+        (IValue::ByteArray(value), IType::ByteArray) => match options.bytes.encode(&value) {
+            Some(encoded) => Ok(JValue::String(encoded)),
+            None => {
+                let result = value.into_iter().map(|v| json!(v)).collect();
+                Ok(JValue::Array(result))
+            }
+        },
+        (IValue::Array(value), IType::ByteArray) if options.bytes != BytesRepresentation::Array => {
+            let bytes = value
                 .into_iter()
-                .map(|v| ivalue_to_json(v, &IType::U8, record_types))
-                .collect();
+                .enumerate()
+                .map(|(id, value)| match value {
+                    IValue::U8(byte) => Ok(byte),
+                    value => Err(ConversionError::new(ConversionErrorKind::TypeMismatch {
+                        expected: ExpectedType::new(&IType::U8, record_types),
+                        actual: ivalue_type_name(&value).to_string(),
+                    })
+                    .in_element(id)),
+                })
+                .collect::<ConversionResult<Vec<_>>>()?;
 
-            Ok(JValue::Array(result?))
+            // unwrap is safe here because the representation isn't an array
+            Ok(JValue::String(options.bytes.encode(&bytes).unwrap()))
+        }
+        (IValue::Array(value), IType::ByteArray) => {
+            array_to_json(value, &IType::U8, record_types, options)
         }
         (IValue::ByteArray(value), IType::Array(array_ty)) => {
-            let result: JsonResult<Vec<_>> = value
-                .into_iter()
-                .map(|v| ivalue_to_json(IValue::U8(v), &array_ty, record_types))
-                .collect();
-
-            Ok(JValue::Array(result?))
+            let value = value.into_iter().map(IValue::U8).collect();
+            array_to_json(value, &array_ty, record_types, options)
         }
         (IValue::Array(value), IType::Array(array_ty)) => {
-            let result: JsonResult<Vec<_>> = value
-                .into_iter()
-                .map(|v| ivalue_to_json(v, &array_ty, record_types))
-                .collect();
-
-            Ok(JValue::Array(result?))
+            array_to_json(value, &array_ty, record_types, options)
         }
         (IValue::Record(field_values), IType::Record(record_id)) => {
             let record_type = record_types.get(&record_id).ok_or_else(|| {
-                Se(format!(
-                    "record id {} wasn't found in module record types",
-                    record_id
-                ))
+                ConversionError::new(ConversionErrorKind::RecordTypeNotFound(record_id))
             })?;
             let field_types = &record_type.fields;
 
             if field_values.len() != field_types.len() {
-                return Err(Se(format!(
-                    "output record {:?} isn't compatible to output record fields {:?}",
-                    field_values, field_types
-                )));
+                return Err(ConversionError::new(
+                    ConversionErrorKind::ValuesCountMismatch {
+                        expected: field_types.len(),
+                        actual: field_values.len(),
+                    },
+                ));
             }
 
             let field_values = field_values.into_vec();
             let mut result = serde_json::Map::with_capacity(field_values.len());
 
             for (field_value, field_type) in field_values.into_iter().zip(field_types.iter()) {
-                let json_field_value = ivalue_to_json(field_value, &field_type.ty, record_types)?;
+                let json_field_value =
+                    ivalue_to_json(field_value, &field_type.ty, record_types, options)
+                        .map_err(|e| e.in_field(&field_type.name))?;
                 result.insert(field_type.name.clone(), json_field_value);
             }
 
             Ok(JValue::Object(result))
         }
-        (ivalue, itype) => Err(Se(format!(
-            "value {:?} is incompatible to type {:?}",
-            ivalue, itype
-        ))),
+        (ivalue, itype) => Err(ConversionError::new(ConversionErrorKind::TypeMismatch {
+            expected: ExpectedType::new(&itype, record_types),
+            actual: ivalue_type_name(&ivalue).to_string(),
+        })),
+    }
+}
+
+fn array_to_json(
+    values: Vec<IValue>,
+    value_type: &IType,
+    record_types: &MRecordTypes,
+    options: &JsonOptions,
+) -> ConversionResult<JValue> {
+    let result = values
+        .into_iter()
+        .enumerate()
+        .map(|(id, v)| {
+            ivalue_to_json(v, value_type, record_types, options).map_err(|e| e.in_element(id))
+        })
+        .collect::<ConversionResult<Vec<_>>>()?;
+
+    Ok(JValue::Array(result))
+}
+
+fn int64_to_json(value: impl Into<JValue> + ToString, options: &JsonOptions) -> JValue {
+    match options.int64 {
+        Int64Representation::Number => value.into(),
+        Int64Representation::String => JValue::String(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn error_path_in_nested_record() {
+        let record_type = IRecordType {
+            name: String::from("Item"),
+            fields: NEVec::new(vec![IRecordFieldType {
+                name: String::from("name"),
+                ty: IType::String,
+            }])
+            .unwrap(),
+        };
+        let record_types = MRecordTypes::from([(0, Arc::new(record_type))]);
+
+        let items = vec![
+            IValue::Record(NEVec::new(vec![IValue::String(String::from("a"))]).unwrap()),
+            IValue::Record(NEVec::new(vec![IValue::U8(1)]).unwrap()),
+        ];
+        let outputs = [IType::String, IType::Array(Box::new(IType::Record(0)))];
+        let ivalues = vec![IValue::String(String::from("s")), IValue::Array(items)];
+
+        let error = ivalues_to_json(ivalues, &outputs, &record_types).unwrap_err();
+        assert!(matches!(error, ITJsonSeDeError::Se(_)));
+        assert_eq!(error.path(), "/1/1/name");
+        assert_eq!(error.to_string(), "at /1/1/name: expected string, got u8");
+    }
+
+    #[test]
+    fn multi_value_round_trip() {
+        let record_type = IRecordType {
+            name: String::from("Item"),
+            fields: NEVec::new(vec![IRecordFieldType {
+                name: String::from("name"),
+                ty: IType::String,
+            }])
+            .unwrap(),
+        };
+        let record_types = MRecordTypes::from([(0, Arc::new(record_type))]);
+
+        let outputs = [
+            IType::I32,
+            IType::String,
+            IType::Record(0),
+            IType::Array(Box::new(IType::U64)),
+        ];
+        let ivalues = vec![
+            IValue::I32(-1),
+            IValue::String(String::from("Fluence")),
+            IValue::Record(NEVec::new(vec![IValue::String(String::from("a"))]).unwrap()),
+            IValue::Array(vec![IValue::U64(1), IValue::U64(2)]),
+        ];
+
+        let json = ivalues_to_json(ivalues.clone(), &outputs, &record_types).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([-1, "Fluence", {"name": "a"}, [1, 2]])
+        );
+
+        // the array of results is accepted back as positional arguments of the same types
+        let names = (0..outputs.len())
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        let round_trip =
+            crate::json_to_ivalues(json, names.iter().zip(outputs.iter()), &record_types).unwrap();
+        assert_eq!(round_trip, ivalues);
+    }
+
+    #[test]
+    fn values_count_mismatch() {
+        let outputs = [IType::I32, IType::I64];
+        let error =
+            ivalues_to_json(vec![IValue::I32(1)], &outputs, &MRecordTypes::new()).unwrap_err();
+
+        assert!(matches!(
+            error.conversion_error().kind,
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: 2,
+                actual: 1
+            }
+        ));
     }
 }
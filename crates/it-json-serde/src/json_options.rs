@@ -0,0 +1,189 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde_json::Value as JValue;
+
+/// Options of representation in json for types which don't have a lossless default one.
+/// The default options keep the representation used by [`crate::ivalues_to_json`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    pub bytes: BytesRepresentation,
+    pub int64: Int64Representation,
+}
+
+/// Representation of byte arrays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BytesRepresentation {
+    /// An array of numbers.
+    #[default]
+    Array,
+
+    /// A string with standard base64 with padding.
+    Base64,
+
+    /// A string with lowercase hex.
+    Hex,
+}
+
+/// Representation of 64-bit integers, which can't be represented by JS numbers without losing precision.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Int64Representation {
+    #[default]
+    Number,
+
+    /// A string with a decimal number.
+    String,
+}
+
+impl BytesRepresentation {
+    /// Encodes a byte array to a string, returns None for the array representation.
+    pub(crate) fn encode(self, bytes: &[u8]) -> Option<String> {
+        use base64::Engine;
+
+        match self {
+            BytesRepresentation::Array => None,
+            BytesRepresentation::Base64 => {
+                Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+            }
+            BytesRepresentation::Hex => Some(hex::encode(bytes)),
+        }
+    }
+
+    /// Decodes a byte array represented by a json string,
+    /// returns None if the value isn't a string or it's the array representation.
+    pub(crate) fn decode_json(self, value: &JValue) -> Option<Result<Vec<u8>, String>> {
+        use base64::Engine;
+
+        let value = match value {
+            JValue::String(value) => value,
+            _ => return None,
+        };
+
+        match self {
+            BytesRepresentation::Array => None,
+            BytesRepresentation::Base64 => Some(
+                base64::engine::general_purpose::STANDARD
+                    .decode(value)
+                    .map_err(|e| format!("invalid base64: {}", e)),
+            ),
+            BytesRepresentation::Hex => {
+                Some(hex::decode(value).map_err(|e| format!("invalid hex: {}", e)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ivalues_to_json_with_options;
+    use crate::json_to_ivalues;
+    use crate::json_to_ivalues_with_options;
+    use crate::IType;
+    use crate::IValue;
+    use crate::MRecordTypes;
+
+    use serde_json::json;
+
+    fn round_trip(options: JsonOptions, expected_json: JValue) {
+        let names = [
+            String::from("data"),
+            String::from("id"),
+            String::from("delta"),
+        ];
+        let types = [IType::ByteArray, IType::U64, IType::S64];
+        let ivalues = vec![
+            IValue::ByteArray(vec![0x13, 0x37, 0xff]),
+            IValue::U64(u64::MAX),
+            IValue::S64(i64::MIN),
+        ];
+        let record_types = MRecordTypes::new();
+
+        let json = ivalues_to_json_with_options(ivalues, &types, &record_types, &options).unwrap();
+        assert_eq!(json, expected_json);
+
+        let args = json!({"data": json[0], "id": json[1], "delta": json[2]});
+        let decoded = json_to_ivalues_with_options(
+            args,
+            names.iter().zip(types.iter()),
+            &record_types,
+            &options,
+        )
+        .unwrap();
+        let decoded_json =
+            ivalues_to_json_with_options(decoded, &types, &record_types, &options).unwrap();
+        assert_eq!(decoded_json, expected_json);
+    }
+
+    #[test]
+    fn default_representation() {
+        round_trip(
+            JsonOptions::default(),
+            json!([[0x13, 0x37, 0xff], u64::MAX, i64::MIN]),
+        );
+    }
+
+    #[test]
+    fn string_representations() {
+        let options = JsonOptions {
+            bytes: BytesRepresentation::Base64,
+            int64: Int64Representation::String,
+        };
+        round_trip(
+            options,
+            json!(["Ezf/", "18446744073709551615", "-9223372036854775808"]),
+        );
+
+        let options = JsonOptions {
+            bytes: BytesRepresentation::Hex,
+            int64: Int64Representation::String,
+        };
+        round_trip(
+            options,
+            json!(["1337ff", "18446744073709551615", "-9223372036854775808"]),
+        );
+    }
+
+    #[test]
+    fn strings_are_rejected_by_default() {
+        let names = [String::from("data"), String::from("id")];
+        let types = [IType::ByteArray, IType::U64];
+
+        let error = json_to_ivalues(
+            json!({"data": "1337", "id": 1}),
+            names.iter().zip(types.iter()),
+            &MRecordTypes::new(),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "at /data: expected []u8, got string");
+
+        let options = JsonOptions {
+            bytes: BytesRepresentation::Hex,
+            int64: Int64Representation::String,
+        };
+        let error = json_to_ivalues_with_options(
+            json!({"data": "1337", "id": "-1"}),
+            names.iter().zip(types.iter()),
+            &MRecordTypes::new(),
+            &options,
+        )
+        .unwrap_err();
+        assert_eq!(error.path(), "/id");
+    }
+}
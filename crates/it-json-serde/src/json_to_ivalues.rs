@@ -19,9 +19,15 @@
 
 use crate::IValue;
 use crate::IType;
-use super::ITJsonSeDeError::De;
-use super::JsonResult;
+use crate::ITJsonSeDeError;
+use crate::JsonResult;
 use crate::MRecordTypes;
+use crate::errors::json_type_name;
+use crate::errors::ConversionError;
+use crate::errors::ConversionErrorKind;
+use crate::errors::ExpectedType;
+use crate::json_options::Int64Representation;
+use crate::json_options::JsonOptions;
 
 use serde_json::Value as JValue;
 use wasmer_it::NEVec;
@@ -29,22 +35,38 @@ use wasmer_it::NEVec;
 use std::collections::HashMap;
 use std::iter::ExactSizeIterator;
 
+type ConversionResult<T> = Result<T, ConversionError>;
+
 /// Convert json to an array of ivalues according to the supplied argument types.
 pub fn json_to_ivalues<'a, 'b>(
     json_args: JValue,
     arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
     record_types: &'b MRecordTypes,
+) -> JsonResult<Vec<IValue>> {
+    json_to_ivalues_with_options(json_args, arg_types, record_types, &JsonOptions::default())
+}
+
+/// Convert json to an array of ivalues like [`json_to_ivalues`], additionally accepting
+/// byte arrays and 64-bit integers in the representation from the supplied options.
+pub fn json_to_ivalues_with_options<'a, 'b>(
+    json_args: JValue,
+    arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
+    record_types: &'b MRecordTypes,
+    options: &JsonOptions,
 ) -> JsonResult<Vec<IValue>> {
     let ivalues = match json_args {
-        JValue::Object(json_map) => json_map_to_ivalues(json_map, arg_types, record_types)?,
-        JValue::Array(json_array) => {
-            json_array_to_ivalues(json_array, arg_types.map(|arg| arg.1), record_types)?
-        }
-        JValue::Null => json_null_to_ivalues(arg_types)?,
-        json_value => json_value_to_ivalues(json_value, arg_types)?,
+        JValue::Object(json_map) => json_map_to_ivalues(json_map, arg_types, record_types, options),
+        JValue::Array(json_array) => json_array_to_ivalues(
+            json_array,
+            arg_types.map(|arg| arg.1),
+            record_types,
+            options,
+        ),
+        JValue::Null => json_null_to_ivalues(arg_types),
+        json_value => json_value_to_ivalues(json_value, arg_types, options),
     };
 
-    Ok(ivalues)
+    ivalues.map_err(ITJsonSeDeError::De)
 }
 
 /// Convert json map to an array of ivalues according to the supplied argument types.
@@ -52,23 +74,26 @@ fn json_map_to_ivalues<'a, 'b>(
     mut json_map: serde_json::Map<String, JValue>,
     arg_types: impl Iterator<Item = (&'a String, &'a IType)>,
     record_types: &'b MRecordTypes,
-) -> JsonResult<Vec<IValue>> {
+    options: &JsonOptions,
+) -> ConversionResult<Vec<IValue>> {
     let mut iargs = Vec::new();
 
     for (arg_name, arg_type) in arg_types {
-        let json_value = json_map
-            .remove(arg_name)
-            .ok_or_else(|| De(format!("missing argument with name {}", arg_name)))?;
-        let iarg = jvalue_to_ivalue(json_value, arg_type, record_types)?;
+        let json_value = json_map.remove(arg_name).ok_or_else(|| {
+            ConversionError::new(ConversionErrorKind::MissingValue {
+                expected: ExpectedType::new(arg_type, record_types),
+            })
+            .in_field(arg_name)
+        })?;
+        let iarg = jvalue_to_ivalue(json_value, arg_type, record_types, options)
+            .map_err(|e| e.in_field(arg_name))?;
         iargs.push(iarg);
     }
 
-    if !json_map.is_empty() {
-        return Err(De(format!(
-            "function requires {} arguments, {} provided",
-            iargs.len(),
-            iargs.len() + json_map.len()
-        )));
+    if let Some(unexpected_name) = json_map.keys().next() {
+        return Err(
+            ConversionError::new(ConversionErrorKind::UnexpectedValue).in_field(unexpected_name)
+        );
     }
 
     Ok(iargs)
@@ -79,20 +104,26 @@ fn json_array_to_ivalues<'a, 'b>(
     json_array: Vec<JValue>,
     arg_types: impl Iterator<Item = &'a IType> + ExactSizeIterator,
     record_types: &'b MRecordTypes,
-) -> JsonResult<Vec<IValue>> {
+    options: &JsonOptions,
+) -> ConversionResult<Vec<IValue>> {
     if json_array.len() != arg_types.len() {
-        return Err(De(format!(
-            "function requires {} arguments, {} provided",
-            arg_types.len(),
-            json_array.len()
-        )));
+        return Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: arg_types.len(),
+                actual: json_array.len(),
+            },
+        ));
     }
 
     let iargs = json_array
         .into_iter()
         .zip(arg_types)
-        .map(|(json_value, arg_type)| jvalue_to_ivalue(json_value, arg_type, record_types))
-        .collect::<JsonResult<Vec<_>>>()?;
+        .enumerate()
+        .map(|(id, (json_value, arg_type))| {
+            jvalue_to_ivalue(json_value, arg_type, record_types, options)
+                .map_err(|e| e.in_element(id))
+        })
+        .collect::<ConversionResult<Vec<_>>>()?;
 
     Ok(iargs)
 }
@@ -101,18 +132,20 @@ fn json_array_to_ivalues<'a, 'b>(
 fn json_value_to_ivalues<'a>(
     json_value: JValue,
     mut arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
-) -> JsonResult<Vec<IValue>> {
+    options: &JsonOptions,
+) -> ConversionResult<Vec<IValue>> {
     if arg_types.len() != 1 {
-        return Err(De(format!(
-            "called function has the following signature: '{:?}', and it isn't suitable for an argument '{:?}' provided",
-            arg_types.collect::<Vec<_>>(),
-            json_value,
-        )));
+        return Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: arg_types.len(),
+                actual: 1,
+            },
+        ));
     }
 
     // unwrap is safe here because iterator size's been checked
     let arg_type = arg_types.next().unwrap().1;
-    let ivalue = jvalue_to_ivalue(json_value, arg_type, &HashMap::new())?;
+    let ivalue = jvalue_to_ivalue(json_value, arg_type, &HashMap::new(), options)?;
 
     Ok(vec![ivalue])
 }
@@ -120,33 +153,46 @@ fn json_value_to_ivalues<'a>(
 /// Convert json Null to an empty array of ivalues.
 fn json_null_to_ivalues<'a>(
     arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
-) -> JsonResult<Vec<IValue>> {
+) -> ConversionResult<Vec<IValue>> {
     if arg_types.len() != 0 {
-        return Err(De(format!(
-            "the called function has the following signature: {:?}, but no arguments is provided",
-            arg_types.collect::<Vec<_>>()
-        )));
+        return Err(ConversionError::new(
+            ConversionErrorKind::ValuesCountMismatch {
+                expected: arg_types.len(),
+                actual: 0,
+            },
+        ));
     }
 
     Ok(vec![])
 }
 
 /// Convert one JValue to an array of ivalues according to the supplied argument type.
-fn jvalue_to_ivalue(jvalue: JValue, ty: &IType, record_types: &MRecordTypes) -> JsonResult<IValue> {
+fn jvalue_to_ivalue(
+    jvalue: JValue,
+    ty: &IType,
+    record_types: &MRecordTypes,
+    options: &JsonOptions,
+) -> ConversionResult<IValue> {
     macro_rules! to_ivalue(
-        ($json_value:expr, $ty:ident) => {
+        ($json_value:expr, $ty:ident, $json_type:literal) => {
             {
-                let value = match $json_value {
+                let json_value = match $json_value {
                     // if there is an array with only one element try to implicitly flatten it,
                     // this is needed mostly because jsonpath lib returns Vec<&JValue> and
                     // could be changed in future
-                    JValue::Array(mut json_array) if json_array.len() == 1 => {
-                        serde_json::from_value(json_array.remove(0))
-                    },
-                    jvalue => serde_json::from_value(jvalue),
-                }.map_err(|e|
-                    De(format!("error {:?} occurred while deserializing function arguments",e))
-                )?;
+                    JValue::Array(mut json_array) if json_array.len() == 1 => json_array.remove(0),
+                    jvalue => jvalue,
+                };
+                let actual = json_type_name(&json_value);
+                let value = serde_json::from_value(json_value).map_err(|e| {
+                    let expected = ExpectedType::new(ty, record_types);
+                    let kind = if actual == $json_type {
+                        ConversionErrorKind::InvalidValue { expected, reason: e.to_string() }
+                    } else {
+                        ConversionErrorKind::TypeMismatch { expected, actual: actual.to_string() }
+                    };
+                    ConversionError::new(kind)
+                })?;
 
                 Ok(IValue::$ty(value))
             }
@@ -154,60 +200,103 @@ fn jvalue_to_ivalue(jvalue: JValue, ty: &IType, record_types: &MRecordTypes) ->
     );
 
     match ty {
-        IType::Boolean => to_ivalue!(jvalue, Boolean),
-        IType::S8 => to_ivalue!(jvalue, S8),
-        IType::S16 => to_ivalue!(jvalue, S16),
-        IType::S32 => to_ivalue!(jvalue, S32),
-        IType::S64 => to_ivalue!(jvalue, S64),
-        IType::U8 => to_ivalue!(jvalue, U8),
-        IType::U16 => to_ivalue!(jvalue, U16),
-        IType::U32 => to_ivalue!(jvalue, U32),
-        IType::U64 => to_ivalue!(jvalue, U64),
-        IType::F32 => to_ivalue!(jvalue, F32),
-        IType::F64 => to_ivalue!(jvalue, F64),
-        IType::String => to_ivalue!(jvalue, String),
-        IType::ByteArray => {
-            let value = match jvalue {
-                JValue::Array(json_array) => {
-                    let iargs = json_array
-                        .into_iter()
-                        .map(|json_value| jvalue_to_ivalue(json_value, &IType::U8, record_types))
-                        .collect::<JsonResult<Vec<_>>>()?;
-
-                    Ok(iargs)
-                }
-                _ => Err(De(format!("expected bytearray, got {:?}", jvalue))),
-            }?;
-
-            Ok(IValue::Array(value))
+        IType::Boolean => to_ivalue!(jvalue, Boolean, "bool"),
+        IType::S8 => to_ivalue!(jvalue, S8, "number"),
+        IType::S16 => to_ivalue!(jvalue, S16, "number"),
+        IType::S32 => to_ivalue!(jvalue, S32, "number"),
+        IType::S64 | IType::U64 | IType::I64
+            if options.int64 == Int64Representation::String && jvalue.is_string() =>
+        {
+            string_to_int64(jvalue, ty, record_types)
         }
+        IType::S64 => to_ivalue!(jvalue, S64, "number"),
+        IType::U8 => to_ivalue!(jvalue, U8, "number"),
+        IType::U16 => to_ivalue!(jvalue, U16, "number"),
+        IType::U32 => to_ivalue!(jvalue, U32, "number"),
+        IType::U64 => to_ivalue!(jvalue, U64, "number"),
+        IType::F32 => to_ivalue!(jvalue, F32, "number"),
+        IType::F64 => to_ivalue!(jvalue, F64, "number"),
+        IType::String => to_ivalue!(jvalue, String, "string"),
+        IType::ByteArray => match (options.bytes.decode_json(&jvalue), jvalue) {
+            (Some(bytes), _) => bytes
+                .map(IValue::ByteArray)
+                .map_err(|reason| invalid_value(ty, reason, record_types)),
+            (None, jvalue) => {
+                let value = json_array_of(jvalue, ty, &IType::U8, record_types, options)?;
+                Ok(IValue::Array(value))
+            }
+        },
         IType::Array(value_type) => {
-            let value = match jvalue {
-                JValue::Array(json_array) => {
-                    let iargs = json_array
-                        .into_iter()
-                        .map(|json_value| jvalue_to_ivalue(json_value, value_type, record_types))
-                        .collect::<JsonResult<Vec<_>>>()?;
-
-                    Ok(iargs)
-                }
-                _ => Err(De(format!(
-                    "expected array of {:?} types, got {:?}",
-                    value_type, jvalue
-                ))),
-            }?;
-
+            let value = json_array_of(jvalue, ty, value_type, record_types, options)?;
             Ok(IValue::Array(value))
         }
-        IType::I32 => to_ivalue!(jvalue, I32),
-        IType::I64 => to_ivalue!(jvalue, I64),
+        IType::I32 => to_ivalue!(jvalue, I32, "number"),
+        IType::I64 => to_ivalue!(jvalue, I64, "number"),
         IType::Record(record_type_id) => {
-            let value = json_record_type_to_ivalue(jvalue, record_type_id, record_types)?;
+            let value = json_record_type_to_ivalue(jvalue, record_type_id, record_types, options)?;
             Ok(IValue::Record(value))
         }
     }
 }
 
+/// Convert JValue of array type to a vector of ivalues of the same type.
+fn json_array_of(
+    jvalue: JValue,
+    array_type: &IType,
+    value_type: &IType,
+    record_types: &MRecordTypes,
+    options: &JsonOptions,
+) -> ConversionResult<Vec<IValue>> {
+    match jvalue {
+        JValue::Array(json_array) => json_array
+            .into_iter()
+            .enumerate()
+            .map(|(id, json_value)| {
+                jvalue_to_ivalue(json_value, value_type, record_types, options)
+                    .map_err(|e| e.in_element(id))
+            })
+            .collect::<ConversionResult<Vec<_>>>(),
+        jvalue => Err(ConversionError::new(ConversionErrorKind::TypeMismatch {
+            expected: ExpectedType::new(array_type, record_types),
+            actual: json_type_name(&jvalue).to_string(),
+        })),
+    }
+}
+
+/// Convert JValue of string type to a 64-bit integer IValue of the supplied type.
+fn string_to_int64(
+    jvalue: JValue,
+    ty: &IType,
+    record_types: &MRecordTypes,
+) -> ConversionResult<IValue> {
+    let value = match jvalue {
+        JValue::String(value) => value,
+        jvalue => unreachable!(
+            "string_to_int64 is called for {} json value",
+            json_type_name(&jvalue)
+        ),
+    };
+
+    let ivalue = match ty {
+        IType::S64 => value.parse().map(IValue::S64),
+        IType::U64 => value.parse().map(IValue::U64),
+        IType::I64 => value.parse().map(IValue::I64),
+        _ => unreachable!(
+            "string_to_int64 is called for non 64-bit integer type {:?}",
+            ty
+        ),
+    };
+
+    ivalue.map_err(|e| invalid_value(ty, format!("{} in \"{}\"", e, value), record_types))
+}
+
+fn invalid_value(expected: &IType, reason: String, record_types: &MRecordTypes) -> ConversionError {
+    ConversionError::new(ConversionErrorKind::InvalidValue {
+        expected: ExpectedType::new(expected, record_types),
+        reason,
+    })
+}
+
 #[allow(clippy::ptr_arg)]
 /// Convert JValue of array or object types to an IValue record type.
 // TODO: after introducing new Record type wrapper change the result type
@@ -215,12 +304,10 @@ fn json_record_type_to_ivalue(
     json_value: JValue,
     record_type_id: &u64,
     record_types: &MRecordTypes,
-) -> JsonResult<NEVec<IValue>> {
+    options: &JsonOptions,
+) -> ConversionResult<NEVec<IValue>> {
     let record_type = record_types.get(record_type_id).ok_or_else(|| {
-        De(format!(
-            "record with type id `{}` wasn't found",
-            record_type_id
-        ))
+        ConversionError::new(ConversionErrorKind::RecordTypeNotFound(*record_type_id))
     })?;
 
     match json_value {
@@ -231,17 +318,126 @@ fn json_record_type_to_ivalue(
                 .iter()
                 .map(|field| (&field.name, &field.ty)),
             record_types,
+            options,
         )?)
         .unwrap()),
         JValue::Array(json_array) => Ok(NEVec::new(json_array_to_ivalues(
             json_array,
             record_type.fields.iter().map(|field| (&field.ty)),
             record_types,
+            options,
         )?)
         .unwrap()),
-        _ => Err(De(format!(
-            "record with type id `{}` should be encoded as array or map of fields",
-            record_type_id
-        ))),
+        json_value => Err(ConversionError::new(ConversionErrorKind::TypeMismatch {
+            expected: ExpectedType::new(&IType::Record(*record_type_id), record_types),
+            actual: json_type_name(&json_value).to_string(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+
+    use std::sync::Arc;
+
+    fn record_types() -> MRecordTypes {
+        let item = IRecordType {
+            name: String::from("Item"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("name"),
+                    ty: IType::String,
+                },
+                IRecordFieldType {
+                    name: String::from("count"),
+                    ty: IType::U8,
+                },
+            ])
+            .unwrap(),
+        };
+        let order = IRecordType {
+            name: String::from("Order"),
+            fields: NEVec::new(vec![IRecordFieldType {
+                name: String::from("items"),
+                ty: IType::Array(Box::new(IType::Record(0))),
+            }])
+            .unwrap(),
+        };
+
+        MRecordTypes::from([(0, Arc::new(item)), (1, Arc::new(order))])
+    }
+
+    fn convert(json_args: JValue) -> ITJsonSeDeError {
+        let arg_names = [String::from("id"), String::from("orders")];
+        let arg_types = [IType::U32, IType::Array(Box::new(IType::Record(1)))];
+
+        json_to_ivalues(
+            json_args,
+            arg_names.iter().zip(arg_types.iter()),
+            &record_types(),
+        )
+        .unwrap_err()
+    }
+
+    #[test]
+    fn type_mismatch_path() {
+        let orders =
+            json!([{"items": []}, {"items": [{"name": "a", "count": 1}, {"name": 2, "count": 1}]}]);
+
+        let error = convert(json!({"id": 1, "orders": orders}));
+        assert_eq!(error.path(), "/orders/1/items/1/name");
+        assert_eq!(
+            error.to_string(),
+            "at /orders/1/items/1/name: expected string, got number"
+        );
+
+        let error = convert(json!([1, orders]));
+        assert_eq!(error.path(), "/1/1/items/1/name");
+    }
+
+    #[test]
+    fn expected_record_name() {
+        let error = convert(json!({"id": 1, "orders": [{"items": ["item"]}]}));
+        assert_eq!(
+            error.to_string(),
+            "at /orders/0/items/0: expected Item, got string"
+        );
+        assert_eq!(
+            error.conversion_error().kind,
+            ConversionErrorKind::TypeMismatch {
+                expected: ExpectedType {
+                    ty: IType::Record(0),
+                    name: String::from("Item")
+                },
+                actual: String::from("string"),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_value() {
+        let error = convert(json!({"id": 1, "orders": [{"items": [{"name": "a", "count": 300}]}]}));
+        assert_eq!(error.path(), "/orders/0/items/0/count");
+        assert!(matches!(
+            error.conversion_error().kind,
+            ConversionErrorKind::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn missing_and_unexpected_values() {
+        let error = convert(json!({"id": 1, "orders": [{"items": [{"name": "a"}]}]}));
+        assert_eq!(
+            error.to_string(),
+            "at /orders/0/items/0/count: missing value of type u8"
+        );
+
+        let error = convert(json!({"id": 1, "orders": [], "a/b": 1}));
+        assert_eq!(error.to_string(), "at /a~1b: unexpected value");
     }
 }
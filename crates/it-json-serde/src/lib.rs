@@ -26,14 +26,28 @@
     unused_unsafe,
     unreachable_patterns
 )]
+mod binary;
 mod ivalues_to_json;
 mod json_to_ivalues;
+mod json_options;
 mod errors;
 
 pub type JsonResult<T> = Result<T, ITJsonSeDeError>;
 pub use errors::ITJsonSeDeError;
+pub use errors::ConversionError;
+pub use errors::ConversionErrorKind;
+pub use errors::ExpectedType;
 pub use ivalues_to_json::ivalues_to_json;
+pub use ivalues_to_json::ivalues_to_json_with_options;
 pub use json_to_ivalues::json_to_ivalues;
+pub use json_to_ivalues::json_to_ivalues_with_options;
+pub use json_options::BytesRepresentation;
+pub use json_options::Int64Representation;
+pub use json_options::JsonOptions;
+pub use binary::cbor_to_ivalues;
+pub use binary::ivalues_to_cbor;
+pub use binary::ivalues_to_msgpack;
+pub use binary::msgpack_to_ivalues;
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -74,6 +74,10 @@ impl Module<JsWasmBackend> for JsModule {
         }
     }
 
+    fn function_imports(&self) -> Vec<(String, String)> {
+        self.module_info.function_imports.clone()
+    }
+
     fn instantiate<'args>(
         &'args self,
         store: &'args mut JsStore,
@@ -32,6 +32,8 @@ use std::collections::HashMap;
 pub(crate) struct ModuleInfo {
     pub(crate) custom_sections: MultiMap<String, Vec<u8>>,
     pub(crate) exports: HashMap<String, Export>,
+    /// Namespaces and names of imported functions.
+    pub(crate) function_imports: Vec<(String, String)>,
 }
 
 #[derive(Clone)]
@@ -55,6 +57,8 @@ struct ModuleInfoParser<'wasm> {
     functions: Vec<u32>,
     /// export names + indexes in `functions` field
     exports: Vec<wasmparser::Export<'wasm>>,
+    /// namespaces and names of imported functions
+    function_imports: Vec<(&'wasm str, &'wasm str)>,
     /// names and data
     custom_sections: Vec<(&'wasm str, &'wasm [u8])>,
 }
@@ -65,6 +69,7 @@ impl<'wasm> ModuleInfoParser<'wasm> {
             types: <_>::default(),
             functions: <_>::default(),
             exports: <_>::default(),
+            function_imports: <_>::default(),
             custom_sections: <_>::default(),
         };
 
@@ -76,9 +81,16 @@ impl<'wasm> ModuleInfoParser<'wasm> {
     pub(crate) fn into_module_info(self) -> Result<ModuleInfo, ModuleCreationError> {
         let exports = self.extract_exports()?;
         let custom_sections = self.extract_custom_sections();
+        let function_imports = self
+            .function_imports
+            .iter()
+            .map(|(module, name)| (module.to_string(), name.to_string()))
+            .collect();
+
         Ok(ModuleInfo {
             exports,
             custom_sections,
+            function_imports,
         })
     }
 
@@ -104,7 +116,8 @@ impl<'wasm> ModuleInfoParser<'wasm> {
                     for import in imports {
                         let import = import.map_err(transform_err)?;
                         if let wasmparser::TypeRef::Func(idx) = import.ty {
-                            self.functions.push(idx)
+                            self.functions.push(idx);
+                            self.function_imports.push((import.module, import.name));
                         }
                     }
                 }
@@ -20,10 +20,16 @@
 use crate::ModuleInfoResult;
 use crate::ModuleInfoError;
 
+use marine_wasm_backend_traits::Module as ModuleTrait;
+use marine_wasm_backend_traits::WasmBackend;
+use thiserror::Error;
+use walrus::ImportKind;
 use walrus::ModuleConfig;
 use walrus::Module;
 
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 
 // TODO: create a common place for these consts to use in both marine and marine-rs-sdk to use in both marine and marine-rs-sdk
 const HOST_IMPORT_NAMESPACE_V0: &str = "host";
@@ -31,12 +37,55 @@ const HOST_IMPORT_NAMESPACE_PREFIX: &str = "__marine_host_api_v";
 const LOGGER_IMPORT_NAME: &str = "log_utf8_string";
 const CALL_PARAMETERS_IMPORT_NAME: &str = "get_call_parameters";
 
-#[derive(Debug)]
+const WASI_NAMESPACES: [&str; 2] = ["wasi_snapshot_preview1", "wasi_unstable"];
+
+/// Something a module could do outside of its own memory, derived from its imports.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum WasmEffect {
+    /// Writes logs with the host logger.
     Logger,
+    /// Calls a host import, which is a mounted binary or an import provided by the host.
     MountedBinary(String),
+    /// Reads parameters of the current call.
+    CallParameters,
+    /// Accesses files in directories mapped with WASI, holds the WASI function name.
+    Filesystem(String),
+    /// Reads or writes file descriptors, only stdio is reachable without filesystem effects.
+    Stdio(String),
+    /// Reads environment variables or command line arguments.
+    EnvironmentRead(String),
+    /// Reads clocks.
+    Clock(String),
+    /// Gets random bytes from the host.
+    Randomness,
+    /// Terminates the process with WASI `proc_exit`.
+    ProcExit,
+    /// Calls any other WASI function, like polling or sockets.
+    Wasi(String),
+    /// Calls a function exported by another Marine module.
+    ModuleImport { module: String, function: String },
+}
+
+/// Kind of an effect regardless of a particular import, used in effects policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EffectKind {
+    Logger,
+    MountedBinary,
+    CallParameters,
+    Filesystem,
+    Stdio,
+    EnvironmentRead,
+    Clock,
+    Randomness,
+    ProcExit,
+    Wasi,
+    ModuleImport,
 }
 
+#[derive(Debug, Error)]
+#[error(r#"unknown effect kind "{0}""#)]
+pub struct UnknownEffectKind(String);
+
 pub fn extract_from_path<P>(wasm_module_path: P) -> ModuleInfoResult<Vec<WasmEffect>>
 where
     P: AsRef<Path>,
@@ -56,28 +105,268 @@ pub fn extract_from_bytes(wasm_module_bytes: &[u8]) -> ModuleInfoResult<Vec<Wasm
     extract_from_module(&module)
 }
 
+/// Returns effects of a module sorted by their kinds, a module could have several effects
+/// of the same kind, e.g. several mounted binaries.
 pub fn extract_from_module(wasm_module: &Module) -> ModuleInfoResult<Vec<WasmEffect>> {
-    let effects = wasm_module
+    let imports = wasm_module
         .imports
         .iter()
-        .filter_map(|import| inspect_import(&import.module, &import.name))
-        .collect();
+        .filter(|import| matches!(import.kind, ImportKind::Function(_)))
+        .map(|import| (import.module.as_str(), import.name.as_str()));
+
+    Ok(extract_from_imports(imports))
+}
+
+pub fn extract_from_compiled_module<WB: WasmBackend>(
+    wasm_module: &<WB as WasmBackend>::Module,
+) -> Vec<WasmEffect> {
+    let imports = wasm_module.function_imports();
+    let imports = imports
+        .iter()
+        .map(|(module, name)| (module.as_str(), name.as_str()));
 
-    Ok(effects)
+    extract_from_imports(imports)
 }
 
-fn inspect_import(module: &str, name: &str) -> Option<WasmEffect> {
-    if !is_host_import(module) {
-        return None;
+/// Returns effects of function imports given by their namespaces and names.
+pub fn extract_from_imports<'i>(
+    imports: impl Iterator<Item = (&'i str, &'i str)>,
+) -> Vec<WasmEffect> {
+    let mut effects = imports
+        .map(|(module, name)| effect_of_import(module, name))
+        .collect::<Vec<_>>();
+
+    effects.sort();
+    effects.dedup();
+
+    effects
+}
+
+impl WasmEffect {
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            Self::Logger => EffectKind::Logger,
+            Self::MountedBinary(_) => EffectKind::MountedBinary,
+            Self::CallParameters => EffectKind::CallParameters,
+            Self::Filesystem(_) => EffectKind::Filesystem,
+            Self::Stdio(_) => EffectKind::Stdio,
+            Self::EnvironmentRead(_) => EffectKind::EnvironmentRead,
+            Self::Clock(_) => EffectKind::Clock,
+            Self::Randomness => EffectKind::Randomness,
+            Self::ProcExit => EffectKind::ProcExit,
+            Self::Wasi(_) => EffectKind::Wasi,
+            Self::ModuleImport { .. } => EffectKind::ModuleImport,
+        }
     }
+}
 
-    match name {
-        LOGGER_IMPORT_NAME => Some(WasmEffect::Logger),
-        CALL_PARAMETERS_IMPORT_NAME => None,
-        name => Some(WasmEffect::MountedBinary(name.to_string())),
+impl EffectKind {
+    const ALL: [EffectKind; 11] = [
+        Self::Logger,
+        Self::MountedBinary,
+        Self::CallParameters,
+        Self::Filesystem,
+        Self::Stdio,
+        Self::EnvironmentRead,
+        Self::Clock,
+        Self::Randomness,
+        Self::ProcExit,
+        Self::Wasi,
+        Self::ModuleImport,
+    ];
+
+    /// Name of the kind as it is written in configs and in the JSON output of the CLI.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Logger => "logger",
+            Self::MountedBinary => "mounted_binary",
+            Self::CallParameters => "call_parameters",
+            Self::Filesystem => "filesystem",
+            Self::Stdio => "stdio",
+            Self::EnvironmentRead => "environment_read",
+            Self::Clock => "clock",
+            Self::Randomness => "randomness",
+            Self::ProcExit => "proc_exit",
+            Self::Wasi => "wasi",
+            Self::ModuleImport => "module_import",
+        }
+    }
+}
+
+impl FromStr for EffectKind {
+    type Err = UnknownEffectKind;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|known| known.as_str() == kind)
+            .ok_or_else(|| UnknownEffectKind(kind.to_string()))
+    }
+}
+
+impl fmt::Display for EffectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Returns the effect of a function import given by its namespace and name.
+pub fn effect_of_import(module: &str, name: &str) -> WasmEffect {
+    if is_host_import(module) {
+        return match name {
+            LOGGER_IMPORT_NAME => WasmEffect::Logger,
+            CALL_PARAMETERS_IMPORT_NAME => WasmEffect::CallParameters,
+            name => WasmEffect::MountedBinary(name.to_string()),
+        };
+    }
+
+    if WASI_NAMESPACES.contains(&module) {
+        return inspect_wasi_import(name);
+    }
+
+    WasmEffect::ModuleImport {
+        module: module.to_string(),
+        function: name.to_string(),
+    }
+}
+
+fn inspect_wasi_import(name: &str) -> WasmEffect {
+    let name = name.to_string();
+    match name.as_str() {
+        "proc_exit" => WasmEffect::ProcExit,
+        "random_get" => WasmEffect::Randomness,
+        // preopened dirs are the only way to reach the filesystem
+        n if n.starts_with("path_") || n.starts_with("fd_prestat_") => WasmEffect::Filesystem(name),
+        "fd_readdir"
+        | "fd_filestat_get"
+        | "fd_filestat_set_size"
+        | "fd_filestat_set_times"
+        | "fd_allocate"
+        | "fd_advise" => WasmEffect::Filesystem(name),
+        n if n.starts_with("fd_") => WasmEffect::Stdio(name),
+        n if n.starts_with("environ_") || n.starts_with("args_") => {
+            WasmEffect::EnvironmentRead(name)
+        }
+        n if n.starts_with("clock_") => WasmEffect::Clock(name),
+        _ => WasmEffect::Wasi(name),
     }
 }
 
 fn is_host_import(namespace: &str) -> bool {
     namespace == HOST_IMPORT_NAMESPACE_V0 || namespace.starts_with(HOST_IMPORT_NAMESPACE_PREFIX)
 }
+
+impl fmt::Display for WasmEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Logger => write!(f, "logger"),
+            Self::MountedBinary(name) => write!(f, "mounted binary: {}", name),
+            Self::CallParameters => write!(f, "call parameters"),
+            Self::Filesystem(name) => write!(f, "filesystem: {}", name),
+            Self::Stdio(name) => write!(f, "stdio: {}", name),
+            Self::EnvironmentRead(name) => write!(f, "environment read: {}", name),
+            Self::Clock(name) => write!(f, "clock: {}", name),
+            Self::Randomness => write!(f, "randomness"),
+            Self::ProcExit => write!(f, "process exit"),
+            Self::Wasi(name) => write!(f, "wasi: {}", name),
+            Self::ModuleImport { module, function } => {
+                write!(f, "module import: {}.{}", module, function)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EffectKind;
+    use super::WasmEffect;
+    use super::extract_from_module;
+
+    use walrus::Module;
+
+    fn effects(imports: &[(&str, &str)]) -> Vec<WasmEffect> {
+        let mut module = Module::default();
+        let ty = module.types.add(&[], &[]);
+        for (namespace, name) in imports {
+            module.add_import_func(namespace, name, ty);
+        }
+
+        extract_from_module(&module).unwrap()
+    }
+
+    #[test]
+    fn host_imports() {
+        let effects = effects(&[
+            ("__marine_host_api_v3", "curl"),
+            ("host", "log_utf8_string"),
+            ("__marine_host_api_v3", "get_call_parameters"),
+            ("__marine_host_api_v1", "log_utf8_string"),
+        ]);
+
+        assert_eq!(
+            effects,
+            vec![
+                WasmEffect::Logger,
+                WasmEffect::MountedBinary(String::from("curl")),
+                WasmEffect::CallParameters,
+            ]
+        );
+    }
+
+    #[test]
+    fn wasi_imports() {
+        let effects = effects(&[
+            ("wasi_snapshot_preview1", "fd_write"),
+            ("wasi_snapshot_preview1", "path_open"),
+            ("wasi_snapshot_preview1", "fd_prestat_get"),
+            ("wasi_snapshot_preview1", "environ_get"),
+            ("wasi_snapshot_preview1", "clock_time_get"),
+            ("wasi_snapshot_preview1", "random_get"),
+            ("wasi_snapshot_preview1", "proc_exit"),
+            ("wasi_unstable", "sched_yield"),
+        ]);
+
+        assert_eq!(
+            effects,
+            vec![
+                WasmEffect::Filesystem(String::from("fd_prestat_get")),
+                WasmEffect::Filesystem(String::from("path_open")),
+                WasmEffect::Stdio(String::from("fd_write")),
+                WasmEffect::EnvironmentRead(String::from("environ_get")),
+                WasmEffect::Clock(String::from("clock_time_get")),
+                WasmEffect::Randomness,
+                WasmEffect::ProcExit,
+                WasmEffect::Wasi(String::from("sched_yield")),
+            ]
+        );
+    }
+
+    #[test]
+    fn module_imports() {
+        let effects = effects(&[("local_storage", "put"), ("curl_adapter", "download")]);
+
+        assert_eq!(
+            effects,
+            vec![
+                WasmEffect::ModuleImport {
+                    module: String::from("curl_adapter"),
+                    function: String::from("download"),
+                },
+                WasmEffect::ModuleImport {
+                    module: String::from("local_storage"),
+                    function: String::from("put"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn effect_kinds() {
+        for kind in EffectKind::ALL {
+            assert_eq!(kind.as_str().parse::<EffectKind>().unwrap(), kind);
+        }
+
+        assert!("network".parse::<EffectKind>().is_err());
+        assert_eq!(WasmEffect::Randomness.kind(), EffectKind::Randomness);
+    }
+}
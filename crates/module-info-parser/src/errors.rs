@@ -19,6 +19,8 @@
 
 use crate::manifest::ManifestError;
 use crate::sdk_version::SDKVersionError;
+use crate::log_targets::LogTargetsError;
+use crate::signature::SignatureError;
 
 use thiserror::Error as ThisError;
 
@@ -40,6 +42,14 @@ pub enum ModuleInfoError {
     #[error(transparent)]
     ManifestError(#[from] ManifestError),
 
+    /// Errors related to corrupted log targets declarations.
+    #[error(transparent)]
+    LogTargetsError(#[from] LogTargetsError),
+
+    /// Errors related to a missing or invalid module signature.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+
     /// An error occurred while parsing Wasm file.
     #[error("provided Wasm file is corrupted: {0}")]
     CorruptedWasmFile(anyhow::Error),
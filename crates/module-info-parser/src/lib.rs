@@ -30,6 +30,8 @@
 pub mod manifest;
 pub mod sdk_version;
 pub mod effects;
+pub mod log_targets;
+pub mod signature;
 mod custom_section_extractor;
 mod errors;
 
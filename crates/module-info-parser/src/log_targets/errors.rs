@@ -0,0 +1,48 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use thiserror::Error as ThisError;
+
+use std::num::ParseIntError;
+use std::str::Utf8Error;
+
+#[derive(Debug, ThisError)]
+pub enum LogTargetsError {
+    /// Log targets section can't be parsed to Utf8 string.
+    #[error("embedded to the Wasm file log targets aren't valid UTF8 string: '{0}'")]
+    NotValidUtf8(Utf8Error),
+
+    /// A declaration isn't in the "name=mask" form.
+    #[error("log target declaration '{0}' should be in the 'name=mask' form")]
+    InvalidDeclaration(String),
+
+    /// Mask of a target isn't a non-zero i32.
+    #[error("mask of log target '{0}' should be a non-zero i32 number: {1}")]
+    InvalidMask(String, String),
+
+    /// The same target is declared twice with different masks.
+    #[error("log target '{0}' is declared several times with different masks")]
+    ConflictingDeclarations(String),
+}
+
+impl LogTargetsError {
+    pub(super) fn invalid_mask(name: &str, error: ParseIntError) -> Self {
+        Self::InvalidMask(name.to_string(), error.to_string())
+    }
+}
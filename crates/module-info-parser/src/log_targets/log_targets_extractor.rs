@@ -0,0 +1,158 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::ModuleInfoResult;
+use crate::ModuleInfoError;
+use super::LogTargetsError;
+use crate::extract_custom_sections_by_name;
+
+use marine_wasm_backend_traits::WasmBackend;
+use marine_wasm_backend_traits::Module as WasmModule;
+
+use walrus::ModuleConfig;
+use walrus::Module;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A custom section where a module declares its logging targets as "name=mask" lines, e.g.:
+/// ```ignore
+/// #[link_section = "__fluence_log_targets"]
+/// pub static LOG_TARGETS: [u8; 12] = *b"db=1\nhttp=2\n";
+/// ```
+/// Sections of the same name are concatenated by the linker, so each line must end with '\n'.
+pub const LOG_TARGETS_SECTION_NAME: &str = "__fluence_log_targets";
+
+/// Logging masks of targets declared by a module, by target names.
+pub type LogTargets = BTreeMap<String, i32>;
+
+pub fn extract_from_path<P>(wasm_module_path: P) -> ModuleInfoResult<LogTargets>
+where
+    P: AsRef<Path>,
+{
+    let module = ModuleConfig::new()
+        .parse_file(wasm_module_path)
+        .map_err(ModuleInfoError::CorruptedWasmFile)?;
+
+    extract_from_module(&module)
+}
+
+/// Returns log targets declared by a module, a module without the section declares none.
+pub fn extract_from_module(wasm_module: &Module) -> ModuleInfoResult<LogTargets> {
+    let sections = extract_custom_sections_by_name(wasm_module, LOG_TARGETS_SECTION_NAME)?;
+    let targets = parse_sections(sections.iter().map(|section| section.as_ref()))?;
+
+    Ok(targets)
+}
+
+pub fn extract_from_compiled_module<WB: WasmBackend>(
+    wasm_module: &<WB as WasmBackend>::Module,
+) -> ModuleInfoResult<LogTargets> {
+    let sections = wasm_module.custom_sections(LOG_TARGETS_SECTION_NAME);
+    let targets = parse_sections(sections.iter().map(Vec::as_slice))?;
+
+    Ok(targets)
+}
+
+fn parse_sections<'s>(
+    sections: impl Iterator<Item = &'s [u8]>,
+) -> Result<LogTargets, LogTargetsError> {
+    let mut targets = LogTargets::new();
+
+    for section in sections {
+        let section = std::str::from_utf8(section).map_err(LogTargetsError::NotValidUtf8)?;
+
+        for declaration in section.lines().map(str::trim) {
+            if declaration.is_empty() {
+                continue;
+            }
+
+            let (name, mask) = parse_declaration(declaration)?;
+            match targets.insert(name.to_string(), mask) {
+                Some(previous_mask) if previous_mask != mask => {
+                    return Err(LogTargetsError::ConflictingDeclarations(name.to_string()))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+fn parse_declaration(declaration: &str) -> Result<(&str, i32), LogTargetsError> {
+    let (name, mask) = declaration
+        .split_once('=')
+        .map(|(name, mask)| (name.trim(), mask.trim()))
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| LogTargetsError::InvalidDeclaration(declaration.to_string()))?;
+
+    let mask = mask
+        .parse::<i32>()
+        .map_err(|e| LogTargetsError::invalid_mask(name, e))?;
+    if mask == 0 {
+        return Err(LogTargetsError::InvalidMask(
+            name.to_string(),
+            String::from("0 is reserved for the default target"),
+        ));
+    }
+
+    Ok((name, mask))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sections: &[&str]) -> Result<LogTargets, LogTargetsError> {
+        parse_sections(sections.iter().map(|section| section.as_bytes()))
+    }
+
+    #[test]
+    fn concatenated_sections() {
+        let targets = parse(&["db=1\nhttp=2\n", "db=1\ncache = 4\n"]).unwrap();
+        let expected = LogTargets::from([
+            (String::from("db"), 1),
+            (String::from("http"), 2),
+            (String::from("cache"), 4),
+        ]);
+
+        assert_eq!(targets, expected);
+    }
+
+    #[test]
+    fn invalid_declarations() {
+        assert!(matches!(
+            parse(&["db\n"]),
+            Err(LogTargetsError::InvalidDeclaration(_))
+        ));
+        assert!(matches!(
+            parse(&["db=x\n"]),
+            Err(LogTargetsError::InvalidMask(_, _))
+        ));
+        assert!(matches!(
+            parse(&["db=0\n"]),
+            Err(LogTargetsError::InvalidMask(_, _))
+        ));
+        assert!(matches!(
+            parse(&["db=1\ndb=2\n"]),
+            Err(LogTargetsError::ConflictingDeclarations(_))
+        ));
+    }
+}
@@ -0,0 +1,28 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+mod errors;
+mod log_targets_extractor;
+
+pub use errors::LogTargetsError;
+pub use log_targets_extractor::extract_from_path;
+pub use log_targets_extractor::extract_from_module;
+pub use log_targets_extractor::extract_from_compiled_module;
+pub use log_targets_extractor::LogTargets;
+pub use log_targets_extractor::LOG_TARGETS_SECTION_NAME;
@@ -58,6 +58,26 @@ pub enum ManifestError {
     #[error("embedded manifest is corrupted: there are some trailing characters")]
     ManifestRemainderNotEmpty,
 
+    /// Versioned manifest has format version 0, versions start from 1.
+    #[error("embedded manifest has unsupported format version {0}")]
+    UnsupportedFormatVersion(u64),
+
+    /// Versioned manifest doesn't contain one of the required fields.
+    #[error("embedded manifest doesn't contain required field '{0}'")]
+    MissingRequiredField(&'static str),
+
+    /// Versioned manifest contains the same field several times.
+    #[error("embedded manifest contains field '{0}' several times")]
+    DuplicateField(String),
+
+    /// A custom field has the name of a built-in one, so it can't be encoded.
+    #[error("custom manifest field '{0}' has the name of a built-in field")]
+    ReservedCustomField(String),
+
+    /// A keyword can't be read back the same after encoding.
+    #[error("manifest keyword '{0}' should be non-empty, without ',' and surrounding whitespaces")]
+    InvalidKeyword(String),
+
     /// Error occurred while parsing embedded build time.
     #[error("build time can't be parsed: {0}")]
     DateTimeParseError(#[from] chrono::ParseError),
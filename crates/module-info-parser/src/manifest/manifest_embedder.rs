@@ -0,0 +1,93 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::ModuleManifest;
+use crate::ModuleInfoResult;
+use crate::ModuleInfoError;
+
+use marine_rs_sdk_main::MANIFEST_SECTION_NAME;
+use walrus::ModuleConfig;
+use walrus::CustomSection;
+use walrus::IdsToIndices;
+
+use std::path::Path;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
+pub(super) struct ManifestCustomSection(Vec<u8>);
+
+impl CustomSection for ManifestCustomSection {
+    fn name(&self) -> &str {
+        MANIFEST_SECTION_NAME
+    }
+
+    fn data(&self, _ids_to_indices: &IdsToIndices) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+/// Embed provided manifest to a Wasm module, replacing the existing one.
+pub fn embed_from_module(
+    mut wasm_module: walrus::Module,
+    manifest: &ModuleManifest,
+) -> ModuleInfoResult<walrus::Module> {
+    let custom = ManifestCustomSection(manifest.to_bytes()?);
+
+    delete_manifest_sections(&mut wasm_module);
+    wasm_module.customs.add(custom);
+
+    Ok(wasm_module)
+}
+
+pub fn embed_from_path<I, O>(
+    in_wasm_module_path: I,
+    out_wasm_module_path: O,
+    manifest: &ModuleManifest,
+) -> ModuleInfoResult<()>
+where
+    I: AsRef<Path>,
+    O: AsRef<Path>,
+{
+    let wasm_module = ModuleConfig::new()
+        .parse_file(in_wasm_module_path)
+        .map_err(ModuleInfoError::CorruptedWasmFile)?;
+
+    let mut wasm_module = embed_from_module(wasm_module, manifest)?;
+    wasm_module
+        .emit_wasm_file(out_wasm_module_path)
+        .map_err(ModuleInfoError::WasmEmitError)
+}
+
+fn delete_manifest_sections(wasm_module: &mut walrus::Module) {
+    let manifest_section_ids = wasm_module
+        .customs
+        .iter()
+        .filter_map(|(id, section)| {
+            if section.name() == MANIFEST_SECTION_NAME {
+                Some(id)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for id in manifest_section_ids {
+        wasm_module.customs.delete(id);
+    }
+}
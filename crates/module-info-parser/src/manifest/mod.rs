@@ -18,14 +18,19 @@
  */
 
 mod errors;
+mod manifest_embedder;
 mod manifest_extractor;
 mod module_manifest;
 #[cfg(test)]
 mod tests;
 
 pub use errors::ManifestError;
+pub use manifest_embedder::embed_from_module;
+pub use manifest_embedder::embed_from_path;
 pub use manifest_extractor::extract_from_path;
 pub use manifest_extractor::extract_from_module;
 pub use manifest_extractor::extract_from_compiled_module;
 pub use manifest_extractor::extract_from_bytes;
 pub use module_manifest::ModuleManifest;
+pub use module_manifest::MANIFEST_FORMAT_MARKER;
+pub use module_manifest::MANIFEST_FORMAT_VERSION;
@@ -18,6 +18,14 @@
  */
 
 /// Describes manifest of a Wasm module in the Fluence network.
+///
+/// Two layouts of the manifest section are supported:
+///  - the legacy one is a fixed sequence of length-prefixed fields: authors, version,
+///    description, repository and build time;
+///  - the versioned one starts with [`MANIFEST_FORMAT_MARKER`] and a format version followed by
+///    length-prefixed key-value pairs. Required keys are the same as the legacy fields,
+///    the other keys are optional, and keys unknown to this parser are kept in `custom`.
+///    Newer format versions are read the same way, so that fields they add end up in `custom`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModuleManifest {
     pub authors: String,
@@ -25,42 +33,227 @@ pub struct ModuleManifest {
     pub description: String,
     pub repository: String,
     pub build_time: chrono::DateTime<chrono::FixedOffset>,
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub keywords: Vec<String>,
+    /// User-defined fields and fields added by newer formats.
+    pub custom: BTreeMap<String, String>,
 }
 
 use super::ManifestError;
 
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
 
 type Result<T> = std::result::Result<T, ManifestError>;
 
+/// Starts a versioned manifest. It can't start the legacy one, because a field of such size
+/// is rejected by the legacy parser.
+pub const MANIFEST_FORMAT_MARKER: u64 = u64::MAX;
+
+/// The latest version of the versioned manifest format.
+pub const MANIFEST_FORMAT_VERSION: u64 = 1;
+
+const AUTHORS_KEY: &str = "authors";
+const VERSION_KEY: &str = "version";
+const DESCRIPTION_KEY: &str = "description";
+const REPOSITORY_KEY: &str = "repository";
+const BUILD_TIME_KEY: &str = "build_time";
+const LICENSE_KEY: &str = "license";
+const HOMEPAGE_KEY: &str = "homepage";
+const KEYWORDS_KEY: &str = "keywords";
+
+const BUILT_IN_KEYS: &[&str] = &[
+    AUTHORS_KEY,
+    VERSION_KEY,
+    DESCRIPTION_KEY,
+    REPOSITORY_KEY,
+    BUILD_TIME_KEY,
+    LICENSE_KEY,
+    HOMEPAGE_KEY,
+    KEYWORDS_KEY,
+];
+
+const KEYWORDS_SEPARATOR: char = ',';
+
+impl ModuleManifest {
+    /// Returns true if the manifest can be encoded in the legacy layout without losing fields.
+    pub fn is_legacy_compatible(&self) -> bool {
+        self.license.is_none()
+            && self.homepage.is_none()
+            && self.keywords.is_empty()
+            && self.custom.is_empty()
+    }
+
+    /// Encodes the manifest in the legacy layout if it's possible, so that older readers could
+    /// read it, otherwise in the latest version of the versioned layout.
+    /// Fails if the manifest can't be read back the same: custom fields mustn't reuse names
+    /// of the built-in ones, and keywords mustn't be empty, contain ',' or surrounding whitespaces.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.check_encodable()?;
+
+        let mut encoder = ManifestEncoder::default();
+
+        if self.is_legacy_compatible() {
+            encoder.add_field(self.authors.as_bytes());
+            encoder.add_field(self.version.to_string().as_bytes());
+            encoder.add_field(self.description.as_bytes());
+            encoder.add_field(self.repository.as_bytes());
+            encoder.add_field(self.build_time.to_rfc3339().as_bytes());
+            return Ok(encoder.buffer);
+        }
+
+        encoder.add_u64(MANIFEST_FORMAT_MARKER);
+        encoder.add_u64(MANIFEST_FORMAT_VERSION);
+
+        encoder.add_pair(AUTHORS_KEY, &self.authors);
+        encoder.add_pair(VERSION_KEY, &self.version.to_string());
+        encoder.add_pair(DESCRIPTION_KEY, &self.description);
+        encoder.add_pair(REPOSITORY_KEY, &self.repository);
+        encoder.add_pair(BUILD_TIME_KEY, &self.build_time.to_rfc3339());
+        if let Some(license) = &self.license {
+            encoder.add_pair(LICENSE_KEY, license);
+        }
+        if let Some(homepage) = &self.homepage {
+            encoder.add_pair(HOMEPAGE_KEY, homepage);
+        }
+        if !self.keywords.is_empty() {
+            let keywords = self.keywords.join(&KEYWORDS_SEPARATOR.to_string());
+            encoder.add_pair(KEYWORDS_KEY, &keywords);
+        }
+        for (key, value) in self.custom.iter() {
+            encoder.add_pair(key, value);
+        }
+
+        Ok(encoder.buffer)
+    }
+
+    fn check_encodable(&self) -> Result<()> {
+        if let Some(key) = self
+            .custom
+            .keys()
+            .find(|key| BUILT_IN_KEYS.contains(&key.as_str()))
+        {
+            return Err(ManifestError::ReservedCustomField(key.clone()));
+        }
+
+        let is_valid_keyword = |keyword: &&String| {
+            !keyword.is_empty()
+                && !keyword.contains(KEYWORDS_SEPARATOR)
+                && keyword.trim() == keyword.as_str()
+        };
+        if let Some(keyword) = self.keywords.iter().find(|k| !is_valid_keyword(k)) {
+            return Err(ManifestError::InvalidKeyword(keyword.clone()));
+        }
+
+        Ok(())
+    }
+}
+
 impl TryFrom<&[u8]> for ModuleManifest {
     type Error = ManifestError;
 
-    #[rustfmt::skip]
     fn try_from(value: &[u8]) -> Result<Self> {
-        let (authors, next_offset) = try_extract_field_as_string(value, 0, "authors")?;
-        let (version, next_offset) = try_extract_field_as_version(value, next_offset, "version")?;
-        let (description, next_offset) = try_extract_field_as_string(value, next_offset, "description")?;
-        let (repository, next_offset) = try_extract_field_as_string(value, next_offset, "repository")?;
-        let (build_time, next_offset) = try_extract_field_as_string(value, next_offset, "build time")?;
-
-        if next_offset != value.len() {
-            return Err(ManifestError::ManifestRemainderNotEmpty)
+        let marker = value.get(0..PREFIX_SIZE).map(read_u64);
+        match marker {
+            Some(MANIFEST_FORMAT_MARKER) => try_from_versioned(&value[PREFIX_SIZE..]),
+            _ => try_from_legacy(value),
         }
+    }
+}
 
-        let build_time = chrono::DateTime::parse_from_rfc3339(&build_time)?;
+#[rustfmt::skip]
+fn try_from_legacy(value: &[u8]) -> Result<ModuleManifest> {
+    let (authors, next_offset) = try_extract_field_as_string(value, 0, "authors")?;
+    let (version, next_offset) = try_extract_field_as_version(value, next_offset, "version")?;
+    let (description, next_offset) = try_extract_field_as_string(value, next_offset, "description")?;
+    let (repository, next_offset) = try_extract_field_as_string(value, next_offset, "repository")?;
+    let (build_time, next_offset) = try_extract_field_as_string(value, next_offset, "build time")?;
 
-        let manifest = ModuleManifest {
-            authors,
-            version,
-            description,
-            repository,
-            build_time
-        };
+    if next_offset != value.len() {
+        return Err(ManifestError::ManifestRemainderNotEmpty)
+    }
+
+    let build_time = chrono::DateTime::parse_from_rfc3339(&build_time)?;
 
-        Ok(manifest)
+    let manifest = ModuleManifest {
+        authors,
+        version,
+        description,
+        repository,
+        build_time,
+        license: None,
+        homepage: None,
+        keywords: Vec::new(),
+        custom: BTreeMap::new(),
+    };
+
+    Ok(manifest)
+}
+
+fn try_from_versioned(value: &[u8]) -> Result<ModuleManifest> {
+    if value.len() < PREFIX_SIZE {
+        return Err(ManifestError::NotEnoughBytesForPrefix("format version"));
+    }
+    let format_version = read_u64(&value[0..PREFIX_SIZE]);
+    // newer versions are read as the latest known one, their new fields are kept in custom
+    if format_version == 0 {
+        return Err(ManifestError::UnsupportedFormatVersion(format_version));
+    }
+
+    let mut fields = BTreeMap::new();
+    let mut offset = PREFIX_SIZE;
+    while offset != value.len() {
+        let (key, next_offset) = try_extract_field_as_string(value, offset, "field name")?;
+        let (field, next_offset) = try_extract_field_as_string(value, next_offset, "field value")?;
+        offset = next_offset;
+
+        if fields.contains_key(&key) {
+            return Err(ManifestError::DuplicateField(key));
+        }
+        fields.insert(key, field);
     }
+
+    let mut take_required = |key: &'static str| {
+        fields
+            .remove(key)
+            .ok_or(ManifestError::MissingRequiredField(key))
+    };
+
+    let authors = take_required(AUTHORS_KEY)?;
+    let version = semver::Version::from_str(&take_required(VERSION_KEY)?)?;
+    let description = take_required(DESCRIPTION_KEY)?;
+    let repository = take_required(REPOSITORY_KEY)?;
+    let build_time = chrono::DateTime::parse_from_rfc3339(&take_required(BUILD_TIME_KEY)?)?;
+
+    let license = fields.remove(LICENSE_KEY);
+    let homepage = fields.remove(HOMEPAGE_KEY);
+    let keywords = fields
+        .remove(KEYWORDS_KEY)
+        .map(|keywords| {
+            keywords
+                .split(KEYWORDS_SEPARATOR)
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let manifest = ModuleManifest {
+        authors,
+        version,
+        description,
+        repository,
+        build_time,
+        license,
+        homepage,
+        keywords,
+        custom: fields,
+    };
+
+    Ok(manifest)
 }
 
 fn try_extract_field_as_string(
@@ -106,10 +299,7 @@ fn try_extract_field_len(array: &[u8], field_name: &'static str) -> Result<usize
         return Err(ManifestError::NotEnoughBytesForPrefix(field_name));
     }
 
-    let mut field_len = [0u8; PREFIX_SIZE];
-    field_len.copy_from_slice(&array[0..PREFIX_SIZE]);
-
-    let field_len = u64::from_le_bytes(field_len);
+    let field_len = read_u64(&array[0..PREFIX_SIZE]);
     // TODO: Until we use Wasm32 and compiles our node to x86_64, converting to usize is sound
     if field_len.checked_add(PREFIX_SIZE as u64).is_none()
         || usize::try_from(field_len + PREFIX_SIZE as u64).is_err()
@@ -134,6 +324,13 @@ fn try_extract_field<'a>(
     Ok(field)
 }
 
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut number = [0u8; PREFIX_SIZE];
+    number.copy_from_slice(bytes);
+
+    u64::from_le_bytes(number)
+}
+
 fn try_to_str<'v>(value: &'v [u8], field_name: &'static str) -> Result<&'v str> {
     match std::str::from_utf8(value) {
         Ok(s) => Ok(s),
@@ -141,6 +338,27 @@ fn try_to_str<'v>(value: &'v [u8], field_name: &'static str) -> Result<&'v str>
     }
 }
 
+#[derive(Default)]
+struct ManifestEncoder {
+    buffer: Vec<u8>,
+}
+
+impl ManifestEncoder {
+    fn add_u64(&mut self, number: u64) {
+        self.buffer.extend_from_slice(&number.to_le_bytes());
+    }
+
+    fn add_field(&mut self, field: &[u8]) {
+        self.add_u64(field.len() as u64);
+        self.buffer.extend_from_slice(field);
+    }
+
+    fn add_pair(&mut self, key: &str, value: &str) {
+        self.add_field(key.as_bytes());
+        self.add_field(value.as_bytes());
+    }
+}
+
 use std::fmt;
 
 impl fmt::Display for ModuleManifest {
@@ -149,6 +367,21 @@ impl fmt::Display for ModuleManifest {
         writeln!(f, "version:     {}", self.version)?;
         writeln!(f, "description: {}", self.description)?;
         writeln!(f, "repository:  {}", self.repository)?;
-        write!(f, "build time:  {} UTC", self.build_time)
+        write!(f, "build time:  {} UTC", self.build_time)?;
+
+        if let Some(license) = &self.license {
+            write!(f, "\nlicense:     {}", license)?;
+        }
+        if let Some(homepage) = &self.homepage {
+            write!(f, "\nhomepage:    {}", homepage)?;
+        }
+        if !self.keywords.is_empty() {
+            write!(f, "\nkeywords:    {}", self.keywords.join(", "))?;
+        }
+        for (key, value) in self.custom.iter() {
+            write!(f, "\n{}: {}", key, value)?;
+        }
+
+        Ok(())
     }
 }
@@ -19,7 +19,10 @@
 
 use super::ManifestError;
 use super::ModuleManifest;
+use super::MANIFEST_FORMAT_MARKER;
+use super::MANIFEST_FORMAT_VERSION;
 
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::str::FromStr;
 
@@ -95,6 +98,10 @@ fn test_reading_simple_config() {
         description,
         repository,
         build_time: build_time.into(),
+        license: None,
+        homepage: None,
+        keywords: vec![],
+        custom: BTreeMap::new(),
     };
 
     assert_eq!(actual, expected);
@@ -158,3 +165,139 @@ fn test_not_enough_data_for_field() {
 
     assert_eq!(actual, expected);
 }
+
+fn versioned_manifest(format_version: u64, pairs: &[(&str, &str)]) -> ByteEncoder {
+    let mut array = ByteEncoder::new();
+
+    array.add_u64(MANIFEST_FORMAT_MARKER);
+    array.add_u64(format_version);
+    for (key, value) in pairs {
+        array.add_utf8_field(key);
+        array.add_utf8_field(value);
+    }
+
+    array
+}
+
+#[test]
+fn test_reading_versioned_manifest() {
+    let array = versioned_manifest(
+        1,
+        &[
+            ("authors", "authors"),
+            ("version", "0.1.0"),
+            ("description", "description"),
+            ("repository", "repository"),
+            ("build_time", "2024-01-01T00:00:00+00:00"),
+            ("license", "Apache-2.0"),
+            ("keywords", "storage, sqlite"),
+            ("team", "core"),
+        ],
+    );
+
+    let actual: ModuleManifest = array
+        .as_bytes()
+        .try_into()
+        .expect("module manifest should be deserialized correctly");
+
+    assert_eq!(actual.license.as_deref(), Some("Apache-2.0"));
+    assert_eq!(actual.homepage, None);
+    assert_eq!(actual.keywords, vec!["storage", "sqlite"]);
+    assert_eq!(
+        actual.custom,
+        BTreeMap::from([(String::from("team"), String::from("core"))])
+    );
+}
+
+#[test]
+fn test_encoding_roundtrip() {
+    let mut manifest = ModuleManifest {
+        authors: "authors".to_string(),
+        version: semver::Version::from_str("0.1.0").unwrap(),
+        description: "description".to_string(),
+        repository: "repository".to_string(),
+        build_time: chrono::Utc::now().into(),
+        license: None,
+        homepage: None,
+        keywords: vec![],
+        custom: BTreeMap::new(),
+    };
+
+    // a manifest without additional fields is readable by older parsers
+    let legacy = manifest.to_bytes().unwrap();
+    assert_ne!(&legacy[0..8], &MANIFEST_FORMAT_MARKER.to_le_bytes());
+    assert_eq!(legacy.as_slice().try_into(), Ok(manifest.clone()));
+
+    manifest.homepage = Some("https://fluence.network".to_string());
+    manifest.keywords = vec!["storage".to_string()];
+    manifest
+        .custom
+        .insert("team".to_string(), "core".to_string());
+
+    let versioned = manifest.to_bytes().unwrap();
+    assert_eq!(versioned.as_slice().try_into(), Ok(manifest.clone()));
+
+    // fields which can't be read back the same are rejected
+    let mut colliding = manifest.clone();
+    colliding
+        .custom
+        .insert("license".to_string(), "MIT".to_string());
+    assert_eq!(
+        colliding.to_bytes(),
+        Err(ManifestError::ReservedCustomField("license".to_string()))
+    );
+
+    for keyword in ["key,value", " storage", ""] {
+        let mut invalid = manifest.clone();
+        invalid.keywords.push(keyword.to_string());
+        assert_eq!(
+            invalid.to_bytes(),
+            Err(ManifestError::InvalidKeyword(keyword.to_string()))
+        );
+    }
+}
+
+#[test]
+fn test_reading_newer_format_version() {
+    let array = versioned_manifest(
+        MANIFEST_FORMAT_VERSION + 1,
+        &[
+            ("authors", "authors"),
+            ("version", "0.1.0"),
+            ("description", "description"),
+            ("repository", "repository"),
+            ("build_time", "2024-01-01T00:00:00+00:00"),
+            ("checksum", "sha256:00"),
+        ],
+    );
+
+    let actual: ModuleManifest = array
+        .as_bytes()
+        .try_into()
+        .expect("newer module manifest should be deserialized correctly");
+
+    assert_eq!(
+        actual.custom,
+        BTreeMap::from([(String::from("checksum"), String::from("sha256:00"))])
+    );
+}
+
+#[test]
+fn test_versioned_manifest_errors() {
+    let actual: Result<ModuleManifest, _> = versioned_manifest(0, &[]).as_bytes().try_into();
+    assert_eq!(actual, Err(ManifestError::UnsupportedFormatVersion(0)));
+
+    let actual: Result<ModuleManifest, _> = versioned_manifest(1, &[("version", "0.1.0")])
+        .as_bytes()
+        .try_into();
+    assert_eq!(actual, Err(ManifestError::MissingRequiredField("authors")));
+
+    let actual: Result<ModuleManifest, _> =
+        versioned_manifest(1, &[("authors", "a"), ("authors", "b")])
+            .as_bytes()
+            .try_into();
+    assert_eq!(
+        actual,
+        Err(ManifestError::DuplicateField("authors".to_string()))
+    );
+}
@@ -0,0 +1,39 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError, PartialEq, Eq)]
+pub enum SignatureError {
+    /// Module bytes can't be split into sections.
+    #[error("module can't be split into sections: {0}")]
+    MalformedModule(&'static str),
+
+    /// Signature section has an unexpected size or format version.
+    #[error("embedded signature is corrupted: {0}")]
+    MalformedSignature(&'static str),
+
+    /// Signature doesn't match the module, it was changed after signing.
+    #[error("embedded signature doesn't match the module, it has been changed after signing")]
+    SignatureMismatch,
+
+    /// A key isn't a base64 encoded ed25519 key.
+    #[error("{0} isn't a valid base64 encoded ed25519 key: {1}")]
+    InvalidKey(&'static str, String),
+}
@@ -0,0 +1,29 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+mod errors;
+mod module_signer;
+mod raw_sections;
+
+pub use errors::SignatureError;
+pub use module_signer::sign;
+pub use module_signer::verify;
+pub use module_signer::ModuleSigner;
+pub use module_signer::SigningKey;
+pub use module_signer::SIGNATURE_SECTION_NAME;
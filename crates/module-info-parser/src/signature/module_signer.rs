@@ -0,0 +1,263 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::raw_sections::custom_section;
+use super::raw_sections::raw_sections;
+use super::raw_sections::RawSection;
+use super::raw_sections::WASM_HEADER_SIZE;
+use super::SignatureError;
+use crate::ModuleInfoResult;
+use crate::try_as_one_section;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_compact::KeyPair;
+use ed25519_compact::PublicKey;
+use ed25519_compact::Seed;
+use ed25519_compact::Signature;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A custom section with an ed25519 signature of a module made by `marine sign`.
+/// It contains a format version byte, a public key of the signer and a signature.
+pub const SIGNATURE_SECTION_NAME: &str = "__fluence_signature";
+
+const SIGNATURE_FORMAT_VERSION: u8 = 1;
+const SIGNATURE_SECTION_SIZE: usize = 1 + PublicKey::BYTES + Signature::BYTES;
+
+// TODO: create a common place for this const to use in both module-info-parser and it-parser
+const IT_SECTION_NAME: &str = "interface-types";
+
+/// Separates module signatures from signatures of other messages made by the same key.
+const SIGNATURE_CONTEXT: &[u8] = b"fluence module signature v1";
+
+/// A public key of a module signer, displayed and parsed in base64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModuleSigner(PublicKey);
+
+/// A secret key to sign modules, parsed from a base64 encoded 32-byte seed.
+pub struct SigningKey(KeyPair);
+
+/// Signs a module, the signature covers the code and the IT sections. The other custom sections,
+/// like the manifest or the sdk version, could be changed without invalidating it.
+/// A signature section of a previously signed module is replaced.
+pub fn sign(wasm_bytes: &[u8], key: &SigningKey) -> ModuleInfoResult<Vec<u8>> {
+    let sections = raw_sections(wasm_bytes)?;
+
+    let signature = key.0.sk.sign(signed_message(&sections), None);
+
+    let mut section_content = vec![SIGNATURE_FORMAT_VERSION];
+    section_content.extend_from_slice(key.0.pk.as_ref());
+    section_content.extend_from_slice(signature.as_ref());
+
+    let mut signed_module = wasm_bytes[0..WASM_HEADER_SIZE].to_vec();
+    sections
+        .iter()
+        .filter(|section| section.custom_name != Some(SIGNATURE_SECTION_NAME))
+        .for_each(|section| signed_module.extend_from_slice(section.bytes));
+    signed_module.extend(custom_section(SIGNATURE_SECTION_NAME, &section_content));
+
+    Ok(signed_module)
+}
+
+/// Checks that the module is signed and hasn't been changed since then, returns its signer.
+pub fn verify(wasm_bytes: &[u8]) -> ModuleInfoResult<ModuleSigner> {
+    let sections = raw_sections(wasm_bytes)?;
+
+    let signature_sections = sections
+        .iter()
+        .filter(|section| section.custom_name == Some(SIGNATURE_SECTION_NAME))
+        .map(|section| section.content)
+        .collect::<Vec<_>>();
+    let signature_section = try_as_one_section(&signature_sections, SIGNATURE_SECTION_NAME)?;
+
+    let (signer, signature) = parse_signature_section(signature_section)?;
+    signer
+        .verify(signed_message(&sections), &signature)
+        .map_err(|_| SignatureError::SignatureMismatch)?;
+
+    Ok(ModuleSigner(signer))
+}
+
+fn signed_message(sections: &[RawSection<'_>]) -> Vec<u8> {
+    let mut message = SIGNATURE_CONTEXT.to_vec();
+    sections
+        .iter()
+        .filter(|section| match section.custom_name {
+            None => true,
+            Some(name) => name == IT_SECTION_NAME,
+        })
+        .for_each(|section| message.extend_from_slice(section.bytes));
+
+    message
+}
+
+fn parse_signature_section(section: &[u8]) -> Result<(PublicKey, Signature), SignatureError> {
+    if section.len() != SIGNATURE_SECTION_SIZE {
+        return Err(SignatureError::MalformedSignature(
+            "unexpected section size",
+        ));
+    }
+    if section[0] != SIGNATURE_FORMAT_VERSION {
+        return Err(SignatureError::MalformedSignature(
+            "unsupported format version",
+        ));
+    }
+
+    let (signer, signature) = section[1..].split_at(PublicKey::BYTES);
+    let signer = PublicKey::from_slice(signer)
+        .map_err(|_| SignatureError::MalformedSignature("invalid public key"))?;
+    let signature = Signature::from_slice(signature)
+        .map_err(|_| SignatureError::MalformedSignature("invalid signature"))?;
+
+    Ok((signer, signature))
+}
+
+impl SigningKey {
+    pub fn signer(&self) -> ModuleSigner {
+        ModuleSigner(self.0.pk)
+    }
+}
+
+impl FromStr for ModuleSigner {
+    type Err = SignatureError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let key = decode_key(key, "public key")?;
+        let key = PublicKey::from_slice(&key)
+            .map_err(|e| SignatureError::InvalidKey("public key", e.to_string()))?;
+
+        Ok(Self(key))
+    }
+}
+
+impl FromStr for SigningKey {
+    type Err = SignatureError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let seed = decode_key(key, "secret key")?;
+        let seed = Seed::from_slice(&seed)
+            .map_err(|e| SignatureError::InvalidKey("secret key", e.to_string()))?;
+
+        Ok(Self(KeyPair::from_seed(seed)))
+    }
+}
+
+fn decode_key(key: &str, key_kind: &'static str) -> Result<Vec<u8>, SignatureError> {
+    BASE64
+        .decode(key.trim())
+        .map_err(|e| SignatureError::InvalidKey(key_kind, e.to_string()))
+}
+
+impl fmt::Display for ModuleSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BASE64.encode(self.0.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ModuleInfoError;
+
+    // the smallest valid module with a function and an IT section
+    fn module() -> Vec<u8> {
+        let mut module = b"\0asm\x01\0\0\0".to_vec();
+        // type section with one () -> () function type
+        module.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // function section with one function
+        module.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        // code section with an empty body
+        module.extend_from_slice(&[0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b]);
+        module.extend(custom_section(IT_SECTION_NAME, b"interface types"));
+        module.extend(custom_section("manifest", b"manifest"));
+
+        module
+    }
+
+    fn key(seed: u8) -> SigningKey {
+        BASE64.encode([seed; 32]).parse().unwrap()
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let key = key(1);
+        let signed = sign(&module(), &key).unwrap();
+        assert_eq!(verify(&signed).unwrap(), key.signer());
+
+        // signing again replaces the signature
+        let other_key = self::key(2);
+        let resigned = sign(&signed, &other_key).unwrap();
+        assert_eq!(verify(&resigned).unwrap(), other_key.signer());
+        assert_eq!(resigned.len(), signed.len());
+    }
+
+    #[test]
+    fn unsigned_module() {
+        assert!(matches!(
+            verify(&module()),
+            Err(ModuleInfoError::NoCustomSection(SIGNATURE_SECTION_NAME))
+        ));
+    }
+
+    #[test]
+    fn tampered_module() {
+        let signed = sign(&module(), &key(1)).unwrap();
+
+        // change the code: the end of the function body
+        let mut tampered_code = signed.clone();
+        let body_end_position = WASM_HEADER_SIZE + 6 + 4 + 5;
+        tampered_code[body_end_position] = 0x01;
+        assert!(matches!(
+            verify(&tampered_code),
+            Err(ModuleInfoError::SignatureError(
+                SignatureError::SignatureMismatch
+            ))
+        ));
+
+        // change the IT section
+        let mut tampered_it = signed.clone();
+        let it_position = signed
+            .windows(b"interface types".len())
+            .position(|window| window == b"interface types")
+            .unwrap();
+        tampered_it[it_position] = b'I';
+        assert!(matches!(
+            verify(&tampered_it),
+            Err(ModuleInfoError::SignatureError(
+                SignatureError::SignatureMismatch
+            ))
+        ));
+
+        // the other custom sections aren't signed
+        let mut with_new_section = signed;
+        with_new_section.extend(custom_section("other", b"data"));
+        assert!(verify(&with_new_section).is_ok());
+    }
+
+    #[test]
+    fn signer_in_base64() {
+        let signer = key(1).signer();
+        let parsed: ModuleSigner = signer.to_string().parse().unwrap();
+        assert_eq!(parsed, signer);
+
+        assert!("not a key".parse::<ModuleSigner>().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::SignatureError;
+
+/// Magic and version of a Wasm binary.
+pub(super) const WASM_HEADER_SIZE: usize = 8;
+const CUSTOM_SECTION_ID: u8 = 0;
+
+/// A section of a Wasm binary as it's stored in the file.
+///
+/// Signatures are computed over raw bytes, because walrus doesn't preserve them
+/// when it emits a module.
+pub(super) struct RawSection<'w> {
+    /// Name of a custom section, None for the other sections.
+    pub(super) custom_name: Option<&'w str>,
+    /// The whole section: id, size and payload.
+    pub(super) bytes: &'w [u8],
+    /// Payload of a custom section after its name, the whole payload for the other sections.
+    pub(super) content: &'w [u8],
+}
+
+pub(super) fn raw_sections(wasm_bytes: &[u8]) -> Result<Vec<RawSection<'_>>, SignatureError> {
+    if wasm_bytes.len() < WASM_HEADER_SIZE || &wasm_bytes[0..4] != b"\0asm" {
+        return Err(SignatureError::MalformedModule("there is no Wasm header"));
+    }
+
+    let mut sections = Vec::new();
+    let mut offset = WASM_HEADER_SIZE;
+    while offset < wasm_bytes.len() {
+        let section_start = offset;
+        let id = wasm_bytes[offset];
+        let (payload_size, read) = read_leb128_u32(&wasm_bytes[offset + 1..])?;
+        let payload_start = offset + 1 + read;
+        let payload_end = payload_start
+            .checked_add(payload_size as usize)
+            .filter(|end| *end <= wasm_bytes.len())
+            .ok_or(SignatureError::MalformedModule(
+                "section exceeds the module",
+            ))?;
+        let payload = &wasm_bytes[payload_start..payload_end];
+
+        let (custom_name, content) = if id == CUSTOM_SECTION_ID {
+            let (name, content) = split_custom_section_name(payload)?;
+            (Some(name), content)
+        } else {
+            (None, payload)
+        };
+
+        sections.push(RawSection {
+            custom_name,
+            bytes: &wasm_bytes[section_start..payload_end],
+            content,
+        });
+        offset = payload_end;
+    }
+
+    Ok(sections)
+}
+
+/// Encodes a custom section with the given name and content.
+pub(super) fn custom_section(name: &str, content: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    write_leb128_u32(&mut payload, name.len() as u32);
+    payload.extend_from_slice(name.as_bytes());
+    payload.extend_from_slice(content);
+
+    let mut section = vec![CUSTOM_SECTION_ID];
+    write_leb128_u32(&mut section, payload.len() as u32);
+    section.extend_from_slice(&payload);
+
+    section
+}
+
+fn split_custom_section_name(payload: &[u8]) -> Result<(&str, &[u8]), SignatureError> {
+    let (name_size, read) = read_leb128_u32(payload)?;
+    let name_end = read
+        .checked_add(name_size as usize)
+        .filter(|end| *end <= payload.len())
+        .ok_or(SignatureError::MalformedModule(
+            "custom section name exceeds the section",
+        ))?;
+
+    let name = std::str::from_utf8(&payload[read..name_end])
+        .map_err(|_| SignatureError::MalformedModule("custom section name isn't valid UTF8"))?;
+
+    Ok((name, &payload[name_end..]))
+}
+
+fn read_leb128_u32(bytes: &[u8]) -> Result<(u32, usize), SignatureError> {
+    const MAX_LEB128_U32_SIZE: usize = 5;
+
+    let mut value = 0u32;
+    for (position, byte) in bytes.iter().take(MAX_LEB128_U32_SIZE).enumerate() {
+        value |= ((byte & 0x7f) as u32) << (7 * position);
+        if byte & 0x80 == 0 {
+            return Ok((value, position + 1));
+        }
+    }
+
+    Err(SignatureError::MalformedModule(
+        "section size isn't a valid LEB128 number",
+    ))
+}
+
+fn write_leb128_u32(buffer: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
@@ -72,10 +72,9 @@ impl fmt::Display for FunctionSignature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use itertools::Itertools;
 
-        let (designator, output) = match self.output_types.len() {
-            0 => ("", ""),
-            1 => ("->", self.output_types[0].as_str()),
-            _ => unimplemented!("more than 1 output type is unsupported"),
+        let designator = match self.output_types.len() {
+            0 => "",
+            _ => "->",
         };
 
         let args = self
@@ -83,7 +82,8 @@ impl fmt::Display for FunctionSignature {
             .iter()
             .map(|(name, ty)| format!("{}: {}", name, ty))
             .format(", ");
-        writeln!(f, "{}({}) {} {}", self.name, args, designator, output)
+        let outputs = self.output_types.iter().format(", ");
+        writeln!(f, "{}({}) {} {}", self.name, args, designator, outputs)
     }
 }
 
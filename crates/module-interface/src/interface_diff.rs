@@ -0,0 +1,753 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::interface::itype_text_view;
+use crate::interface::InterfaceError;
+use crate::interface::InterfaceResult;
+use crate::it_interface::IFunctionSignature;
+use crate::it_interface::IModuleInterface;
+use crate::it_interface::IRecordTypes;
+
+use serde::Deserialize;
+use serde::Serialize;
+use wasmer_it::IRecordType;
+use wasmer_it::IType;
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// A change between two versions of a module interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InterfaceChange {
+    FunctionAdded {
+        function: String,
+    },
+    FunctionRemoved {
+        function: String,
+    },
+    /// Arguments were added, removed, renamed or reordered.
+    ArgumentsChanged {
+        function: String,
+        old_arguments: Vec<(String, String)>,
+        new_arguments: Vec<(String, String)>,
+    },
+    ArgumentTypeChanged {
+        function: String,
+        argument: String,
+        old_type: String,
+        new_type: String,
+    },
+    ResultTypesChanged {
+        function: String,
+        old_types: Vec<String>,
+        new_types: Vec<String>,
+    },
+    RecordAdded {
+        record: String,
+    },
+    RecordRemoved {
+        record: String,
+    },
+    /// A record got a new name, but kept its fields.
+    RecordRenamed {
+        old_name: String,
+        new_name: String,
+    },
+    RecordFieldAdded {
+        record: String,
+        field: String,
+        ty: String,
+    },
+    RecordFieldRemoved {
+        record: String,
+        field: String,
+    },
+    RecordFieldTypeChanged {
+        record: String,
+        field: String,
+        old_type: String,
+        new_type: String,
+    },
+    RecordFieldsReordered {
+        record: String,
+        old_order: Vec<String>,
+        new_order: Vec<String>,
+    },
+}
+
+impl InterfaceChange {
+    /// Returns true if calls made by existing callers could fail after this change.
+    ///
+    /// Records are passed between modules field by field, so any change of record fields,
+    /// including their order, is breaking. Renaming a record changes neither its layout
+    /// nor its JSON representation.
+    pub fn is_breaking(&self) -> bool {
+        !matches!(
+            self,
+            InterfaceChange::FunctionAdded { .. }
+                | InterfaceChange::RecordAdded { .. }
+                | InterfaceChange::RecordRenamed { .. }
+        )
+    }
+}
+
+/// Compares exported functions and records of two versions of a module interface.
+/// Changes are returned in a deterministic order: records first, then functions,
+/// each sorted by name.
+pub fn diff_interfaces(
+    old: &IModuleInterface,
+    new: &IModuleInterface,
+) -> InterfaceResult<Vec<InterfaceChange>> {
+    let mut differ = InterfaceDiffer {
+        old_records: &old.export_record_types,
+        new_records: &new.export_record_types,
+        renames: HashMap::new(),
+        changes: Vec::new(),
+    };
+
+    differ.diff_records()?;
+    differ.diff_functions(&old.function_signatures, &new.function_signatures)?;
+
+    Ok(differ.changes)
+}
+
+struct InterfaceDiffer<'i> {
+    old_records: &'i IRecordTypes,
+    new_records: &'i IRecordTypes,
+    /// Old names of renamed records mapped to the new ones.
+    renames: HashMap<String, String>,
+    changes: Vec<InterfaceChange>,
+}
+
+impl<'i> InterfaceDiffer<'i> {
+    fn diff_records(&mut self) -> InterfaceResult<()> {
+        let old_records = records_by_name(self.old_records);
+        let new_records = records_by_name(self.new_records);
+
+        self.find_renames(&old_records, &new_records)?;
+
+        for (name, old_record) in old_records.iter() {
+            let new_name = self.renames.get(*name).map(String::as_str);
+            match new_records.get(new_name.unwrap_or(name)) {
+                Some(new_record) => {
+                    if let Some(new_name) = new_name {
+                        self.changes.push(InterfaceChange::RecordRenamed {
+                            old_name: name.to_string(),
+                            new_name: new_name.to_string(),
+                        });
+                    }
+                    self.diff_record_fields(old_record, new_record)?;
+                }
+                None => self.changes.push(InterfaceChange::RecordRemoved {
+                    record: name.to_string(),
+                }),
+            }
+        }
+
+        for name in new_records.keys() {
+            let is_renamed = self.renames.values().any(|new_name| new_name == name);
+            if !old_records.contains_key(name) && !is_renamed {
+                self.changes.push(InterfaceChange::RecordAdded {
+                    record: name.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A removed record is considered renamed if there is exactly one added record
+    /// with the same fields.
+    fn find_renames(
+        &mut self,
+        old_records: &Records<'i>,
+        new_records: &Records<'i>,
+    ) -> InterfaceResult<()> {
+        let added = new_records
+            .iter()
+            .filter(|(name, _)| !old_records.contains_key(*name))
+            .map(|(name, record)| Ok((*name, self.fields_view(record, self.new_records)?)))
+            .collect::<InterfaceResult<Vec<_>>>()?;
+
+        for (name, old_record) in old_records.iter() {
+            if new_records.contains_key(name) {
+                continue;
+            }
+
+            let old_fields = self.fields_view(old_record, self.old_records)?;
+            let mut candidates = added.iter().filter(|(new_name, fields)| {
+                fields == &old_fields && !self.renames.values().any(|name| name == new_name)
+            });
+            if let (Some((new_name, _)), None) = (candidates.next(), candidates.next()) {
+                self.renames.insert(name.to_string(), new_name.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn diff_record_fields(
+        &mut self,
+        old_record: &IRecordType,
+        new_record: &IRecordType,
+    ) -> InterfaceResult<()> {
+        let record = &new_record.name;
+
+        for old_field in old_record.fields.iter() {
+            let new_field = new_record
+                .fields
+                .iter()
+                .find(|field| field.name == old_field.name);
+
+            match new_field {
+                Some(new_field) => {
+                    let old_type = self.old_type(&old_field.ty)?;
+                    let new_type = self.new_type(&new_field.ty)?;
+                    if old_type != new_type {
+                        self.changes.push(InterfaceChange::RecordFieldTypeChanged {
+                            record: record.clone(),
+                            field: old_field.name.clone(),
+                            old_type,
+                            new_type,
+                        });
+                    }
+                }
+                None => self.changes.push(InterfaceChange::RecordFieldRemoved {
+                    record: record.clone(),
+                    field: old_field.name.clone(),
+                }),
+            }
+        }
+
+        for new_field in new_record.fields.iter() {
+            let is_added = !old_record
+                .fields
+                .iter()
+                .any(|field| field.name == new_field.name);
+            if is_added {
+                self.changes.push(InterfaceChange::RecordFieldAdded {
+                    record: record.clone(),
+                    field: new_field.name.clone(),
+                    ty: self.new_type(&new_field.ty)?,
+                });
+            }
+        }
+
+        // compare the relative order of fields present in both versions
+        let old_order = common_field_names(old_record, new_record);
+        let new_order = common_field_names(new_record, old_record);
+        if old_order != new_order {
+            self.changes.push(InterfaceChange::RecordFieldsReordered {
+                record: record.clone(),
+                old_order,
+                new_order,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn diff_functions(
+        &mut self,
+        old_functions: &[IFunctionSignature],
+        new_functions: &[IFunctionSignature],
+    ) -> InterfaceResult<()> {
+        let mut old_functions = old_functions.iter().collect::<Vec<_>>();
+        old_functions.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        let mut new_functions = new_functions.iter().collect::<Vec<_>>();
+        new_functions.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+        for old_function in old_functions.iter() {
+            let new_function = new_functions
+                .iter()
+                .find(|function| function.name == old_function.name);
+
+            match new_function {
+                Some(new_function) => self.diff_function(old_function, new_function)?,
+                None => self.changes.push(InterfaceChange::FunctionRemoved {
+                    function: old_function.name.to_string(),
+                }),
+            }
+        }
+
+        for new_function in new_functions {
+            let is_added = !old_functions
+                .iter()
+                .any(|function| function.name == new_function.name);
+            if is_added {
+                self.changes.push(InterfaceChange::FunctionAdded {
+                    function: new_function.name.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn diff_function(
+        &mut self,
+        old_function: &IFunctionSignature,
+        new_function: &IFunctionSignature,
+    ) -> InterfaceResult<()> {
+        let function = new_function.name.to_string();
+
+        let same_argument_names = old_function.arguments.len() == new_function.arguments.len()
+            && old_function
+                .arguments
+                .iter()
+                .zip(new_function.arguments.iter())
+                .all(|(old_arg, new_arg)| old_arg.name == new_arg.name);
+
+        if same_argument_names {
+            for (old_arg, new_arg) in old_function
+                .arguments
+                .iter()
+                .zip(new_function.arguments.iter())
+            {
+                let old_type = self.old_type(&old_arg.ty)?;
+                let new_type = self.new_type(&new_arg.ty)?;
+                if old_type != new_type {
+                    self.changes.push(InterfaceChange::ArgumentTypeChanged {
+                        function: function.clone(),
+                        argument: new_arg.name.clone(),
+                        old_type,
+                        new_type,
+                    });
+                }
+            }
+        } else {
+            let old_arguments = old_function
+                .arguments
+                .iter()
+                .map(|arg| Ok((arg.name.clone(), self.old_type(&arg.ty)?)))
+                .collect::<InterfaceResult<_>>()?;
+            let new_arguments = new_function
+                .arguments
+                .iter()
+                .map(|arg| Ok((arg.name.clone(), self.new_type(&arg.ty)?)))
+                .collect::<InterfaceResult<_>>()?;
+
+            self.changes.push(InterfaceChange::ArgumentsChanged {
+                function: function.clone(),
+                old_arguments,
+                new_arguments,
+            });
+        }
+
+        let old_types = old_function
+            .outputs
+            .iter()
+            .map(|ty| self.old_type(ty))
+            .collect::<InterfaceResult<Vec<_>>>()?;
+        let new_types = new_function
+            .outputs
+            .iter()
+            .map(|ty| self.new_type(ty))
+            .collect::<InterfaceResult<Vec<_>>>()?;
+        if old_types != new_types {
+            self.changes.push(InterfaceChange::ResultTypesChanged {
+                function,
+                old_types,
+                new_types,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns a text view of an old type with renamed records replaced by their new names,
+    /// so that it could be compared with new types.
+    fn old_type(&self, ty: &IType) -> InterfaceResult<String> {
+        type_view(ty, self.old_records, &self.renames)
+    }
+
+    fn new_type(&self, ty: &IType) -> InterfaceResult<String> {
+        type_view(ty, self.new_records, &HashMap::new())
+    }
+
+    fn fields_view(
+        &self,
+        record: &IRecordType,
+        record_types: &IRecordTypes,
+    ) -> InterfaceResult<Vec<(String, String)>> {
+        record
+            .fields
+            .iter()
+            .map(|field| {
+                let ty = type_view(&field.ty, record_types, &HashMap::new())?;
+                Ok((field.name.clone(), ty))
+            })
+            .collect()
+    }
+}
+
+type Records<'i> = std::collections::BTreeMap<&'i str, &'i IRecordType>;
+
+fn records_by_name(record_types: &IRecordTypes) -> Records<'_> {
+    record_types
+        .values()
+        .map(|record| (record.name.as_str(), record.as_ref()))
+        .collect()
+}
+
+fn common_field_names(record: &IRecordType, other: &IRecordType) -> Vec<String> {
+    record
+        .fields
+        .iter()
+        .filter(|field| other.fields.iter().any(|other| other.name == field.name))
+        .map(|field| field.name.clone())
+        .collect()
+}
+
+fn type_view(
+    ty: &IType,
+    record_types: &IRecordTypes,
+    renames: &HashMap<String, String>,
+) -> InterfaceResult<String> {
+    match ty {
+        IType::Record(record_id) => {
+            let record = record_types
+                .get(record_id)
+                .ok_or(InterfaceError::NotFoundRecordTypeId(*record_id))?;
+            let name = renames.get(&record.name).unwrap_or(&record.name);
+            Ok(name.clone())
+        }
+        IType::Array(ty) => Ok(format!("[]{}", type_view(ty, record_types, renames)?)),
+        ty => Ok(itype_text_view(ty, record_types)),
+    }
+}
+
+impl fmt::Display for InterfaceChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use itertools::Itertools;
+
+        let arguments_view = |arguments: &Vec<(String, String)>| {
+            arguments
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", name, ty))
+                .join(", ")
+        };
+
+        match self {
+            InterfaceChange::FunctionAdded { function } => {
+                write!(f, "function {} was added", function)
+            }
+            InterfaceChange::FunctionRemoved { function } => {
+                write!(f, "function {} was removed", function)
+            }
+            InterfaceChange::ArgumentsChanged {
+                function,
+                old_arguments,
+                new_arguments,
+            } => write!(
+                f,
+                "arguments of function {} changed from ({}) to ({})",
+                function,
+                arguments_view(old_arguments),
+                arguments_view(new_arguments)
+            ),
+            InterfaceChange::ArgumentTypeChanged {
+                function,
+                argument,
+                old_type,
+                new_type,
+            } => write!(
+                f,
+                "type of argument {} of function {} changed from {} to {}",
+                argument, function, old_type, new_type
+            ),
+            InterfaceChange::ResultTypesChanged {
+                function,
+                old_types,
+                new_types,
+            } => write!(
+                f,
+                "results of function {} changed from ({}) to ({})",
+                function,
+                old_types.iter().join(", "),
+                new_types.iter().join(", ")
+            ),
+            InterfaceChange::RecordAdded { record } => write!(f, "record {} was added", record),
+            InterfaceChange::RecordRemoved { record } => {
+                write!(f, "record {} was removed", record)
+            }
+            InterfaceChange::RecordRenamed { old_name, new_name } => {
+                write!(f, "record {} was renamed to {}", old_name, new_name)
+            }
+            InterfaceChange::RecordFieldAdded { record, field, ty } => {
+                write!(f, "field {}: {} was added to record {}", field, ty, record)
+            }
+            InterfaceChange::RecordFieldRemoved { record, field } => {
+                write!(f, "field {} was removed from record {}", field, record)
+            }
+            InterfaceChange::RecordFieldTypeChanged {
+                record,
+                field,
+                old_type,
+                new_type,
+            } => write!(
+                f,
+                "type of field {} of record {} changed from {} to {}",
+                field, record, old_type, new_type
+            ),
+            InterfaceChange::RecordFieldsReordered {
+                record,
+                old_order,
+                new_order,
+            } => write!(
+                f,
+                "fields of record {} were reordered from ({}) to ({})",
+                record,
+                old_order.iter().join(", "),
+                new_order.iter().join(", ")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wasmer_it::ast::FunctionArg as IFunctionArg;
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    fn record(name: &str, fields: &[(&str, IType)]) -> Arc<IRecordType> {
+        let fields = fields
+            .iter()
+            .map(|(name, ty)| IRecordFieldType {
+                name: name.to_string(),
+                ty: ty.clone(),
+            })
+            .collect();
+
+        Arc::new(IRecordType {
+            name: name.to_string(),
+            fields: NEVec::new(fields).unwrap(),
+        })
+    }
+
+    fn function(name: &str, arguments: &[(&str, IType)], outputs: &[IType]) -> IFunctionSignature {
+        let arguments = arguments
+            .iter()
+            .map(|(name, ty)| IFunctionArg {
+                name: name.to_string(),
+                ty: ty.clone(),
+            })
+            .collect();
+
+        IFunctionSignature {
+            name: Arc::new(name.to_string()),
+            arguments: Arc::new(arguments),
+            outputs: Arc::new(outputs.to_vec()),
+            adapter_function_type: 0,
+        }
+    }
+
+    fn interface(
+        records: Vec<Arc<IRecordType>>,
+        function_signatures: Vec<IFunctionSignature>,
+    ) -> IModuleInterface {
+        let record_types = records
+            .into_iter()
+            .enumerate()
+            .map(|(id, record)| (id as u64, record))
+            .collect::<IRecordTypes>();
+
+        IModuleInterface {
+            export_record_types: record_types.clone(),
+            record_types,
+            function_signatures,
+        }
+    }
+
+    #[test]
+    fn same_interfaces() {
+        let old = interface(
+            vec![record("User", &[("name", IType::String)])],
+            vec![function(
+                "get_user",
+                &[("id", IType::U64)],
+                &[IType::Record(0)],
+            )],
+        );
+
+        assert_eq!(diff_interfaces(&old, &old.clone()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn function_changes() {
+        let old = interface(
+            vec![],
+            vec![
+                function("removed", &[], &[]),
+                function("args", &[("a", IType::U8), ("b", IType::String)], &[]),
+                function("arg_type", &[("a", IType::U8)], &[IType::String]),
+            ],
+        );
+        let new = interface(
+            vec![],
+            vec![
+                function("args", &[("b", IType::String), ("a", IType::U8)], &[]),
+                function(
+                    "arg_type",
+                    &[("a", IType::U16)],
+                    &[IType::String, IType::U8],
+                ),
+                function("added", &[], &[]),
+            ],
+        );
+
+        let changes = diff_interfaces(&old, &new).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                InterfaceChange::ArgumentTypeChanged {
+                    function: String::from("arg_type"),
+                    argument: String::from("a"),
+                    old_type: String::from("u8"),
+                    new_type: String::from("u16"),
+                },
+                InterfaceChange::ResultTypesChanged {
+                    function: String::from("arg_type"),
+                    old_types: vec![String::from("string")],
+                    new_types: vec![String::from("string"), String::from("u8")],
+                },
+                InterfaceChange::ArgumentsChanged {
+                    function: String::from("args"),
+                    old_arguments: vec![
+                        (String::from("a"), String::from("u8")),
+                        (String::from("b"), String::from("string")),
+                    ],
+                    new_arguments: vec![
+                        (String::from("b"), String::from("string")),
+                        (String::from("a"), String::from("u8")),
+                    ],
+                },
+                InterfaceChange::FunctionRemoved {
+                    function: String::from("removed"),
+                },
+                InterfaceChange::FunctionAdded {
+                    function: String::from("added"),
+                },
+            ]
+        );
+        assert!(changes[..4].iter().all(InterfaceChange::is_breaking));
+        assert!(!changes[4].is_breaking());
+    }
+
+    #[test]
+    fn record_field_changes() {
+        let old = interface(
+            vec![record(
+                "User",
+                &[
+                    ("name", IType::String),
+                    ("age", IType::U8),
+                    ("email", IType::String),
+                    ("removed", IType::Boolean),
+                ],
+            )],
+            vec![function("get_user", &[], &[IType::Record(0)])],
+        );
+        let new = interface(
+            vec![record(
+                "User",
+                &[
+                    ("age", IType::U16),
+                    ("name", IType::String),
+                    ("email", IType::String),
+                    ("added", IType::ByteArray),
+                ],
+            )],
+            vec![function("get_user", &[], &[IType::Record(0)])],
+        );
+
+        let changes = diff_interfaces(&old, &new).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                InterfaceChange::RecordFieldTypeChanged {
+                    record: String::from("User"),
+                    field: String::from("age"),
+                    old_type: String::from("u8"),
+                    new_type: String::from("u16"),
+                },
+                InterfaceChange::RecordFieldRemoved {
+                    record: String::from("User"),
+                    field: String::from("removed"),
+                },
+                InterfaceChange::RecordFieldAdded {
+                    record: String::from("User"),
+                    field: String::from("added"),
+                    ty: String::from("[]u8"),
+                },
+                InterfaceChange::RecordFieldsReordered {
+                    record: String::from("User"),
+                    old_order: vec![
+                        String::from("name"),
+                        String::from("age"),
+                        String::from("email")
+                    ],
+                    new_order: vec![
+                        String::from("age"),
+                        String::from("name"),
+                        String::from("email")
+                    ],
+                },
+            ]
+        );
+        assert!(changes.iter().all(InterfaceChange::is_breaking));
+    }
+
+    #[test]
+    fn renamed_record() {
+        let fields = [("name", IType::String), ("age", IType::U8)];
+        let old = interface(
+            vec![record("User", &fields)],
+            vec![function(
+                "get_users",
+                &[],
+                &[IType::Array(Box::new(IType::Record(0)))],
+            )],
+        );
+        let new = interface(
+            vec![record("Person", &fields)],
+            vec![function(
+                "get_users",
+                &[],
+                &[IType::Array(Box::new(IType::Record(0)))],
+            )],
+        );
+
+        let changes = diff_interfaces(&old, &new).unwrap();
+        assert_eq!(
+            changes,
+            vec![InterfaceChange::RecordRenamed {
+                old_name: String::from("User"),
+                new_name: String::from("Person"),
+            }]
+        );
+        assert!(!changes[0].is_breaking());
+    }
+}
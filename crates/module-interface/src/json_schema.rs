@@ -0,0 +1,475 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::interface::InterfaceError;
+use crate::interface::InterfaceResult;
+use crate::it_interface::IModuleInterface;
+use crate::it_interface::IRecordTypes;
+
+use serde::Serialize;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::Value as JValue;
+use wasmer_it::IType;
+use wasmer_it::ast::FunctionArg as IFunctionArg;
+
+use std::collections::HashMap;
+
+const JSON_SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// JSON Schemas of arguments and results of an exported function.
+///
+/// The arguments schema accepts the same json as `it_json_serde::json_to_ivalues`:
+/// arguments could be passed as an object, as an array, as a sole value if the function
+/// has one scalar argument and as null if the function has no arguments.
+/// The only difference is that integers written with a fractional part, like `1.0`,
+/// are accepted by the schema, but rejected by `json_to_ivalues`.
+///
+/// The results schema describes json produced by `it_json_serde::ivalues_to_json`.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionSchema {
+    pub name: String,
+    pub arguments: JValue,
+    pub results: JValue,
+}
+
+/// Returns JSON Schemas of all exported functions of a module.
+pub fn module_json_schema(interface: &IModuleInterface) -> InterfaceResult<Vec<FunctionSchema>> {
+    interface
+        .function_signatures
+        .iter()
+        .map(|signature| {
+            function_json_schema(
+                &signature.name,
+                &signature.arguments,
+                &signature.outputs,
+                &interface.record_types,
+            )
+        })
+        .collect()
+}
+
+/// Returns JSON Schemas of arguments and results of a function with the provided signature.
+pub fn function_json_schema(
+    name: &str,
+    arguments: &[IFunctionArg],
+    outputs: &[IType],
+    record_types: &IRecordTypes,
+) -> InterfaceResult<FunctionSchema> {
+    let arguments_schema =
+        SchemaBuilder::new(Direction::Arguments, record_types).arguments_schema(name, arguments)?;
+    let results_schema =
+        SchemaBuilder::new(Direction::Results, record_types).results_schema(name, outputs)?;
+
+    Ok(FunctionSchema {
+        name: name.to_string(),
+        arguments: arguments_schema,
+        results: results_schema,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Arguments,
+    Results,
+}
+
+struct SchemaBuilder<'r> {
+    direction: Direction,
+    record_types: &'r IRecordTypes,
+    definitions: serde_json::Map<String, JValue>,
+    definition_names: HashMap<u64, String>,
+}
+
+impl<'r> SchemaBuilder<'r> {
+    fn new(direction: Direction, record_types: &'r IRecordTypes) -> Self {
+        Self {
+            direction,
+            record_types,
+            definitions: serde_json::Map::new(),
+            definition_names: HashMap::new(),
+        }
+    }
+
+    fn arguments_schema(
+        mut self,
+        function_name: &str,
+        arguments: &[IFunctionArg],
+    ) -> InterfaceResult<JValue> {
+        let fields = arguments
+            .iter()
+            .map(|arg| Ok((arg.name.as_str(), self.value_schema(&arg.ty)?)))
+            .collect::<InterfaceResult<Vec<_>>>()?;
+
+        let mut forms = vec![object_schema(&fields), tuple_schema(&fields)];
+        match arguments {
+            [] => forms.push(json!({ "type": "null" })),
+            // a sole scalar argument could be passed as is
+            [arg] if is_scalar(&arg.ty) => forms.push(fields[0].1.clone()),
+            _ => {}
+        }
+
+        let schema = json!({ "anyOf": forms });
+        Ok(self.finish(format!("{} arguments", function_name), schema))
+    }
+
+    fn results_schema(mut self, function_name: &str, outputs: &[IType]) -> InterfaceResult<JValue> {
+        let schema = match outputs {
+            [] => json!({ "type": "null" }),
+            [output] => self.value_schema(output)?,
+            // multi-values are represented as an array
+            outputs => {
+                let fields = outputs
+                    .iter()
+                    .map(|output| Ok(("", self.value_schema(output)?)))
+                    .collect::<InterfaceResult<Vec<_>>>()?;
+                tuple_schema(&fields)
+            }
+        };
+
+        Ok(self.finish(format!("{} results", function_name), schema))
+    }
+
+    fn finish(self, title: String, schema: JValue) -> JValue {
+        let mut result = serde_json::Map::new();
+        result.insert("$schema".to_string(), json!(JSON_SCHEMA_DRAFT));
+        result.insert("title".to_string(), json!(title));
+        if let JValue::Object(schema) = schema {
+            result.extend(schema);
+        }
+        if !self.definitions.is_empty() {
+            result.insert("definitions".to_string(), JValue::Object(self.definitions));
+        }
+
+        JValue::Object(result)
+    }
+
+    fn value_schema(&mut self, ty: &IType) -> InterfaceResult<JValue> {
+        let schema = match ty {
+            IType::Boolean => self.scalar_schema(json!({ "type": "boolean" })),
+            IType::S8 => self.integer_schema(i8::MIN, i8::MAX),
+            IType::S16 => self.integer_schema(i16::MIN, i16::MAX),
+            IType::S32 | IType::I32 => self.integer_schema(i32::MIN, i32::MAX),
+            IType::S64 | IType::I64 => self.integer_schema(i64::MIN, i64::MAX),
+            IType::U8 => self.integer_schema(u8::MIN, u8::MAX),
+            IType::U16 => self.integer_schema(u16::MIN, u16::MAX),
+            IType::U32 => self.integer_schema(u32::MIN, u32::MAX),
+            IType::U64 => self.integer_schema(u64::MIN, u64::MAX),
+            IType::F32 | IType::F64 => self.scalar_schema(json!({ "type": "number" })),
+            IType::String => self.scalar_schema(json!({ "type": "string" })),
+            IType::ByteArray => json!({
+                "type": "array",
+                "items": self.value_schema(&IType::U8)?,
+            }),
+            IType::Array(value_type) => json!({
+                "type": "array",
+                "items": self.value_schema(value_type)?,
+            }),
+            IType::Record(record_type_id) => self.record_schema(*record_type_id)?,
+        };
+
+        Ok(schema)
+    }
+
+    fn integer_schema(&self, min: impl Into<JValue>, max: impl Into<JValue>) -> JValue {
+        self.scalar_schema(json!({
+            "type": "integer",
+            "minimum": min.into(),
+            "maximum": max.into(),
+        }))
+    }
+
+    fn scalar_schema(&self, schema: JValue) -> JValue {
+        match self.direction {
+            // json_to_ivalues implicitly flattens arrays with one scalar element
+            Direction::Arguments => json!({
+                "anyOf": [
+                    schema,
+                    {
+                        "type": "array",
+                        "items": schema,
+                        "minItems": 1,
+                        "maxItems": 1,
+                    },
+                ]
+            }),
+            Direction::Results => schema,
+        }
+    }
+
+    /// Returns a reference to a record definition, adding the definition if it's absent.
+    fn record_schema(&mut self, record_type_id: u64) -> InterfaceResult<JValue> {
+        if let Some(name) = self.definition_names.get(&record_type_id) {
+            return Ok(definition_ref(name));
+        }
+
+        let record_type = self
+            .record_types
+            .get(&record_type_id)
+            .ok_or(InterfaceError::NotFoundRecordTypeId(record_type_id))?
+            .clone();
+
+        let mut name = record_type.name.clone();
+        if self.definitions.contains_key(&name) {
+            name = format!("{}_{}", name, record_type_id);
+        }
+        self.definition_names.insert(record_type_id, name.clone());
+        // reserve the name before fields, nested records are added to definitions by them
+        self.definitions.insert(name.clone(), JValue::Null);
+
+        let fields = record_type
+            .fields
+            .iter()
+            .map(|field| Ok((field.name.as_str(), self.value_schema(&field.ty)?)))
+            .collect::<InterfaceResult<Vec<_>>>()?;
+
+        let mut definition = match self.direction {
+            // records are accepted both as objects and as arrays of fields
+            Direction::Arguments => json!({
+                "anyOf": [object_schema(&fields), tuple_schema(&fields)]
+            }),
+            Direction::Results => object_schema(&fields),
+        };
+        definition["title"] = json!(record_type.name);
+        self.definitions.insert(name.clone(), definition);
+
+        Ok(definition_ref(&name))
+    }
+}
+
+fn object_schema(fields: &[(&str, JValue)]) -> JValue {
+    let properties = fields
+        .iter()
+        .map(|(name, schema)| (name.to_string(), schema.clone()))
+        .collect::<serde_json::Map<_, _>>();
+    let required = fields.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn tuple_schema(fields: &[(&str, JValue)]) -> JValue {
+    // items must be a non-empty array in draft-07
+    if fields.is_empty() {
+        return json!({ "type": "array", "maxItems": 0 });
+    }
+
+    let items = fields
+        .iter()
+        .map(|(_, schema)| schema.clone())
+        .collect::<Vec<_>>();
+
+    json!({
+        "type": "array",
+        "items": items,
+        "additionalItems": false,
+        "minItems": fields.len(),
+    })
+}
+
+fn definition_ref(name: &str) -> JValue {
+    json!({ "$ref": format!("#/definitions/{}", name) })
+}
+
+fn is_scalar(ty: &IType) -> bool {
+    !matches!(ty, IType::ByteArray | IType::Array(_) | IType::Record(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use it_json_serde::ivalues_to_json;
+    use it_json_serde::json_to_ivalues;
+    use jsonschema::JSONSchema;
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+    use wasmer_it::IValue;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    fn record_types() -> IRecordTypes {
+        let item = IRecordType {
+            name: String::from("Item"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("name"),
+                    ty: IType::String,
+                },
+                IRecordFieldType {
+                    name: String::from("count"),
+                    ty: IType::U8,
+                },
+            ])
+            .unwrap(),
+        };
+        let order = IRecordType {
+            name: String::from("Order"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("items"),
+                    ty: IType::Array(Box::new(IType::Record(0))),
+                },
+                IRecordFieldType {
+                    name: String::from("data"),
+                    ty: IType::ByteArray,
+                },
+            ])
+            .unwrap(),
+        };
+
+        IRecordTypes::from([(0, Arc::new(item)), (1, Arc::new(order))])
+    }
+
+    fn arg(name: &str, ty: IType) -> IFunctionArg {
+        IFunctionArg {
+            name: name.to_string(),
+            ty,
+        }
+    }
+
+    /// Checks that the arguments schema accepts exactly the same json as json_to_ivalues.
+    fn check_arguments(arguments: &[IFunctionArg], samples: &[JValue]) {
+        let record_types = record_types();
+        let schema = function_json_schema("f", arguments, &[], &record_types).unwrap();
+        let validator = JSONSchema::compile(&schema.arguments).unwrap();
+
+        for sample in samples {
+            let converted = json_to_ivalues(
+                sample.clone(),
+                arguments.iter().map(|arg| (&arg.name, &arg.ty)),
+                &record_types,
+            );
+            assert_eq!(
+                validator.is_valid(sample),
+                converted.is_ok(),
+                "sample {} is accepted differently: {:?}",
+                sample,
+                converted
+            );
+        }
+    }
+
+    #[test]
+    fn arguments_schema_matches_conversion() {
+        let arguments = [
+            arg("id", IType::U32),
+            arg("orders", IType::Array(Box::new(IType::Record(1)))),
+            arg("tag", IType::String),
+        ];
+        let order = json!({"items": [{"name": "a", "count": 1}], "data": [1, 2]});
+
+        check_arguments(
+            &arguments,
+            &[
+                json!({"id": 1, "orders": [order], "tag": "t"}),
+                json!([1, [order], "t"]),
+                json!([[1], [order, [[["a", 1]], [3]]], ["t"]]),
+                json!({"id": 1, "orders": [], "tag": "t"}),
+                json!({"id": -1, "orders": [], "tag": "t"}),
+                json!({"id": 4294967296u64, "orders": [], "tag": "t"}),
+                json!({"id": 1, "orders": [], "tag": 1}),
+                json!({"id": 1, "orders": []}),
+                json!({"id": 1, "orders": [], "tag": "t", "extra": 1}),
+                json!({"id": 1, "orders": [{"items": [], "data": [256]}], "tag": "t"}),
+                json!({"id": 1, "orders": [{"items": [{"name": "a"}], "data": []}], "tag": "t"}),
+                json!({"id": 1, "orders": [[[], [], []]], "tag": "t"}),
+                json!({"id": 1, "orders": "order", "tag": "t"}),
+                json!({"id": [1, 2], "orders": [], "tag": "t"}),
+                json!([1, [order]]),
+                json!([1, [order], "t", "t"]),
+                json!(null),
+                json!(1),
+            ],
+        );
+    }
+
+    #[test]
+    fn sole_argument_schema_matches_conversion() {
+        let samples = [
+            json!(5),
+            json!([5]),
+            json!({"value": 5}),
+            json!("5"),
+            json!(null),
+            json!([]),
+            json!(-5),
+            json!(true),
+        ];
+
+        check_arguments(&[arg("value", IType::S8)], &samples);
+        check_arguments(&[arg("value", IType::F64)], &samples);
+        check_arguments(&[arg("value", IType::Boolean)], &samples);
+        check_arguments(&[arg("value", IType::Record(0))], &samples);
+        check_arguments(&[], &samples);
+    }
+
+    #[test]
+    fn results_schema_matches_conversion() {
+        let record_types = record_types();
+        let outputs = [IType::Record(1), IType::U64];
+        let schema = function_json_schema("f", &[], &outputs, &record_types).unwrap();
+        let validator = JSONSchema::compile(&schema.results).unwrap();
+
+        let item = IValue::Record(
+            NEVec::new(vec![IValue::String(String::from("a")), IValue::U8(1)]).unwrap(),
+        );
+        let order = IValue::Record(
+            NEVec::new(vec![
+                IValue::Array(vec![item]),
+                IValue::ByteArray(vec![1, 2]),
+            ])
+            .unwrap(),
+        );
+        let results =
+            ivalues_to_json(vec![order, IValue::U64(u64::MAX)], &outputs, &record_types).unwrap();
+
+        assert!(validator.is_valid(&results));
+        assert!(!validator.is_valid(&json!([results[0], 1, 1])));
+        assert!(!validator.is_valid(&json!([[[], [1, 2]], 1])));
+    }
+
+    #[test]
+    fn record_definitions() {
+        let record_types = record_types();
+        let schema =
+            function_json_schema("f", &[arg("order", IType::Record(1))], &[], &record_types)
+                .unwrap();
+
+        let definitions = schema.arguments["definitions"].as_object().unwrap();
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            vec!["Item", "Order"]
+        );
+        assert_eq!(definitions["Order"]["title"], json!("Order"));
+        assert_eq!(
+            schema.results,
+            json!({
+                "$schema": JSON_SCHEMA_DRAFT,
+                "title": "f results",
+                "type": "null",
+            })
+        );
+    }
+}
@@ -29,4 +29,6 @@
 )]
 
 pub mod interface;
+pub mod interface_diff;
 pub mod it_interface;
+pub mod json_schema;
@@ -0,0 +1,456 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::BundleError;
+use crate::BundleFile;
+use crate::BundleManifest;
+use crate::BundleModule;
+use crate::BundleResult;
+use crate::BUNDLE_FORMAT_VERSION;
+use crate::manifest::sha256_hash;
+use crate::manifest::CONFIG_PATH;
+use crate::manifest::DATA_DIR;
+use crate::manifest::MANIFEST_PATH;
+
+use marine::ModuleHash;
+
+use toml::value::Table;
+use toml::Value;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Module bytes by module names.
+pub type BundleModules = HashMap<String, Vec<u8>>;
+
+/// Initial data by paths relative to the service working dir.
+pub type BundleData = BTreeMap<String, Vec<u8>>;
+
+/// Service config, modules and initial data packed into a single file.
+#[derive(Debug, Clone)]
+pub struct ServiceBundle {
+    manifest: BundleManifest,
+    /// Config as it is stored in the bundle, its hash is checked against the manifest.
+    config_content: Vec<u8>,
+    config: Table,
+    modules: BundleModules,
+    data: BundleData,
+}
+
+impl ServiceBundle {
+    /// Creates a bundle from a service TOML config, modules are resolved the same way Marine does.
+    /// The bundled config is self-contained: its includes and `${VAR}` references are resolved.
+    /// Files from the data dir, if provided, are copied into the service working dir on the first start.
+    pub fn create(config_path: &Path, data_dir: Option<&Path>) -> BundleResult<Self> {
+        let config_path = config_path
+            .canonicalize()
+            .map_err(|error| BundleError::ReadFile {
+                path: config_path.to_path_buf(),
+                error,
+            })?;
+        let base_path = config_path.parent().unwrap_or_else(|| Path::new("/"));
+
+        // includes and environment variables are resolved on the host creating the bundle
+        let mut config = marine::resolve_toml_config(&config_path)?;
+
+        let mut modules_config = match config.remove("module") {
+            Some(Value::Array(modules)) => modules,
+            Some(_) => {
+                return Err(BundleError::InvalidConfig(String::from(
+                    r#""module" should be an array of tables"#,
+                )))
+            }
+            None => Vec::new(),
+        };
+
+        let mut modules = HashMap::new();
+        let mut bundle_modules = Vec::new();
+        for module in modules_config.iter_mut() {
+            let module = module.as_table_mut().ok_or_else(|| {
+                BundleError::InvalidConfig(String::from(r#""module" should be an array of tables"#))
+            })?;
+
+            let path = module_path(&config, module, base_path)?;
+            // module_path checks that the name is present
+            let name = str_field(module, "name")?.unwrap_or_default().to_string();
+            let wasm_bytes = read_file(&path)?;
+
+            let file = BundleFile::new(crate::manifest::module_path(&name), &wasm_bytes);
+            module.remove("load_from");
+            module.remove("file_name");
+            // a hash pinned by the config is checked by Marine when the module is loaded
+            module
+                .entry("hash")
+                .or_insert_with(|| Value::String(file.hash.clone()));
+
+            if modules.insert(name.clone(), wasm_bytes).is_some() {
+                return Err(BundleError::InvalidConfig(format!(
+                    "module {} is duplicated in config",
+                    name
+                )));
+            }
+            bundle_modules.push(BundleModule { name, file });
+        }
+
+        // the bundle doesn't depend on the filesystem layout of its creator
+        config.remove("modules_dir");
+        config.remove("module_store");
+        config.remove("service_working_dir");
+        config.insert(String::from("module"), Value::Array(modules_config));
+
+        // Value puts plain values before tables as toml requires, unlike Table
+        let config_content = toml::to_string(&Value::Table(config.clone()))
+            .map_err(|error| BundleError::SerializeError {
+                file: CONFIG_PATH,
+                error,
+            })?
+            .into_bytes();
+
+        let mut data = BTreeMap::new();
+        if let Some(data_dir) = data_dir {
+            collect_data(data_dir, data_dir, &mut data)?;
+        }
+
+        let manifest = BundleManifest {
+            version: BUNDLE_FORMAT_VERSION,
+            config: BundleFile::new(CONFIG_PATH.to_string(), &config_content),
+            modules: bundle_modules,
+            data: data
+                .iter()
+                .map(|(path, content)| BundleFile::new(crate::manifest::data_path(path), content))
+                .collect(),
+        };
+
+        Ok(Self {
+            manifest,
+            config_content,
+            config,
+            modules,
+            data,
+        })
+    }
+
+    /// Unpacks a bundle, hashes aren't checked, use [`Self::verify`] for that.
+    pub fn from_bytes(bundle: &[u8]) -> BundleResult<Self> {
+        let mut files = unpack(bundle)?;
+        let mut take_file = |path: &str| {
+            files
+                .remove(path)
+                .ok_or_else(|| BundleError::MissingFile(path.to_string()))
+        };
+
+        let manifest = take_file(MANIFEST_PATH)?;
+        let manifest: BundleManifest =
+            toml::from_slice(&manifest).map_err(|error| BundleError::ParseError {
+                file: MANIFEST_PATH,
+                error,
+            })?;
+        if manifest.version != BUNDLE_FORMAT_VERSION {
+            return Err(BundleError::UnsupportedVersion(manifest.version));
+        }
+
+        let config_content = take_file(&manifest.config.path)?;
+        let config: Table =
+            toml::from_slice(&config_content).map_err(|error| BundleError::ParseError {
+                file: CONFIG_PATH,
+                error,
+            })?;
+
+        let modules = manifest
+            .modules
+            .iter()
+            .map(|module| Ok((module.name.clone(), take_file(&module.file.path)?)))
+            .collect::<BundleResult<BundleModules>>()?;
+
+        let data = manifest
+            .data
+            .iter()
+            .map(|file| {
+                let relative_path = data_relative_path(&file.path)?;
+                Ok((relative_path.to_string(), take_file(&file.path)?))
+            })
+            .collect::<BundleResult<BundleData>>()?;
+
+        if let Some(path) = files.into_keys().next() {
+            return Err(BundleError::UnexpectedFile(path));
+        }
+
+        let bundle = Self {
+            manifest,
+            config_content,
+            config,
+            modules,
+            data,
+        };
+        bundle.check_modules()?;
+
+        Ok(bundle)
+    }
+
+    pub fn load(path: &Path) -> BundleResult<Self> {
+        Self::from_bytes(&read_file(path)?)
+    }
+
+    /// Packs the bundle into a tar archive, the output is reproducible for the same content.
+    pub fn to_bytes(&self) -> BundleResult<Vec<u8>> {
+        let manifest =
+            toml::to_string(&self.manifest).map_err(|error| BundleError::SerializeError {
+                file: MANIFEST_PATH,
+                error,
+            })?;
+
+        let mut builder = tar::Builder::new(Vec::new());
+        append_file(&mut builder, MANIFEST_PATH, manifest.as_bytes())?;
+        append_file(
+            &mut builder,
+            &self.manifest.config.path,
+            &self.config_content,
+        )?;
+        for module in &self.manifest.modules {
+            append_file(&mut builder, &module.file.path, &self.modules[&module.name])?;
+        }
+        for file in &self.manifest.data {
+            append_file(&mut builder, &file.path, self.data_file(file)?)?;
+        }
+
+        Ok(builder.into_inner()?)
+    }
+
+    pub fn save(&self, path: &Path) -> BundleResult<()> {
+        std::fs::write(path, self.to_bytes()?).map_err(BundleError::ArchiveError)
+    }
+
+    /// Checks that all files of the bundle match their hashes from the manifest.
+    pub fn verify(&self) -> BundleResult<()> {
+        check_hash(&self.manifest.config, &self.config_content)?;
+        for module in &self.manifest.modules {
+            check_hash(&module.file, &self.modules[&module.name])?;
+        }
+        for file in &self.manifest.data {
+            check_hash(file, self.data_file(file)?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Service config, modules in it have no paths and are loaded from the bundle.
+    pub fn config(&self) -> &Table {
+        &self.config
+    }
+
+    pub fn modules(&self) -> &BundleModules {
+        &self.modules
+    }
+
+    pub fn data(&self) -> &BundleData {
+        &self.data
+    }
+
+    /// Returns the config, modules and initial data of the bundle.
+    pub fn into_parts(self) -> (Table, BundleModules, BundleData) {
+        (self.config, self.modules, self.data)
+    }
+
+    /// Returns content of a data file listed in the manifest.
+    pub fn data_file(&self, file: &BundleFile) -> BundleResult<&[u8]> {
+        let relative_path = data_relative_path(&file.path)?;
+        self.data
+            .get(relative_path)
+            .map(Vec::as_slice)
+            .ok_or_else(|| BundleError::MissingFile(file.path.clone()))
+    }
+
+    fn check_modules(&self) -> BundleResult<()> {
+        let config_modules = match self.config.get("module") {
+            Some(Value::Array(modules)) => modules
+                .iter()
+                .filter_map(|module| module.get("name").and_then(Value::as_str))
+                .map(ToString::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let manifest_modules = self
+            .manifest
+            .modules
+            .iter()
+            .map(|module| module.name.clone())
+            .collect::<Vec<_>>();
+
+        if config_modules != manifest_modules {
+            return Err(BundleError::ModulesMismatch {
+                config: config_modules,
+                manifest: manifest_modules,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves a module path the same way Marine does it.
+fn module_path(config: &Table, module: &Table, base_path: &Path) -> BundleResult<PathBuf> {
+    let name = str_field(module, "name")?
+        .ok_or_else(|| BundleError::InvalidConfig(String::from("module without a name")))?;
+    let file_name = str_field(module, "file_name")?
+        .map(ToString::to_string)
+        .unwrap_or_else(|| format!("{}.wasm", name));
+    let hash = str_field(module, "hash")?
+        .map(|hash| {
+            hash.parse::<ModuleHash>().map_err(|e| {
+                BundleError::InvalidConfig(format!("invalid hash of module {}: {}", name, e))
+            })
+        })
+        .transpose()?;
+
+    let load_from = str_field(module, "load_from")?.map(|path| base_path.join(path));
+    let modules_dir = str_field(config, "modules_dir")?.map(|dir| base_path.join(dir));
+    let module_store = str_field(config, "module_store")?.map(|dir| base_path.join(dir));
+
+    let path = marine::module_path(
+        load_from.as_deref(),
+        hash.as_ref(),
+        &file_name,
+        name,
+        modules_dir.as_deref(),
+        module_store.as_deref(),
+    )?;
+
+    Ok(path)
+}
+
+fn str_field<'t>(table: &'t Table, field: &str) -> BundleResult<Option<&'t str>> {
+    match table.get(field) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(BundleError::InvalidConfig(format!(
+            r#""{}" field should be a string"#,
+            field
+        ))),
+    }
+}
+
+fn read_file(path: &Path) -> BundleResult<Vec<u8>> {
+    std::fs::read(path).map_err(|error| BundleError::ReadFile {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+fn data_relative_path(path: &str) -> BundleResult<&str> {
+    path.strip_prefix(DATA_DIR)
+        .and_then(|path| path.strip_prefix('/'))
+        .ok_or_else(|| BundleError::InvalidPath(path.to_string()))
+}
+
+fn check_hash(file: &BundleFile, content: &[u8]) -> BundleResult<()> {
+    let actual = sha256_hash(content);
+    if actual != file.hash {
+        return Err(BundleError::HashMismatch {
+            path: file.path.clone(),
+            expected: file.hash.clone(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// Collects files from the data dir recursively by paths relative to it.
+fn collect_data(data_dir: &Path, dir: &Path, data: &mut BundleData) -> BundleResult<()> {
+    let read_dir_error = |error| BundleError::ReadFile {
+        path: dir.to_path_buf(),
+        error,
+    };
+
+    for entry in std::fs::read_dir(dir).map_err(read_dir_error)? {
+        let path = entry.map_err(read_dir_error)?.path();
+        if path.is_dir() {
+            collect_data(data_dir, &path, data)?;
+            continue;
+        }
+
+        // data_dir is a prefix of all paths returned by read_dir
+        let relative_path = path.strip_prefix(data_dir).unwrap_or(&path);
+        let relative_path = archive_path(relative_path)?;
+        data.insert(relative_path, read_file(&path)?);
+    }
+
+    Ok(())
+}
+
+fn unpack(bundle: &[u8]) -> BundleResult<BTreeMap<String, Vec<u8>>> {
+    let mut archive = tar::Archive::new(bundle);
+    let mut files = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        let path = entry.path()?;
+        if entry_type.is_dir() {
+            continue;
+        }
+        if !entry_type.is_file() {
+            return Err(BundleError::InvalidPath(path.display().to_string()));
+        }
+
+        let path = archive_path(&path)?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.insert(path, content);
+    }
+
+    Ok(files)
+}
+
+/// Converts a relative path to the form used in the archive, rejecting paths leading outside of it.
+fn archive_path(path: &Path) -> BundleResult<String> {
+    let invalid_path = || BundleError::InvalidPath(path.display().to_string());
+
+    path.components()
+        .map(|component| match component {
+            Component::Normal(component) => component.to_str().ok_or_else(invalid_path),
+            _ => Err(invalid_path()),
+        })
+        .collect::<BundleResult<Vec<_>>>()
+        .map(|components| components.join("/"))
+}
+
+fn append_file(
+    builder: &mut tar::Builder<Vec<u8>>,
+    path: &str,
+    content: &[u8],
+) -> BundleResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_cksum();
+
+    builder.append_data(&mut header, path, content)?;
+    Ok(())
+}
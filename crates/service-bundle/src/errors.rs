@@ -0,0 +1,92 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use thiserror::Error;
+
+use std::path::PathBuf;
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    /// An error occurred while reading a file referenced by the service config.
+    #[error("failed to read {path}: {error}")]
+    ReadFile {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    /// An error occurred while reading or writing the tar archive.
+    #[error("bundle archive error: {0}")]
+    ArchiveError(#[from] std::io::Error),
+
+    /// Includes or environment variables references of the service config can't be resolved.
+    #[error("failed to resolve service config: {0}")]
+    ConfigResolutionError(#[from] marine::MarineError),
+
+    /// The service config is malformed.
+    #[error("invalid service config: {0}")]
+    InvalidConfig(String),
+
+    /// The manifest or the config of the bundle can't be parsed.
+    #[error("failed to parse {file}: {error}")]
+    ParseError {
+        file: &'static str,
+        error: toml::de::Error,
+    },
+
+    /// The manifest or the config can't be serialized.
+    #[error("failed to serialize {file}: {error}")]
+    SerializeError {
+        file: &'static str,
+        error: toml::ser::Error,
+    },
+
+    /// The bundle was created by a newer version of the format.
+    #[error(
+        "unsupported bundle format version {0}, expected {}",
+        crate::BUNDLE_FORMAT_VERSION
+    )]
+    UnsupportedVersion(u32),
+
+    /// A file listed in the manifest isn't found in the archive.
+    #[error("file {0} is listed in the manifest, but missing in the bundle")]
+    MissingFile(String),
+
+    /// The archive contains a file which isn't listed in the manifest.
+    #[error("file {0} isn't listed in the bundle manifest")]
+    UnexpectedFile(String),
+
+    /// A path in the archive is absolute or points outside of it.
+    #[error("invalid path {0} in the bundle")]
+    InvalidPath(String),
+
+    /// A file content doesn't match its hash from the manifest.
+    #[error("file {path} hash mismatch: expected {expected}, but it is {actual}")]
+    HashMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// Modules in the config differ from modules in the manifest.
+    #[error("config modules {config:?} don't match modules {manifest:?} from the manifest")]
+    ModulesMismatch {
+        config: Vec<String>,
+        manifest: Vec<String>,
+    },
+}
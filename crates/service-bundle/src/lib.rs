@@ -0,0 +1,57 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+#![warn(rust_2018_idioms)]
+#![deny(
+    dead_code,
+    nonstandard_style,
+    unused_imports,
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    unreachable_patterns
+)]
+
+//! A single-file service bundle: a tar archive with a manifest, the service config,
+//! its modules and optional initial data, so a service could be deployed without
+//! reproducing its filesystem layout.
+//!
+//! The archive layout is:
+//! - `manifest.toml` with the format version and hashes of all other files,
+//! - `config.toml` with the service config, modules in it are referenced only by names and hashes,
+//! - `modules/<name>.wasm` for every module of the config,
+//! - `data/...` with files copied into the service working dir on the first start.
+
+mod bundle;
+mod errors;
+mod manifest;
+
+#[cfg(test)]
+mod tests;
+
+pub use bundle::ServiceBundle;
+pub use bundle::BundleData;
+pub use bundle::BundleModules;
+pub use errors::BundleError;
+pub use manifest::BundleManifest;
+pub use manifest::BundleFile;
+pub use manifest::BundleModule;
+pub use manifest::BUNDLE_FORMAT_VERSION;
+
+pub(crate) type BundleResult<T> = std::result::Result<T, BundleError>;
@@ -0,0 +1,79 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+
+/// Version of the bundle format, bumped on incompatible changes.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+pub(crate) const MANIFEST_PATH: &str = "manifest.toml";
+pub(crate) const CONFIG_PATH: &str = "config.toml";
+pub(crate) const MODULES_DIR: &str = "modules";
+pub(crate) const DATA_DIR: &str = "data";
+
+/// Describes content of a bundle, every file except the manifest itself is listed here with its hash.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub config: BundleFile,
+    /// Modules in the same order as in the config.
+    #[serde(default, rename = "module", skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<BundleModule>,
+    /// Initial data, paths are inside the data dir of the bundle.
+    #[serde(default, rename = "data", skip_serializing_if = "Vec::is_empty")]
+    pub data: Vec<BundleFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundleModule {
+    pub name: String,
+    #[serde(flatten)]
+    pub file: BundleFile,
+}
+
+/// A file in the bundle with its hash in the `sha256:<hex digest>` form.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundleFile {
+    pub path: String,
+    pub hash: String,
+}
+
+impl BundleFile {
+    pub(crate) fn new(path: String, content: &[u8]) -> Self {
+        Self {
+            path,
+            hash: sha256_hash(content),
+        }
+    }
+}
+
+/// Returns a hash in the format accepted by the `hash` field of modules in Marine configs.
+pub(crate) fn sha256_hash(content: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(sha2::Sha256::digest(content)))
+}
+
+pub(crate) fn module_path(name: &str) -> String {
+    format!("{}/{}.wasm", MODULES_DIR, name)
+}
+
+pub(crate) fn data_path(relative_path: &str) -> String {
+    format!("{}/{}", DATA_DIR, relative_path)
+}
@@ -0,0 +1,202 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::BundleError;
+use crate::ServiceBundle;
+
+use std::io::Read;
+use std::path::PathBuf;
+
+const GREETING_PATH: &str = "../../examples/greeting/artifacts/greeting.wasm";
+
+/// Creates a service dir with a config, a module and initial data, removed on drop.
+struct ServiceDir(PathBuf);
+
+impl ServiceDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "marine_service_bundle_{}_{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("artifacts")).unwrap();
+        std::fs::create_dir_all(dir.join("data/db")).unwrap();
+
+        std::fs::copy(GREETING_PATH, dir.join("artifacts/greeting.wasm")).unwrap();
+        std::fs::write(dir.join("data/db/init.sql"), "create table t(x);").unwrap();
+        std::fs::write(
+            dir.join("Config.toml"),
+            r#"
+            modules_dir = "artifacts"
+            total_memory_limit = "10 MiB"
+
+            [[module]]
+            name = "greeting"
+            logger_enabled = true
+            "#,
+        )
+        .unwrap();
+
+        Self(dir)
+    }
+
+    fn bundle(&self) -> ServiceBundle {
+        ServiceBundle::create(&self.0.join("Config.toml"), Some(&self.0.join("data"))).unwrap()
+    }
+}
+
+impl Drop for ServiceDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn replace(bundle: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    let mut bundle = bundle.to_vec();
+    let position = bundle
+        .windows(from.len())
+        .position(|window| window == from)
+        .unwrap();
+    bundle[position..position + to.len()].copy_from_slice(to);
+    bundle
+}
+
+#[test]
+fn create_and_unpack() {
+    let service_dir = ServiceDir::new("create_and_unpack");
+    let bundle = service_dir.bundle();
+    let greeting = std::fs::read(GREETING_PATH).unwrap();
+
+    let manifest = bundle.manifest();
+    assert_eq!(manifest.modules.len(), 1);
+    assert_eq!(manifest.modules[0].name, "greeting");
+    assert_eq!(manifest.modules[0].file.path, "modules/greeting.wasm");
+    assert_eq!(manifest.data.len(), 1);
+    assert_eq!(manifest.data[0].path, "data/db/init.sql");
+
+    let config = bundle.config();
+    assert!(config.get("modules_dir").is_none());
+    let module = &config["module"][0];
+    assert_eq!(
+        module["hash"].as_str(),
+        Some(manifest.modules[0].file.hash.as_str())
+    );
+    assert_eq!(module["logger_enabled"].as_bool(), Some(true));
+
+    let bytes = bundle.to_bytes().unwrap();
+    // bundles are reproducible
+    assert_eq!(bytes, service_dir.bundle().to_bytes().unwrap());
+
+    let unpacked = ServiceBundle::from_bytes(&bytes).unwrap();
+    unpacked.verify().unwrap();
+    assert_eq!(unpacked.manifest(), manifest);
+    assert_eq!(unpacked.config(), config);
+    assert_eq!(unpacked.modules()["greeting"], greeting);
+    assert_eq!(unpacked.data()["db/init.sql"], b"create table t(x);");
+}
+
+#[test]
+fn tampered_bundle() {
+    let service_dir = ServiceDir::new("tampered_bundle");
+    let bytes = service_dir.bundle().to_bytes().unwrap();
+
+    let tampered = replace(&bytes, b"create table", b"delete table");
+    let bundle = ServiceBundle::from_bytes(&tampered).unwrap();
+    match bundle.verify() {
+        Err(BundleError::HashMismatch { path, .. }) => assert_eq!(path, "data/db/init.sql"),
+        result => panic!("expected hash mismatch, got {:?}", result),
+    }
+}
+
+/// Repacks the bundle with an additional file, the path is written as is.
+fn with_extra_file(bundle: &[u8], path: &str) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut archive = tar::Archive::new(bundle);
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let header = entry.header().clone();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).unwrap();
+        builder.append(&header, content.as_slice()).unwrap();
+    }
+
+    let mut header = tar::Header::new_old();
+    header.set_size(1);
+    // set_path rejects parent dirs, so the name is written directly
+    header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+    header.set_cksum();
+    builder.append(&header, &b"x"[..]).unwrap();
+
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn unexpected_paths() {
+    let service_dir = ServiceDir::new("unexpected_paths");
+    let bytes = service_dir.bundle().to_bytes().unwrap();
+
+    let result = ServiceBundle::from_bytes(&with_extra_file(&bytes, "data/extra"));
+    assert!(matches!(result, Err(BundleError::UnexpectedFile(path)) if path == "data/extra"));
+
+    let result = ServiceBundle::from_bytes(&with_extra_file(&bytes, "../escape"));
+    assert!(matches!(result, Err(BundleError::InvalidPath(_))));
+}
+
+#[test]
+fn module_store_paths() {
+    let service_dir = ServiceDir::new("module_store_paths");
+    let greeting = std::fs::read(GREETING_PATH).unwrap();
+    let hash = crate::manifest::sha256_hash(&greeting);
+    let digest = hash.strip_prefix("sha256:").unwrap();
+
+    // the store layout is the one of Marine, digests in it are always lowercase
+    std::fs::create_dir_all(service_dir.0.join("store/sha256")).unwrap();
+    std::fs::copy(
+        GREETING_PATH,
+        service_dir.0.join(format!("store/sha256/{}.wasm", digest)),
+    )
+    .unwrap();
+
+    let write_config = |hash: &str| {
+        let config = format!(
+            r#"
+            module_store = "store"
+            total_memory_limit = "10 MiB"
+
+            [[module]]
+            name = "greeting"
+            hash = "{}"
+            "#,
+            hash
+        );
+        std::fs::write(service_dir.0.join("Config.toml"), config).unwrap();
+    };
+
+    write_config(&format!("sha256:{}", digest.to_uppercase()));
+    let bundle = service_dir.bundle();
+    assert_eq!(bundle.modules()["greeting"], greeting);
+
+    write_config(&format!("md5:{}", digest));
+    let result = ServiceBundle::create(&service_dir.0.join("Config.toml"), None);
+    assert!(
+        matches!(result, Err(BundleError::InvalidConfig(_))),
+        "{:?}",
+        result
+    );
+}
@@ -31,6 +31,9 @@ pub trait Module<WB: WasmBackend>: Sized {
     /// Returns custom sections corresponding to `name`, empty slice if there is no sections.
     fn custom_sections(&self, name: &str) -> &[Vec<u8>];
 
+    /// Returns namespaces and names of functions imported by the module.
+    fn function_imports(&self) -> Vec<(String, String)>;
+
     /// Instantiates module by allocating memory, VM state and linking imports with ones from `import` argument.
     /// Does not call `_start` or `_initialize` functions.
     ///
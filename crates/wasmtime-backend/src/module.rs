@@ -56,6 +56,14 @@ impl Module<WasmtimeWasmBackend> for WasmtimeModule {
             .unwrap_or_default()
     }
 
+    fn function_imports(&self) -> Vec<(String, String)> {
+        self.inner
+            .imports()
+            .filter(|import| matches!(import.ty(), wasmtime::ExternType::Func(_)))
+            .map(|import| (import.module().to_string(), import.name().to_string()))
+            .collect()
+    }
+
     fn instantiate<'args>(
         &'args self,
         store: &'args mut WasmtimeStore,
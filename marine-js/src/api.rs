@@ -86,8 +86,12 @@ impl From<ApiModuleConfig> for MarineModuleConfig<JsWasmBackend> {
         Self {
             logger_enabled: value.logger_enabled,
             host_imports: Default::default(),
+            host_import_providers: Default::default(),
+            mounted_binaries: Default::default(),
             wasi: value.wasi.map(Into::into),
             logging_mask: value.logging_mask,
+            log_targets: None,
+            logger_limits: Default::default(),
         }
     }
 }
@@ -98,6 +102,7 @@ impl From<ApiModuleDescriptor> for ModuleDescriptor<JsWasmBackend> {
             load_from: None,
             file_name: value.import_name.clone(),
             import_name: value.import_name,
+            hash: None,
             config: value.config.map(Into::into).unwrap_or_default(),
         }
     }
@@ -113,9 +118,15 @@ impl From<ApiServiceConfig> for MarineConfig<JsWasmBackend> {
 
         MarineConfig {
             modules_dir: None,
+            module_store: None,
             total_memory_limit: None,
             modules_config,
             default_modules_config: value.default_modules_config.map(Into::into),
+            host_import_providers: <_>::default(),
+            mounted_binaries_limiter: <_>::default(),
+            log_directives: <_>::default(),
+            trusted_signers: <_>::default(),
+            effects_policy: <_>::default(),
         }
     }
 }
@@ -0,0 +1,355 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::as_relative_to_base;
+use super::config_loader::resolve_toml_config_reporting_all;
+use super::marine_config::effects_policy;
+use super::marine_config::log_directives;
+use super::marine_config::logging_mask;
+use super::marine_config::module_file_name;
+use super::marine_config::module_hash;
+use super::marine_config::module_path;
+use super::marine_config::mounted_binaries_limiter;
+use super::marine_config::trusted_signer;
+use super::raw_marine_config::canonical_config_path;
+use super::raw_marine_config::config_base_path;
+use super::ConfigContext;
+use super::LoggerLimits;
+use super::MarineWASIConfig;
+use super::MountedBinaryConfig;
+use super::TomlMarineConfig;
+use super::TomlMarineModuleConfig;
+use super::TomlMarineNamedModuleConfig;
+use super::TomlMountedBinary;
+use crate::MarineError;
+
+use toml::value::Table;
+use toml::Value;
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// Loads a config like `TomlMarineConfig::load` and checks everything that could be checked
+/// without instantiating modules: all fields are parsed, module files and mounted binaries exist.
+/// Returns all found errors instead of stopping at the first one, so an empty result means
+/// the config is valid.
+pub fn check_config<P: AsRef<Path>>(path: P) -> Vec<MarineError> {
+    let path = match canonical_config_path(path.as_ref()) {
+        Ok(path) => path,
+        Err(e) => return vec![e],
+    };
+    let config = match resolve_toml_config_reporting_all(&path).and_then(deserialize_config) {
+        Ok(config) => config,
+        Err(errors) => return errors,
+    };
+
+    let mut errors = Vec::new();
+    let base_path = config_base_path(&path);
+    let context = ConfigContext {
+        base_path: Some(base_path.clone()),
+    };
+    let base_path = Some(base_path.as_path());
+
+    let modules_dir = config
+        .modules_dir
+        .as_ref()
+        .and_then(|dir| collect_error(as_relative_to_base(base_path, dir), &mut errors));
+    let module_store = config
+        .module_store
+        .as_ref()
+        .and_then(|dir| collect_error(as_relative_to_base(base_path, dir), &mut errors));
+
+    // modules are looked up in these dirs, so errors in them were already reported
+    let unresolved_dirs = (config.modules_dir.is_some() && modules_dir.is_none())
+        || (config.module_store.is_some() && module_store.is_none());
+
+    for signer in config.trusted_signers.iter().flatten() {
+        collect_error(trusted_signer(signer), &mut errors);
+    }
+
+    collect_error(log_directives(config.log_directives.clone()), &mut errors);
+    collect_error(
+        mounted_binaries_limiter(config.max_concurrent_mounted_binaries),
+        &mut errors,
+    );
+
+    let policy = effects_policy(
+        config.allowed_effects.clone(),
+        config.denied_effects.clone(),
+    );
+    collect_error(policy, &mut errors);
+
+    if let Some(default) = &config.default {
+        check_module_config(default, "default", &context, &mut errors);
+    }
+
+    let mut names = HashSet::new();
+    for module in &config.module {
+        if !names.insert(module.name.as_str()) {
+            errors.push(MarineError::InvalidConfig(format!(
+                r#"module "{}" is duplicated"#,
+                module.name
+            )));
+        }
+
+        let hash = module
+            .hash
+            .as_ref()
+            .and_then(|hash| collect_error(module_hash(&module.name, hash), &mut errors));
+
+        let file_name = module_file_name(&module.name, module.file_name.as_deref());
+        let load_from = match &module.load_from {
+            Some(load_from) => match as_relative_to_base(base_path, load_from) {
+                Ok(load_from) => Some(load_from),
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let path = match load_from.is_none() && unresolved_dirs {
+            true => None,
+            false => Some(module_path(
+                load_from.as_deref(),
+                hash.as_ref(),
+                &file_name,
+                &module.name,
+                modules_dir.as_deref(),
+                module_store.as_deref(),
+            )),
+        };
+        match path {
+            Some(Ok(path)) if !path.is_file() => errors.push(MarineError::InvalidConfig(format!(
+                r#"module "{}" file {} doesn't exist"#,
+                module.name,
+                path.display()
+            ))),
+            Some(Err(e)) => errors.push(e),
+            _ => {}
+        }
+
+        check_module_config(&module.config, &module.name, &context, &mut errors);
+    }
+
+    errors
+}
+
+/// Deserializes a resolved config, if it fails, top-level fields and modules are deserialized
+/// one by one to report errors of all of them.
+fn deserialize_config(config: Table) -> Result<TomlMarineConfig, Vec<MarineError>> {
+    let error = match Value::Table(config.clone()).try_into::<TomlMarineConfig>() {
+        Ok(config) => return Ok(config),
+        Err(e) => e,
+    };
+
+    let mut errors = Vec::new();
+    for (key, value) in config {
+        match value {
+            Value::Array(modules) if key == "module" => {
+                for (id, module) in modules.into_iter().enumerate() {
+                    let name = module
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .map(|name| format!(r#""{}""#, name))
+                        .unwrap_or_else(|| format!("#{}", id));
+
+                    if let Err(e) = module.try_into::<TomlMarineNamedModuleConfig>() {
+                        errors.push(MarineError::InvalidConfig(format!(
+                            "module {}: {}",
+                            name, e
+                        )));
+                    }
+                }
+            }
+            value => {
+                // required fields are set, so only an error of the checked field is possible
+                let mut field = Table::new();
+                field.insert(
+                    String::from("total_memory_limit"),
+                    Value::String(String::from("Infinity")),
+                );
+                field.insert(String::from("module"), Value::Array(Vec::new()));
+                field.insert(key, value);
+
+                if let Err(e) = Value::Table(field).try_into::<TomlMarineConfig>() {
+                    errors.push(MarineError::from(e));
+                }
+            }
+        }
+    }
+
+    // e.g. a required field is missing
+    if errors.is_empty() {
+        errors.push(MarineError::from(error));
+    }
+
+    Err(errors)
+}
+
+fn check_module_config(
+    config: &TomlMarineModuleConfig,
+    module_name: &str,
+    context: &ConfigContext,
+    errors: &mut Vec<MarineError>,
+) {
+    for (name, mounted_binary) in config.mounted_binaries.iter().flatten() {
+        let mounted_binary = mounted_binary
+            .clone()
+            .try_into::<TomlMountedBinary>()
+            .map_err(MarineError::from)
+            .and_then(|binary| MountedBinaryConfig::try_from(context.wrapped(binary)));
+
+        match mounted_binary {
+            Ok(binary) if !binary.path.is_file() => {
+                errors.push(MarineError::InvalidConfig(format!(
+                    r#"mounted binary "{}" of module "{}": {} doesn't exist"#,
+                    name,
+                    module_name,
+                    binary.path.display()
+                )))
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(MarineError::InvalidConfig(format!(
+                r#"mounted binary "{}" of module "{}": {}"#,
+                name, module_name, e
+            ))),
+        }
+    }
+
+    if let Err(e) = logging_mask(config.logging_mask, &config.log_targets) {
+        errors.push(MarineError::InvalidConfig(format!(
+            r#"logger config of module "{}": {}"#,
+            module_name, e
+        )));
+    }
+
+    if let Some(limits) = &config.logger_limits {
+        if let Err(e) = LoggerLimits::try_from(limits.clone()) {
+            errors.push(MarineError::InvalidConfig(format!(
+                r#"logger limits of module "{}": {}"#,
+                module_name, e
+            )));
+        }
+    }
+
+    if let Some(wasi) = &config.wasi {
+        if let Err(e) = MarineWASIConfig::try_from(wasi.clone()) {
+            errors.push(MarineError::InvalidConfig(format!(
+                r#"wasi config of module "{}": {}"#,
+                module_name, e
+            )));
+        }
+    }
+}
+
+fn collect_error<T>(result: Result<T, MarineError>, errors: &mut Vec<MarineError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_config;
+
+    #[test]
+    fn all_errors_are_reported() {
+        let dir = std::env::temp_dir().join(format!("marine-config-check-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("artifacts")).unwrap();
+        std::fs::write(dir.join("artifacts/facade.wasm"), b"").unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"
+            modules_dir = "artifacts"
+            total_memory_limit = "Infinity"
+            max_concurrent_mounted_binaries = 0
+            log_directives = "facade=loud"
+            denied_effects = ["teleportation"]
+
+            [[module]]
+            name = "facade"
+            logger_limits = { max_messages_per_second = 0 }
+
+            [[module]]
+            name = "storage"
+            hash = "md5:0123"
+
+            [module.mounted_binaries]
+            curl = "/nonexistent/bin/curl"
+            "#,
+        )
+        .unwrap();
+
+        let errors = check_config(dir.join("config.toml"))
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(errors.len(), 7, "{:#?}", errors);
+        assert!(errors[0].contains("log_directives"));
+        assert!(errors[1].contains("max_concurrent_mounted_binaries"));
+        assert!(errors[2].contains("teleportation"));
+        assert!(errors[3].contains(r#"logger limits of module "facade""#));
+        assert!(errors[4].contains(r#"module "storage" hash"#));
+        assert!(errors[5].contains("storage.wasm doesn't exist"));
+        assert!(errors[6].contains(r#"mounted binary "curl""#));
+    }
+
+    #[test]
+    fn errors_of_all_fields_are_reported() {
+        let dir =
+            std::env::temp_dir().join(format!("marine-config-check-fields-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"
+            total_memory_limit = "Infinity"
+            max_concurrent_mounted_binaries = "four"
+
+            [[module]]
+            name = "facade"
+            logging_mask = "all"
+
+            [[module]]
+            name = "storage"
+            logger_enabled = "yes"
+            "#,
+        )
+        .unwrap();
+
+        let errors = check_config(dir.join("config.toml"))
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(errors.len(), 3, "{:#?}", errors);
+        assert!(errors[0].contains("max_concurrent_mounted_binaries"));
+        assert!(errors[1].contains(r#"module "facade""#));
+        assert!(errors[2].contains(r#"module "storage""#));
+    }
+}
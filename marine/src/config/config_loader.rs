@@ -0,0 +1,552 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::MarineError;
+use crate::MarineResult;
+
+use toml::value::Table;
+use toml::Value;
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+const INCLUDE_FIELD: &str = "include";
+const ENV_INTERPOLATION_FIELD: &str = "env_interpolation";
+
+/// Reads a TOML config and merges fragments listed in its `include` field. If a file sets
+/// `env_interpolation = true`, `${VAR}` and `${VAR:-default}` references to environment variables
+/// in its string values are resolved, `$${` could be used to write `${` literally there.
+/// Interpolation is enabled per file, so it doesn't change configs written without it in mind.
+///
+/// Included paths are relative to the including file. Fragments are merged in the listed order
+/// and the including file goes last: tables are merged recursively, arrays of tables
+/// (e.g. `[[module]]`) are concatenated and other values are overridden. A file included
+/// several times, e.g. a common fragment of two other ones, is merged only at its first inclusion.
+/// Fragments are merged as is, so relative paths in their values, e.g. `modules_dir`,
+/// are resolved later against the directory of the top-level config, not of the fragment.
+pub fn resolve_toml_config<P: AsRef<Path>>(path: P) -> MarineResult<Table> {
+    resolve_toml_config_reporting_all(path.as_ref()).map_err(|mut errors| match errors.len() {
+        1 => errors.remove(0),
+        _ => MarineError::InvalidConfig(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+    })
+}
+
+/// Works like `resolve_toml_config`, but doesn't stop at the first error:
+/// errors of all fragments are returned.
+pub(crate) fn resolve_toml_config_reporting_all(path: &Path) -> Result<Table, Vec<MarineError>> {
+    let lookup = |name: &str| std::env::var(name).ok();
+    resolve_file(path, &lookup, &mut Vec::new(), &mut HashSet::new())
+}
+
+fn resolve_file(
+    path: &Path,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    include_stack: &mut Vec<PathBuf>,
+    resolved_files: &mut HashSet<PathBuf>,
+) -> Result<Table, Vec<MarineError>> {
+    let path = path.canonicalize().map_err(|e| {
+        vec![MarineError::IOError(format!(
+            "failed to canonicalize path {}: {}",
+            path.display(),
+            e
+        ))]
+    })?;
+
+    if include_stack.contains(&path) {
+        let cycle = include_stack
+            .iter()
+            .chain(std::iter::once(&path))
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+        return Err(vec![MarineError::InvalidConfig(format!(
+            "include cycle: {}",
+            cycle.join(" -> ")
+        ))]);
+    }
+
+    // otherwise arrays of tables of the file would be duplicated
+    if !resolved_files.insert(path.clone()) {
+        return Ok(Table::new());
+    }
+
+    let file_content = std::fs::read(&path).map_err(|e| {
+        vec![MarineError::IOError(format!(
+            "failed to load {}: {}",
+            path.display(),
+            e
+        ))]
+    })?;
+    let mut config: Table = toml::from_slice(&file_content).map_err(|e| {
+        vec![MarineError::InvalidConfig(format!(
+            "{}: {}",
+            path.display(),
+            e
+        ))]
+    })?;
+
+    let mut errors = Vec::new();
+    let in_file =
+        |message: String| MarineError::InvalidConfig(format!("{}: {}", path.display(), message));
+
+    let env_interpolation = match config.remove(ENV_INTERPOLATION_FIELD) {
+        None => false,
+        Some(Value::Boolean(enabled)) => enabled,
+        Some(_) => {
+            errors.push(in_file(format!(
+                r#""{}" should be a boolean"#,
+                ENV_INTERPOLATION_FIELD
+            )));
+            false
+        }
+    };
+
+    if env_interpolation {
+        let mut undefined = Vec::new();
+        let mut invalid = Vec::new();
+        for (_, value) in config.iter_mut() {
+            interpolate_value(value, lookup, &mut undefined, &mut invalid);
+        }
+        errors.extend(invalid.into_iter().map(&in_file));
+
+        if !undefined.is_empty() {
+            undefined.sort();
+            undefined.dedup();
+            errors.push(in_file(format!(
+                "undefined environment variables: {}",
+                undefined.join(", ")
+            )));
+        }
+    }
+
+    let includes = match config.remove(INCLUDE_FIELD) {
+        None => Vec::new(),
+        Some(Value::String(include)) => vec![include],
+        Some(Value::Array(includes)) => {
+            let all_strings = includes.iter().all(Value::is_str);
+            if !all_strings {
+                errors.push(in_file(format!(
+                    r#""{}" should contain only strings"#,
+                    INCLUDE_FIELD
+                )));
+            }
+
+            includes
+                .into_iter()
+                .filter_map(|include| match include {
+                    Value::String(include) => Some(include),
+                    _ => None,
+                })
+                .collect()
+        }
+        Some(_) => {
+            errors.push(in_file(format!(
+                r#""{}" should be a string or an array of strings"#,
+                INCLUDE_FIELD
+            )));
+            Vec::new()
+        }
+    };
+
+    let base_path = path
+        .parent()
+        .unwrap_or_else(|| Path::new("/"))
+        .to_path_buf();
+    include_stack.push(path);
+
+    let mut resolved = Table::new();
+    for include in includes {
+        match resolve_file(
+            &base_path.join(include),
+            lookup,
+            include_stack,
+            resolved_files,
+        ) {
+            Ok(fragment) => merge_tables(&mut resolved, fragment),
+            Err(fragment_errors) => errors.extend(fragment_errors),
+        }
+    }
+    merge_tables(&mut resolved, config);
+
+    include_stack.pop();
+
+    match errors.is_empty() {
+        true => Ok(resolved),
+        false => Err(errors),
+    }
+}
+
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge_tables(base, overlay),
+            (Some(Value::Array(base)), Value::Array(overlay))
+                if is_array_of_tables(base) && is_array_of_tables(&overlay) =>
+            {
+                base.extend(overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn is_array_of_tables(array: &[Value]) -> bool {
+    array.iter().all(Value::is_table)
+}
+
+/// Interpolates all strings inside the value, names of undefined variables and invalid
+/// references are collected to report them all at once.
+fn interpolate_value(
+    value: &mut Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    undefined: &mut Vec<String>,
+    invalid: &mut Vec<String>,
+) {
+    match value {
+        Value::String(string) => match interpolate(string, lookup, undefined) {
+            Ok(interpolated) => *string = interpolated,
+            Err(e) => invalid.push(e),
+        },
+        Value::Array(array) => {
+            for value in array {
+                interpolate_value(value, lookup, undefined, invalid);
+            }
+        }
+        Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value, lookup, undefined, invalid);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn interpolate(
+    string: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    undefined: &mut Vec<String>,
+) -> Result<String, String> {
+    let mut result = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(position) = rest.find('$') {
+        result.push_str(&rest[..position]);
+        rest = &rest[position..];
+
+        if let Some(after_escape) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = after_escape;
+            continue;
+        }
+
+        let reference = match rest.strip_prefix("${") {
+            Some(reference) => reference,
+            None => {
+                result.push('$');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let end = reference
+            .find('}')
+            .ok_or_else(|| format!(r#"unclosed "${{" in "{}""#, string))?;
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!(
+                r#"invalid environment variable name "{}" in "{}""#,
+                name, string
+            ));
+        }
+
+        match (lookup(name), default) {
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => undefined.push(name.to_string()),
+        }
+
+        rest = &reference[end + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::interpolate;
+    use super::resolve_file;
+
+    use toml::Value;
+
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOST" => Some(String::from("relay.fluence.dev")),
+            "PORT" => Some(String::from("9990")),
+            _ => None,
+        }
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "marine-config-loader-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn interpolation() {
+        let mut undefined = Vec::new();
+
+        let result = interpolate("/dns4/${HOST}/tcp/${PORT}", &lookup, &mut undefined);
+        assert_eq!(result.unwrap(), "/dns4/relay.fluence.dev/tcp/9990");
+
+        let result = interpolate("${LEVEL:-info},${HOST:-localhost}", &lookup, &mut undefined);
+        assert_eq!(result.unwrap(), "info,relay.fluence.dev");
+
+        let result = interpolate("$${HOST} costs $5", &lookup, &mut undefined);
+        assert_eq!(result.unwrap(), "${HOST} costs $5");
+
+        assert!(undefined.is_empty());
+
+        let result = interpolate("${USER}:${PASSWORD}", &lookup, &mut undefined);
+        assert_eq!(result.unwrap(), ":");
+        assert_eq!(undefined, vec!["USER", "PASSWORD"]);
+
+        assert!(interpolate("${HOST", &lookup, &mut undefined).is_err());
+        assert!(interpolate("${HOST NAME}", &lookup, &mut undefined).is_err());
+    }
+
+    #[test]
+    fn includes() {
+        let dir = test_dir("includes");
+        std::fs::create_dir(dir.join("common")).unwrap();
+        std::fs::write(
+            dir.join("common/default.toml"),
+            r#"
+            modules_dir = "artifacts"
+            total_memory_limit = "10 MiB"
+
+            [default]
+            logger_enabled = false
+            logging_mask = 1
+
+            [[module]]
+            name = "sqlite"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"
+            include = "common/default.toml"
+            env_interpolation = true
+            total_memory_limit = "${LIMIT:-20 MiB}"
+
+            [default]
+            logger_enabled = true
+
+            [[module]]
+            name = "facade"
+
+            [module.wasi]
+            envs = { "ADDR" = "${HOST}:${PORT}" }
+            "#,
+        )
+        .unwrap();
+
+        let config = resolve_file(
+            &dir.join("config.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap();
+
+        assert!(config.get("include").is_none());
+        assert!(config.get("env_interpolation").is_none());
+        assert_eq!(config["modules_dir"].as_str(), Some("artifacts"));
+        assert_eq!(config["total_memory_limit"].as_str(), Some("20 MiB"));
+        assert_eq!(config["default"]["logger_enabled"], Value::Boolean(true));
+        assert_eq!(config["default"]["logging_mask"], Value::Integer(1));
+
+        let modules = config["module"].as_array().unwrap();
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0]["name"].as_str(), Some("sqlite"));
+        assert_eq!(
+            modules[1]["wasi"]["envs"]["ADDR"].as_str(),
+            Some("relay.fluence.dev:9990")
+        );
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = test_dir("include-cycle");
+        std::fs::write(dir.join("a.toml"), r#"include = ["b.toml"]"#).unwrap();
+        std::fs::write(dir.join("b.toml"), r#"include = ["a.toml"]"#).unwrap();
+
+        let errors = resolve_file(
+            &dir.join("a.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(
+            errors[0].to_string().contains("include cycle"),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn common_fragment_is_merged_once() {
+        let dir = test_dir("diamond");
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"include = ["logger.toml", "wasi.toml"]"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("logger.toml"), r#"include = "common.toml""#).unwrap();
+        std::fs::write(dir.join("wasi.toml"), r#"include = "common.toml""#).unwrap();
+        std::fs::write(
+            dir.join("common.toml"),
+            r#"
+            [[module]]
+            name = "sqlite"
+            "#,
+        )
+        .unwrap();
+
+        let config = resolve_file(
+            &dir.join("config.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap();
+
+        let modules = config["module"].as_array().unwrap();
+        assert_eq!(modules.len(), 1, "{:?}", modules);
+    }
+
+    #[test]
+    fn undefined_variables_are_reported_together() {
+        let dir = test_dir("undefined-variables");
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"
+            env_interpolation = true
+            modules_dir = "${MODULES_DIR}"
+
+            [default.wasi]
+            envs = { "TOKEN" = "${TOKEN}" }
+            "#,
+        )
+        .unwrap();
+
+        let errors = resolve_file(
+            &dir.join("config.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        let error = errors[0].to_string();
+        assert!(error.contains("MODULES_DIR"), "{}", error);
+        assert!(error.contains("TOKEN"), "{}", error);
+    }
+
+    #[test]
+    fn interpolation_is_opt_in() {
+        let dir = test_dir("interpolation-opt-in");
+        std::fs::write(dir.join("config.toml"), r#"modules_dir = "${HOST}""#).unwrap();
+
+        let config = resolve_file(
+            &dir.join("config.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap();
+        assert_eq!(config["modules_dir"].as_str(), Some("${HOST}"));
+    }
+
+    #[test]
+    fn errors_of_all_fragments_are_reported() {
+        let dir = test_dir("fragment-errors");
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"include = ["logger.toml", "missing.toml", "wasi.toml"]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("logger.toml"),
+            r#"
+            env_interpolation = true
+            log_directives = "${LEVEL}"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("wasi.toml"),
+            r#"
+            env_interpolation = true
+            [default.wasi]
+            envs = { "ADDR" = "${HOST" }
+            "#,
+        )
+        .unwrap();
+
+        let errors = resolve_file(
+            &dir.join("config.toml"),
+            &lookup,
+            &mut Vec::new(),
+            &mut HashSet::new(),
+        )
+        .unwrap_err();
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(errors.len(), 3, "{:#?}", errors);
+        assert!(errors[0].contains("logger.toml: undefined environment variables: LEVEL"));
+        assert!(errors[1].contains("missing.toml"));
+        assert!(errors[2].contains(r#"wasi.toml: unclosed "${""#));
+    }
+}
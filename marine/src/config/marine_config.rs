@@ -20,6 +20,15 @@
 use marine_wasm_backend_traits::WasmBackend;
 use marine_core::generic::HostImportDescriptor;
 use marine_core::HostAPIVersion;
+use marine_core::ModuleSigner;
+use marine_core::EffectsPolicy;
+use marine_core::EffectKind;
+
+use regex::Regex;
+use crate::host_imports::logger::LoggerFilter;
+use crate::host_imports::HostImportProviders;
+use crate::host_imports::MountedBinariesLimiter;
+use crate::module_loading::ModuleHash;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -50,27 +59,56 @@ pub struct ModuleDescriptor<WB: WasmBackend> {
     pub load_from: Option<PathBuf>,
     pub file_name: String,
     pub import_name: String,
+    /// If set, the module is loaded only if its content has this hash.
+    pub hash: Option<ModuleHash>,
     pub config: MarineModuleConfig<WB>,
 }
 
 impl<WB: WasmBackend> ModuleDescriptor<WB> {
-    pub fn get_path(&self, modules_dir: &Option<PathBuf>) -> Result<PathBuf, MarineError> {
-        match &self.load_from {
-            None => match modules_dir {
-                Some(dir) => Ok(dir.join(Path::new(&self.file_name))),
-                None => Err(MarineError::InvalidConfig(format!(
-                    r#""modules_dir" field is not defined, but it is required to load module "{}""#,
-                    self.import_name
-                ))),
-            },
-            Some(path) => {
-                if path.is_file() {
-                    Ok(path.clone())
-                } else {
-                    Ok(path.join(Path::new(&self.file_name)))
-                }
+    /// Resolves a module path: `load_from` takes precedence, then a module with a hash
+    /// is looked up in the module store, and finally in the modules dir by its file name.
+    pub fn get_path(
+        &self,
+        modules_dir: &Option<PathBuf>,
+        module_store: &Option<PathBuf>,
+    ) -> Result<PathBuf, MarineError> {
+        module_path(
+            self.load_from.as_deref(),
+            self.hash.as_ref(),
+            &self.file_name,
+            &self.import_name,
+            modules_dir.as_deref(),
+            module_store.as_deref(),
+        )
+    }
+}
+
+/// Resolves a module path the way [`ModuleDescriptor::get_path`] does it,
+/// for tools working with configs without converting them.
+pub fn module_path(
+    load_from: Option<&Path>,
+    hash: Option<&ModuleHash>,
+    file_name: &str,
+    import_name: &str,
+    modules_dir: Option<&Path>,
+    module_store: Option<&Path>,
+) -> Result<PathBuf, MarineError> {
+    match (load_from, hash, module_store) {
+        (Some(path), _, _) => {
+            if path.is_file() {
+                Ok(path.to_path_buf())
+            } else {
+                Ok(path.join(file_name))
             }
         }
+        (None, Some(hash), Some(store)) => Ok(hash.store_path(store)),
+        (None, _, _) => match modules_dir {
+            Some(dir) => Ok(dir.join(file_name)),
+            None => Err(MarineError::InvalidConfig(format!(
+                r#""modules_dir" field is not defined, but it is required to load module "{}""#,
+                import_name
+            ))),
+        },
     }
 }
 
@@ -79,6 +117,10 @@ pub struct MarineConfig<WB: WasmBackend> {
     /// Path to a dir where compiled Wasm modules are located.
     pub modules_dir: Option<PathBuf>,
 
+    /// Path to a content-addressed store where modules with a hash are looked up
+    /// as `<algorithm>/<hex digest>.wasm`.
+    pub module_store: Option<PathBuf>,
+
     /// Total memory available for the service (in bytes)
     pub total_memory_limit: Option<u64>,
 
@@ -87,6 +129,23 @@ pub struct MarineConfig<WB: WasmBackend> {
 
     /// Settings for a module that name's not been found in modules_config.
     pub default_modules_config: Option<MarineModuleConfig<WB>>,
+
+    /// Host import providers available to modules by their names.
+    pub host_import_providers: HostImportProviders<WB>,
+
+    /// Limits how many mounted binaries of all modules could run at the same time.
+    /// If it's None, the binaries share a process-wide limit with other such services.
+    pub mounted_binaries_limiter: Option<MountedBinariesLimiter>,
+
+    /// Log directives in the WASM_LOG format (e.g. "info,module_name=debug"),
+    /// the WASM_LOG env variable is used if they aren't set.
+    pub log_directives: Option<String>,
+
+    /// If set, only modules signed by one of these signers could be loaded.
+    pub trusted_signers: Option<Vec<ModuleSigner>>,
+
+    /// If set, only modules which effects satisfy this policy could be loaded.
+    pub effects_policy: Option<EffectsPolicy>,
 }
 
 // Manual implementation because #[derive(Default)] does not allow direct usage of non-Default wasm backend.
@@ -94,13 +153,29 @@ impl<WB: WasmBackend> Default for MarineConfig<WB> {
     fn default() -> Self {
         Self {
             modules_dir: <_>::default(),
+            module_store: <_>::default(),
             total_memory_limit: <_>::default(),
             modules_config: <_>::default(),
             default_modules_config: <_>::default(),
+            host_import_providers: <_>::default(),
+            mounted_binaries_limiter: <_>::default(),
+            log_directives: <_>::default(),
+            trusted_signers: <_>::default(),
+            effects_policy: <_>::default(),
         }
     }
 }
 
+impl<WB: WasmBackend> MarineConfig<WB> {
+    /// Returns the expected hash of a module with the given import name, if it is specified.
+    pub(crate) fn module_hash(&self, import_name: &str) -> Option<&ModuleHash> {
+        self.modules_config
+            .iter()
+            .find(|module| module.import_name == import_name)
+            .and_then(|module| module.hash.as_ref())
+    }
+}
+
 /// Various settings that could be used to guide Marine how to load a module in a proper way.
 #[derive(Default)]
 pub struct MarineModuleConfig<WB: WasmBackend> {
@@ -111,11 +186,25 @@ pub struct MarineModuleConfig<WB: WasmBackend> {
     /// The imports are provided separately for each marine host api version
     pub host_imports: HashMap<HostAPIVersion, HashMap<String, HostImportDescriptor<WB>>>,
 
+    /// Names of host import providers registered on Marine, which imports this module should get.
+    pub host_import_providers: Vec<String>,
+
+    /// Host binaries that the module could run by the import names.
+    pub mounted_binaries: HashMap<String, MountedBinaryConfig>,
+
     /// A WASI config.
     pub wasi: Option<MarineWASIConfig>,
 
     /// Mask used to filter logs, for details see `log_utf8_string`
     pub logging_mask: i32,
+
+    /// Names of logging targets declared by the module, which should be enabled.
+    /// If set, the logging mask is made from masks of these targets instead of `logging_mask`,
+    /// so a TOML config can't set both of them.
+    pub log_targets: Option<Vec<String>>,
+
+    /// Limits applied to messages the module logs.
+    pub logger_limits: LoggerLimits,
 }
 
 impl<WB: WasmBackend> MarineModuleConfig<WB> {
@@ -141,6 +230,30 @@ impl<WB: WasmBackend> MarineModuleConfig<WB> {
             None => {}
         }
     }
+
+    pub fn root_mounted_binaries_at(&mut self, root: &Path) {
+        for mounted_binary in self.mounted_binaries.values_mut() {
+            mounted_binary.root_dir = Some(root.to_path_buf());
+        }
+    }
+}
+
+/// Limits applied to messages a module logs with `log_utf8_string`,
+/// they are checked before a message is read from the module memory.
+#[derive(Debug, Clone, Default)]
+pub struct LoggerLimits {
+    /// Messages over this number in a second are dropped.
+    pub max_messages_per_second: Option<u32>,
+
+    /// Max size of a message in bytes.
+    pub max_message_size: Option<u64>,
+
+    /// Max number of bytes the module could log during a call.
+    pub max_bytes_per_call: Option<u64>,
+
+    /// Messages exceeding the size limits are truncated and marked with `TRUNCATION_MARKER`
+    /// instead of being dropped.
+    pub truncate: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -152,10 +265,91 @@ pub struct MarineWASIConfig {
     pub mapped_dirs: HashMap<String, PathBuf>,
 }
 
+/// Describes how a host binary mounted to a module is run.
+#[derive(Debug, Clone, Default)]
+pub struct MountedBinaryConfig {
+    /// Path to the binary on the host.
+    pub path: PathBuf,
+
+    /// The binary is killed if it runs longer, the result then has the 100005 return code.
+    pub timeout: Option<Duration>,
+
+    /// Max size of stdout and of stderr of the binary, it's killed if one of them exceeds the limit
+    /// and the result then has the 100006 return code.
+    pub max_output_size: Option<u64>,
+
+    /// Environment variables of the host passed to the binary, all of them are passed if it's None.
+    pub env_allowlist: Option<Vec<String>>,
+
+    /// Environment variables set for the binary, they take precedence over the host ones.
+    pub envs: HashMap<String, String>,
+
+    /// Working directory of the binary relative to `root_dir`, the binary isn't run
+    /// if it points outside of `root_dir`, e.g. with `..` or a symlink.
+    pub working_dir: Option<PathBuf>,
+
+    /// A dir the working dir is resolved against and can't leave, AppService sets it to
+    /// the service working dir. The current dir of the process is used if it's None.
+    pub root_dir: Option<PathBuf>,
+
+    /// Resource limits of the binary process.
+    pub rlimits: MountedBinaryRLimits,
+
+    /// Restrictions on arguments the binary could be run with.
+    pub arg_policy: MountedBinaryArgPolicy,
+}
+
+/// Resource limits applied to a mounted binary process, only supported on unix.
+/// The result has the 100007 return code if the binary is killed for exceeding the CPU time
+/// or the file size limit. Exceeding the other ones isn't fatal: allocations or opening files
+/// fail inside the binary, so it's up to the binary how to report that.
+#[derive(Debug, Clone, Default)]
+pub struct MountedBinaryRLimits {
+    /// CPU time in seconds.
+    pub cpu_time: Option<u64>,
+
+    /// Size of the virtual memory in bytes.
+    pub address_space: Option<u64>,
+
+    /// Size of files the binary could create in bytes.
+    pub file_size: Option<u64>,
+
+    /// Number of file descriptors the binary could open.
+    pub open_files: Option<u64>,
+}
+
+/// Restrictions on arguments of a mounted binary, checked before the binary is run.
+/// The result has the 100008 return code if the arguments violate them.
+///
+/// Arguments starting with `-` are flags, the ones starting with a single `-` are also
+/// checked as a cluster of one-letter flags, so `-so` is both `-so` and `-s`, `-o`.
+/// A flag value after `=` isn't a part of the flag. All the other arguments and
+/// all the ones after `--` are positional, including values of flags passed separately.
+#[derive(Debug, Clone, Default)]
+pub struct MountedBinaryArgPolicy {
+    /// Flags the binary could be run with, all flags are allowed if it's None.
+    pub allowed_flags: Option<Vec<String>>,
+
+    /// Flags the binary couldn't be run with.
+    pub forbidden_flags: Vec<String>,
+
+    /// If not empty, each positional argument and each value passed as `--flag=value`
+    /// must match one of these patterns as a whole.
+    pub positional_patterns: Vec<Regex>,
+
+    /// Max number of arguments.
+    pub max_args_count: Option<usize>,
+}
+
 use super::TomlMarineConfig;
 use super::TomlMarineModuleConfig;
 use super::TomlWASIConfig;
 use super::TomlMarineNamedModuleConfig;
+use super::TomlMountedBinary;
+use super::TomlMountedBinaryConfig;
+use super::TomlLoggerLimits;
+use super::TomlMountedBinaryRLimits;
+use super::TomlMountedBinaryArgPolicy;
 use crate::MarineError;
 use crate::MarineResult;
 use crate::config::as_relative_to_base;
@@ -163,6 +357,8 @@ use crate::config::raw_marine_config::MemoryLimit;
 
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::num::NonZeroUsize;
+use std::time::Duration;
 
 impl<WB: WasmBackend> TryFrom<TomlMarineConfig> for MarineConfig<WB> {
     type Error = MarineError;
@@ -178,6 +374,11 @@ impl<WB: WasmBackend> TryFrom<TomlMarineConfig> for MarineConfig<WB> {
             .map(|dir| as_relative_to_base(context.base_path.as_deref(), &dir))
             .transpose()?;
 
+        let module_store = toml_config
+            .module_store
+            .map(|dir| as_relative_to_base(context.base_path.as_deref(), &dir))
+            .transpose()?;
+
         let default_modules_config = toml_config
             .default
             .map(|m| context.wrapped(m).try_into())
@@ -189,18 +390,133 @@ impl<WB: WasmBackend> TryFrom<TomlMarineConfig> for MarineConfig<WB> {
             .map(|toml_module| ModuleDescriptor::try_from(context.wrapped(toml_module)))
             .collect::<MarineResult<Vec<_>>>()?;
 
+        let trusted_signers = toml_config
+            .trusted_signers
+            .map(|signers| {
+                signers
+                    .iter()
+                    .map(|signer| trusted_signer(signer))
+                    .collect::<MarineResult<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let effects_policy =
+            effects_policy(toml_config.allowed_effects, toml_config.denied_effects)?;
+
         let total_memory_limit = match toml_config.total_memory_limit {
             MemoryLimit::Infinity => None,
             MemoryLimit::Value(bytesize) => Some(bytesize.as_u64()),
         };
 
+        let mounted_binaries_limiter =
+            mounted_binaries_limiter(toml_config.max_concurrent_mounted_binaries)?;
+
         Ok(MarineConfig {
             modules_dir,
+            module_store,
             total_memory_limit,
             modules_config,
             default_modules_config,
+            host_import_providers: <_>::default(),
+            mounted_binaries_limiter,
+            log_directives: log_directives(toml_config.log_directives)?,
+            trusted_signers,
+            effects_policy,
+        })
+    }
+}
+
+// Validators of separate fields, they are shared with `check_config` to report all errors at once.
+
+pub(crate) fn trusted_signer(signer: &str) -> MarineResult<ModuleSigner> {
+    signer
+        .parse::<ModuleSigner>()
+        .map_err(|e| MarineError::InvalidConfig(format!("trusted signer {}: {}", signer, e)))
+}
+
+pub(crate) fn module_hash(module_name: &str, hash: &str) -> MarineResult<ModuleHash> {
+    hash.parse::<ModuleHash>()
+        .map_err(|e| MarineError::InvalidConfig(format!("module \"{}\" hash: {}", module_name, e)))
+}
+
+pub(crate) fn module_file_name(module_name: &str, file_name: Option<&str>) -> String {
+    match file_name {
+        Some(file_name) => file_name.to_string(),
+        None => format!("{}.wasm", module_name),
+    }
+}
+
+/// Directives are checked here, since the logger only warns about invalid ones and ignores them.
+pub(crate) fn log_directives(log_directives: Option<String>) -> MarineResult<Option<String>> {
+    if let Some(directives) = &log_directives {
+        let (_, warnings) = LoggerFilter::parse(directives);
+        if !warnings.is_empty() {
+            return Err(MarineError::InvalidConfig(format!(
+                "log_directives: {}",
+                warnings.join(", ")
+            )));
+        }
+    }
+
+    Ok(log_directives)
+}
+
+pub(crate) fn logging_mask(
+    logging_mask: Option<i32>,
+    log_targets: &Option<Vec<String>>,
+) -> MarineResult<i32> {
+    match (logging_mask, log_targets) {
+        (Some(_), Some(_)) => Err(MarineError::InvalidConfig(String::from(
+            "logging_mask and log_targets couldn't be set together, \
+             since the logging mask is made from the log targets",
+        ))),
+        (logging_mask, _) => Ok(logging_mask.unwrap_or(i32::max_value())),
+    }
+}
+
+pub(crate) fn mounted_binaries_limiter(
+    max_concurrent_mounted_binaries: Option<usize>,
+) -> MarineResult<Option<MountedBinariesLimiter>> {
+    max_concurrent_mounted_binaries
+        .map(|max| {
+            NonZeroUsize::new(max)
+                .map(MountedBinariesLimiter::new)
+                .ok_or_else(|| {
+                    MarineError::InvalidConfig(String::from(
+                        "max_concurrent_mounted_binaries should be greater than 0",
+                    ))
+                })
         })
+        .transpose()
+}
+
+pub(crate) fn effects_policy(
+    allowed: Option<Vec<String>>,
+    denied: Option<Vec<String>>,
+) -> MarineResult<Option<EffectsPolicy>> {
+    fn parse_kinds(kinds: Vec<String>, field: &str) -> MarineResult<Vec<EffectKind>> {
+        kinds
+            .iter()
+            .map(|kind| {
+                kind.parse::<EffectKind>()
+                    .map_err(|e| MarineError::InvalidConfig(format!("{}: {}", field, e)))
+            })
+            .collect()
+    }
+
+    if allowed.is_none() && denied.is_none() {
+        return Ok(None);
     }
+
+    let allowed = allowed
+        .map(|kinds| parse_kinds(kinds, "allowed_effects"))
+        .transpose()?;
+    let denied = denied
+        .map(|kinds| parse_kinds(kinds, "denied_effects"))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(Some(EffectsPolicy { allowed, denied }))
 }
 
 impl<'c, WB: WasmBackend> TryFrom<WithContext<'c, TomlMarineNamedModuleConfig>>
@@ -214,16 +530,22 @@ impl<'c, WB: WasmBackend> TryFrom<WithContext<'c, TomlMarineNamedModuleConfig>>
             data: config,
         } = config;
 
-        let file_name = config.file_name.unwrap_or(format!("{}.wasm", config.name));
+        let file_name = module_file_name(&config.name, config.file_name.as_deref());
         let load_from = config
             .load_from
             .map(|path| as_relative_to_base(context.base_path.as_deref(), &path))
             .transpose()?;
 
+        let hash = config
+            .hash
+            .map(|hash| module_hash(&config.name, &hash))
+            .transpose()?;
+
         Ok(ModuleDescriptor {
             load_from,
             file_name,
             import_name: config.name,
+            hash,
             config: context.wrapped(config.config).try_into()?,
         })
     }
@@ -243,39 +565,185 @@ impl<'c, WB: WasmBackend> TryFrom<WithContext<'c, TomlMarineModuleConfig>>
         let mounted_binaries = toml_config.mounted_binaries.unwrap_or_default();
         let mounted_binaries = mounted_binaries
             .into_iter()
-            .map(|(import_func_name, host_cmd)| {
-                let host_cmd = host_cmd.try_into::<PathBuf>()?;
-                Ok((import_func_name, host_cmd))
+            .map(|(import_func_name, mounted_binary)| {
+                let mounted_binary = mounted_binary.try_into::<TomlMountedBinary>()?;
+                let mounted_binary = context.wrapped(mounted_binary).try_into()?;
+                Ok((import_func_name, mounted_binary))
             })
-            .collect::<Result<Vec<_>, Self::Error>>()?;
+            .collect::<Result<HashMap<_, _>, Self::Error>>()?;
 
-        let mut host_imports = HashMap::from([
+        let host_imports = HashMap::from([
             (HostAPIVersion::V0, HashMap::new()),
             (HostAPIVersion::V1, HashMap::new()),
             (HostAPIVersion::V2, HashMap::new()),
             (HostAPIVersion::V3, HashMap::new()),
         ]);
-        for (import_name, host_cmd) in mounted_binaries {
-            let host_cmd = as_relative_to_base(context.base_path.as_deref(), &host_cmd)?;
-            for (_, host_cli_imports) in &mut host_imports {
-                host_cli_imports.insert(
-                    import_name.clone(),
-                    crate::host_imports::create_mounted_binary_import(host_cmd.clone()),
-                );
-            }
-        }
 
         let wasi = toml_config.wasi.map(|w| w.try_into()).transpose()?;
 
         Ok(MarineModuleConfig {
             logger_enabled: toml_config.logger_enabled.unwrap_or(true),
             host_imports,
+            host_import_providers: toml_config.host_imports.unwrap_or_default(),
+            mounted_binaries,
             wasi,
-            logging_mask: toml_config.logging_mask.unwrap_or(i32::max_value()),
+            logging_mask: logging_mask(toml_config.logging_mask, &toml_config.log_targets)?,
+            log_targets: toml_config.log_targets,
+            logger_limits: toml_config
+                .logger_limits
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl TryFrom<TomlLoggerLimits> for LoggerLimits {
+    type Error = MarineError;
+
+    fn try_from(limits: TomlLoggerLimits) -> Result<Self, Self::Error> {
+        // a zero limit drops all messages, disabling the logger is the way to do it
+        fn non_zero<T: Default + PartialEq>(
+            limit: Option<T>,
+            field: &str,
+        ) -> MarineResult<Option<T>> {
+            match limit {
+                Some(limit) if limit == T::default() => Err(MarineError::InvalidConfig(format!(
+                    "logger_limits: {} should be greater than 0",
+                    field
+                ))),
+                limit => Ok(limit),
+            }
+        }
+
+        Ok(Self {
+            max_messages_per_second: non_zero(
+                limits.max_messages_per_second,
+                "max_messages_per_second",
+            )?,
+            max_message_size: non_zero(
+                limits.max_message_size.map(|size| size.as_u64()),
+                "max_message_size",
+            )?,
+            max_bytes_per_call: non_zero(
+                limits.max_bytes_per_call.map(|size| size.as_u64()),
+                "max_bytes_per_call",
+            )?,
+            truncate: limits.truncate.unwrap_or(false),
         })
     }
 }
 
+impl<'c> TryFrom<WithContext<'c, TomlMountedBinary>> for MountedBinaryConfig {
+    type Error = MarineError;
+
+    fn try_from(mounted_binary: WithContext<'c, TomlMountedBinary>) -> Result<Self, Self::Error> {
+        let WithContext {
+            context,
+            data: mounted_binary,
+        } = mounted_binary;
+
+        let config = match mounted_binary {
+            TomlMountedBinary::Path(path) => TomlMountedBinaryConfig {
+                path,
+                ..<_>::default()
+            },
+            TomlMountedBinary::Config(config) => *config,
+        };
+
+        let path = as_relative_to_base(context.base_path.as_deref(), &config.path)?;
+        let timeout = config
+            .timeout
+            .map(|timeout| {
+                humantime::parse_duration(&timeout).map_err(|e| {
+                    MarineError::InvalidConfig(format!(
+                        r#"invalid timeout "{}" of mounted binary {}: {}"#,
+                        timeout,
+                        path.display(),
+                        e
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let TomlMountedBinaryArgPolicy {
+            allowed_flags,
+            forbidden_flags,
+            positional_patterns,
+            max_args_count,
+        } = config.arg_policy.unwrap_or_default();
+
+        let positional_patterns = positional_patterns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| {
+                // patterns must match an argument as a whole
+                Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+                    MarineError::InvalidConfig(format!(
+                        r#"invalid argument pattern "{}" of mounted binary {}: {}"#,
+                        pattern,
+                        path.display(),
+                        e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let TomlMountedBinaryRLimits {
+            cpu_time,
+            address_space,
+            file_size,
+            open_files,
+        } = config.rlimits.unwrap_or_default();
+
+        let working_dir = config
+            .working_dir
+            .map(|dir| check_working_dir(dir, &path))
+            .transpose()?;
+
+        Ok(MountedBinaryConfig {
+            path,
+            timeout,
+            max_output_size: config.max_output_size.map(|size| size.as_u64()),
+            env_allowlist: config.env_allowlist,
+            envs: config.envs.unwrap_or_default(),
+            working_dir,
+            root_dir: None,
+            rlimits: MountedBinaryRLimits {
+                cpu_time,
+                address_space: address_space.map(|size| size.as_u64()),
+                file_size: file_size.map(|size| size.as_u64()),
+                open_files,
+            },
+            arg_policy: MountedBinaryArgPolicy {
+                allowed_flags,
+                forbidden_flags: forbidden_flags.unwrap_or_default(),
+                positional_patterns,
+                max_args_count,
+            },
+        })
+    }
+}
+
+/// The working dir must be a relative path without `..`, so that it stays inside the root dir.
+fn check_working_dir(working_dir: PathBuf, binary_path: &Path) -> MarineResult<PathBuf> {
+    use std::path::Component;
+
+    let inside_root = working_dir
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if !inside_root {
+        return Err(MarineError::InvalidConfig(format!(
+            r#"working dir "{}" of mounted binary {} must be a relative path without "..""#,
+            working_dir.display(),
+            binary_path.display()
+        )));
+    }
+
+    Ok(working_dir)
+}
+
 impl TryFrom<TomlWASIConfig> for MarineWASIConfig {
     type Error = MarineError;
 
@@ -298,11 +766,20 @@ impl TryFrom<TomlWASIConfig> for MarineWASIConfig {
         };
 
         let envs = toml_config.envs.unwrap_or_default();
-        let envs = envs
+        let mut envs = envs
             .into_iter()
             .map(to_string)
             .collect::<Result<HashMap<_, _>, _>>()?;
 
+        for name in toml_config.env_allowlist.unwrap_or_default() {
+            if envs.contains_key(&name) {
+                continue;
+            }
+            if let Ok(value) = std::env::var(&name) {
+                envs.insert(name, value);
+            }
+        }
+
         let mapped_dirs = toml_config.mapped_dirs.unwrap_or_default();
         let mapped_dirs = mapped_dirs
             .into_iter()
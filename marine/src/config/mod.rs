@@ -17,6 +17,8 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+mod config_check;
+mod config_loader;
 mod raw_marine_config;
 mod to_marine_config;
 mod marine_config;
@@ -24,15 +26,28 @@ mod path_utils;
 
 pub use marine_config::ConfigContext;
 pub use marine_config::WithContext;
+pub use marine_config::LoggerLimits;
 pub use marine_config::MarineModuleConfig;
 pub use marine_config::MarineConfig;
 pub use marine_config::MarineWASIConfig;
 pub use marine_config::ModuleDescriptor;
+pub use marine_config::module_path;
+pub use marine_config::MountedBinaryArgPolicy;
+pub use marine_config::MountedBinaryConfig;
+pub use marine_config::MountedBinaryRLimits;
+
+pub use config_check::check_config;
+pub use config_loader::resolve_toml_config;
 
 pub use raw_marine_config::TomlMarineNamedModuleConfig;
 pub use raw_marine_config::TomlWASIConfig;
 pub use raw_marine_config::TomlMarineConfig;
 pub use raw_marine_config::TomlMarineModuleConfig;
+pub use raw_marine_config::TomlLoggerLimits;
+pub use raw_marine_config::TomlMountedBinary;
+pub use raw_marine_config::TomlMountedBinaryArgPolicy;
+pub use raw_marine_config::TomlMountedBinaryConfig;
+pub use raw_marine_config::TomlMountedBinaryRLimits;
 
 // reexport toml types, so users don't have to directly depend on the same version of toml crate
 pub use toml::Value as TomlValue;
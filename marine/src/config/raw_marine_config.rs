@@ -19,6 +19,7 @@
 
 use crate::MarineError;
 use crate::MarineResult;
+use super::resolve_toml_config;
 
 use bytesize::ByteSize;
 use serde_derive::Serialize;
@@ -26,25 +27,55 @@ use serde_derive::Deserialize;
 use serde_with::serde_as;
 use serde_with::skip_serializing_none;
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 
 /*
 An example of the config:
 
+# fragments are merged in order, this file goes last,
+# relative paths in them are resolved against the directory of this file
+include = ["common/effects.toml"]
+# enables ${VAR} references to environment variables in this file
+env_interpolation = true
+
 modules_dir = "wasm/artifacts/wasm_modules"
+module_store = "wasm/store"
+max_concurrent_mounted_binaries = 4
+denied_effects = ["mounted_binary", "filesystem"]
+
+[[module]]
+    name = "sqlite"
+    # loaded from wasm/store/blake3/<digest>.wasm
+    hash = "blake3:af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
 
 [[module]]
     name = "ipfs_node.wasm"
     mem_pages_count = 100
     logger_enabled = true
 
+    host_imports = ["kv"]
+
     [module.mounted_binaries]
     mysql = "/usr/bin/mysql"
     ipfs = "/usr/local/bin/ipfs"
 
+    [module.mounted_binaries.curl]
+    path = "/usr/bin/curl"
+    timeout = "10s"
+    max_output_size = "1 MiB"
+    env_allowlist = ["PATH"]
+    envs = { "HOME" = "/tmp" }
+    working_dir = "curl"
+    rlimits = { cpu_time = 5, address_space = "512 MiB" }
+    arg_policy = { forbidden_flags = ["-o", "--output"], positional_patterns = ["https://.*"] }
+
     [module.wasi]
-    envs = { "IPFS_ADDR" = "/dns4/relay02.fluence.dev/tcp/15001" }
+    # ${VAR} and ${VAR:-default} are replaced with host environment variables
+    envs = { "IPFS_ADDR" = "/dns4/${RELAY_HOST:-relay02.fluence.dev}/tcp/15001" }
+    # these host environment variables are passed to the module as is, if they are set
+    env_allowlist = ["RUST_LOG"]
     mapped_dirs = {"tmp" = "/Users/user/tmp"}
 
 [default]
@@ -63,7 +94,23 @@ modules_dir = "wasm/artifacts/wasm_modules"
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct TomlMarineConfig {
     pub modules_dir: Option<PathBuf>,
+    /// Content-addressed store where modules with a hash are looked up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_store: Option<PathBuf>,
     pub total_memory_limit: MemoryLimit,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_mounted_binaries: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_directives: Option<String>,
+    /// Base64 encoded ed25519 public keys, only modules signed by them could be loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted_signers: Option<Vec<String>>,
+    /// If set, modules could have only effects of these kinds, e.g. "logger" or "filesystem".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_effects: Option<Vec<String>>,
+    /// Modules couldn't have effects of these kinds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub denied_effects: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub module: Vec<TomlMarineNamedModuleConfig>,
     pub default: Option<TomlMarineModuleConfig>,
@@ -72,40 +119,37 @@ pub struct TomlMarineConfig {
 }
 
 impl TomlMarineConfig {
-    /// Load config from filesystem.
+    /// Load config from filesystem, resolving includes and, where it's enabled,
+    /// environment variables references.
     pub fn load<P: AsRef<Path>>(path: P) -> MarineResult<Self> {
-        let path = PathBuf::from(path.as_ref()).canonicalize().map_err(|e| {
-            MarineError::IOError(format!(
-                "failed to canonicalize path {}: {}",
-                path.as_ref().display(),
-                e
-            ))
-        })?;
-
-        let file_content = std::fs::read(&path).map_err(|e| {
-            MarineError::IOError(format!("failed to load {}: {}", path.display(), e))
-        })?;
-
-        let mut config: TomlMarineConfig = toml::from_slice(&file_content)?;
-
-        let default_base_path = Path::new("/");
-        config.base_path = path
-            .canonicalize()
-            .map_err(|e| {
-                MarineError::IOError(format!(
-                    "Failed to canonicalize config path {}: {}",
-                    path.display(),
-                    e
-                ))
-            })?
-            .parent()
-            .unwrap_or(default_base_path)
-            .to_path_buf();
+        let path = canonical_config_path(path.as_ref())?;
+
+        let config = resolve_toml_config(&path)?;
+        let mut config: TomlMarineConfig = toml::Value::Table(config).try_into()?;
+        config.base_path = config_base_path(&path);
 
         Ok(config)
     }
 }
 
+pub(crate) fn canonical_config_path(path: &Path) -> MarineResult<PathBuf> {
+    path.canonicalize().map_err(|e| {
+        MarineError::IOError(format!(
+            "failed to canonicalize path {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Relative paths in a config are resolved against the directory of the config file.
+pub(crate) fn config_base_path(canonical_path: &Path) -> PathBuf {
+    canonical_path
+        .parent()
+        .unwrap_or_else(|| Path::new("/"))
+        .to_path_buf()
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct TomlMarineNamedModuleConfig {
     pub name: String,
@@ -113,6 +157,9 @@ pub struct TomlMarineNamedModuleConfig {
     pub load_from: Option<PathBuf>,
     #[serde(default)]
     pub file_name: Option<String>,
+    /// Expected module hash in the `sha256:<hex>` or `blake3:<hex>` form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
     #[serde(flatten)]
     pub config: TomlMarineModuleConfig,
 }
@@ -122,13 +169,67 @@ pub struct TomlMarineNamedModuleConfig {
 pub struct TomlMarineModuleConfig {
     pub logger_enabled: Option<bool>,
     pub logging_mask: Option<i32>,
+    pub log_targets: Option<Vec<String>>,
+    pub host_imports: Option<Vec<String>>,
+    pub logger_limits: Option<TomlLoggerLimits>,
     pub wasi: Option<TomlWASIConfig>,
     pub mounted_binaries: Option<toml::value::Table>,
 }
 
+/// A mounted binary is described either by a path or by a table with the path and run settings.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TomlMountedBinary {
+    Path(PathBuf),
+    Config(Box<TomlMountedBinaryConfig>),
+}
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TomlMountedBinaryConfig {
+    pub path: PathBuf,
+    pub timeout: Option<String>,
+    pub max_output_size: Option<ByteSize>,
+    pub env_allowlist: Option<Vec<String>>,
+    pub working_dir: Option<PathBuf>,
+    pub envs: Option<HashMap<String, String>>,
+    pub rlimits: Option<TomlMountedBinaryRLimits>,
+    pub arg_policy: Option<TomlMountedBinaryArgPolicy>,
+}
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TomlMountedBinaryRLimits {
+    pub cpu_time: Option<u64>,
+    pub address_space: Option<ByteSize>,
+    pub file_size: Option<ByteSize>,
+    pub open_files: Option<u64>,
+}
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TomlMountedBinaryArgPolicy {
+    pub allowed_flags: Option<Vec<String>>,
+    pub forbidden_flags: Option<Vec<String>>,
+    pub positional_patterns: Option<Vec<String>>,
+    pub max_args_count: Option<usize>,
+}
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TomlLoggerLimits {
+    pub max_messages_per_second: Option<u32>,
+    pub max_message_size: Option<ByteSize>,
+    pub max_bytes_per_call: Option<ByteSize>,
+    pub truncate: Option<bool>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct TomlWASIConfig {
     pub envs: Option<toml::value::Table>,
+    /// Host environment variables passed to the module, `envs` take precedence over them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_allowlist: Option<Vec<String>>,
     pub mapped_dirs: Option<toml::value::Table>,
 }
 
@@ -147,6 +248,9 @@ mod tests {
     use super::TomlMarineNamedModuleConfig;
     use super::TomlMarineModuleConfig;
     use super::TomlWASIConfig;
+    use crate::MarineWASIConfig;
+
+    use std::convert::TryFrom;
 
     #[test]
     fn serialize_marine_named_module_config() {
@@ -160,11 +264,16 @@ mod tests {
             name: "name".to_string(),
             file_name: Some("file_name".to_string()),
             load_from: <_>::default(),
+            hash: <_>::default(),
             config: TomlMarineModuleConfig {
                 logger_enabled: Some(false),
                 logging_mask: Some(1),
+                log_targets: None,
+                host_imports: Some(vec!["kv".to_string()]),
+                logger_limits: None,
                 wasi: Some(TomlWASIConfig {
                     envs: None,
+                    env_allowlist: None,
                     mapped_dirs: None,
                 }),
                 mounted_binaries: Some(mounted_binaries),
@@ -173,4 +282,68 @@ mod tests {
 
         assert!(toml::to_string(&config).is_ok())
     }
+
+    #[test]
+    fn wasi_env_allowlist() {
+        let mut envs = toml::value::Table::new();
+        envs.insert("HOME".to_string(), toml::Value::String("/tmp".to_string()));
+
+        let config = TomlWASIConfig {
+            envs: Some(envs),
+            env_allowlist: Some(vec![
+                "PATH".to_string(),
+                "HOME".to_string(),
+                "MARINE_UNSET_TEST_VARIABLE".to_string(),
+            ]),
+            mapped_dirs: None,
+        };
+
+        let config = MarineWASIConfig::try_from(config).unwrap();
+
+        assert_eq!(config.envs.get("PATH"), std::env::var("PATH").ok().as_ref());
+        assert_eq!(config.envs.get("HOME").map(String::as_str), Some("/tmp"));
+        assert!(!config.envs.contains_key("MARINE_UNSET_TEST_VARIABLE"));
+    }
+
+    #[test]
+    fn mounted_binary_working_dir_stays_inside_root() {
+        use super::TomlMountedBinary;
+        use super::TomlMountedBinaryConfig;
+        use crate::ConfigContext;
+        use crate::MountedBinaryConfig;
+
+        let context = ConfigContext::default();
+        let mounted_binary = |working_dir: &str| {
+            let binary = TomlMountedBinary::Config(Box::new(TomlMountedBinaryConfig {
+                path: "/bin/sh".into(),
+                working_dir: Some(working_dir.into()),
+                ..<_>::default()
+            }));
+            MountedBinaryConfig::try_from(context.wrapped(binary))
+        };
+
+        assert!(mounted_binary("./curl/data").is_ok());
+        assert!(mounted_binary("curl/../../etc").is_err());
+        assert!(mounted_binary("/etc").is_err());
+    }
+
+    #[test]
+    fn zero_mounted_binaries_limit_is_rejected() {
+        use super::TomlMarineConfig;
+        use crate::config::marine_config::mounted_binaries_limiter;
+
+        let config: TomlMarineConfig = toml::from_str(
+            r#"
+            total_memory_limit = "Infinity"
+            max_concurrent_mounted_binaries = 0
+            module = []
+            "#,
+        )
+        .unwrap();
+        let error = mounted_binaries_limiter(config.max_concurrent_mounted_binaries).unwrap_err();
+        assert!(error.to_string().contains("greater than 0"), "{}", error);
+
+        assert!(mounted_binaries_limiter(Some(1)).unwrap().is_some());
+        assert!(mounted_binaries_limiter(None).unwrap().is_none());
+    }
 }
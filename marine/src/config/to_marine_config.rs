@@ -18,12 +18,21 @@
  */
 
 use crate::MarineWASIConfig;
+use crate::MountedBinaryConfig;
 use crate::MarineResult;
 use crate::config::MarineModuleConfig;
+use crate::config::LoggerLimits;
 use crate::host_imports::logger::log_utf8_string_closure;
-use crate::host_imports::logger::LoggerFilter;
+use crate::host_imports::logger::log_utf8_string_signature;
+use crate::host_imports::logger::SharedLoggerSettings;
+use crate::host_imports::logger::LogCapture;
 use crate::host_imports::logger::WASM_LOG_ENV_NAME;
 use crate::host_imports::create_call_parameters_import;
+use crate::host_imports::create_mounted_binary_import;
+use crate::host_imports::HostImportContext;
+use crate::host_imports::HostImportProviders;
+use crate::host_imports::MountedBinariesLimiter;
+use crate::MarineError;
 
 use marine_core::generic::HostImportDescriptor;
 use marine_core::generic::MModuleConfig;
@@ -33,6 +42,8 @@ use marine_wasm_backend_traits::WasmBackend;
 
 use marine_rs_sdk::CallParameters;
 
+use log::LevelFilter;
+
 use parking_lot::Mutex;
 use serde::Serialize;
 
@@ -50,6 +61,7 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(self) fn build(
         self,
         module_name: String,
@@ -58,7 +70,10 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
         call_parameters_v1: Arc<Mutex<marine_call_parameters_v1::CallParameters>>,
         call_parameters_v2: Arc<Mutex<marine_call_parameters_v2::CallParameters>>,
         call_parameters_v3: Arc<Mutex<CallParameters>>,
-        logger_filter: &LoggerFilter<'_>,
+        logger_settings: &SharedLoggerSettings,
+        log_capture: LogCapture,
+        host_import_providers: &HostImportProviders<WB>,
+        mounted_binaries_limiter: Option<&MountedBinariesLimiter>,
     ) -> MarineResult<MModuleConfig<WB>> {
         let marine_module_config = match marine_module_config {
             Some(config) => config,
@@ -68,12 +83,30 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
         let MarineModuleConfig {
             logger_enabled,
             host_imports,
+            host_import_providers: enabled_providers,
+            mounted_binaries,
             wasi,
             logging_mask,
+            // already turned into the logging mask, since it requires the compiled module
+            log_targets: _,
+            logger_limits,
         } = marine_module_config;
 
+        let explicit_wasm_log = wasi
+            .as_ref()
+            .map_or(false, |wasi| wasi.envs.contains_key(WASM_LOG_ENV_NAME));
+
+        let context = HostImportContext::new(module_name.clone(), call_parameters_v3.clone());
         let config = self
-            .populate_logger(logger_enabled, logging_mask, logger_filter, module_name)
+            .populate_logger(
+                logger_enabled,
+                logging_mask,
+                logger_limits,
+                explicit_wasm_log,
+                logger_settings,
+                log_capture,
+                module_name.clone(),
+            )
             .populate_host_imports(
                 host_imports,
                 call_parameters_v0,
@@ -81,6 +114,8 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
                 call_parameters_v2,
                 call_parameters_v3,
             )
+            .populate_mounted_binaries(&module_name, mounted_binaries, mounted_binaries_limiter)
+            .populate_provided_imports(enabled_providers, host_import_providers, &context)?
             .populate_wasi(wasi)?
             .into_config();
 
@@ -93,7 +128,9 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
             None => return Ok(self),
         };
 
-        self.config.wasi_parameters.envs = wasi.envs;
+        // keep variables set by other parts of the config, e.g. WASM_LOG set by the logger,
+        // unless they are set explicitly in the wasi config
+        self.config.wasi_parameters.envs.extend(wasi.envs);
 
         self.config.wasi_parameters.mapped_dirs = wasi.mapped_dirs;
 
@@ -126,6 +163,69 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
             .add_call_parameters_import(HostAPIVersion::V3, call_parameters_v3)
     }
 
+    fn populate_mounted_binaries(
+        mut self,
+        module_name: &str,
+        mounted_binaries: HashMap<String, MountedBinaryConfig>,
+        limiter: Option<&MountedBinariesLimiter>,
+    ) -> Self {
+        use HostAPIVersion::*;
+        for (import_name, mounted_binary) in mounted_binaries {
+            for api_version in [V0, V1, V2, V3] {
+                self.config
+                    .host_imports
+                    .entry(api_version)
+                    .or_default()
+                    .insert(
+                        import_name.clone(),
+                        create_mounted_binary_import(
+                            module_name.to_string(),
+                            mounted_binary.clone(),
+                            limiter.cloned(),
+                        ),
+                    );
+            }
+        }
+
+        self
+    }
+
+    fn populate_provided_imports(
+        mut self,
+        enabled_providers: Vec<String>,
+        host_import_providers: &HostImportProviders<WB>,
+        context: &HostImportContext,
+    ) -> MarineResult<Self> {
+        for provider_name in enabled_providers {
+            let provider = host_import_providers.get(&provider_name).ok_or_else(|| {
+                MarineError::InvalidConfig(format!(
+                    r#"module "{}" enables host import provider "{}", but it isn't registered"#,
+                    context.module_name(),
+                    provider_name
+                ))
+            })?;
+
+            use HostAPIVersion::*;
+            for api_version in [V0, V1, V2, V3] {
+                let imports = self.config.host_imports.entry(api_version).or_default();
+                for (import_name, descriptor) in provider.host_imports(context) {
+                    if imports.contains_key(&import_name) {
+                        return Err(MarineError::InvalidConfig(format!(
+                            r#"host import "{}" of provider "{}" conflicts with another host import of module "{}""#,
+                            import_name,
+                            provider_name,
+                            context.module_name()
+                        )));
+                    }
+
+                    imports.insert(import_name, descriptor);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
     fn add_call_parameters_import<CP: Serialize + Send + 'static>(
         mut self,
         api_version: HostAPIVersion,
@@ -142,35 +242,43 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
         self
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn populate_logger(
         mut self,
         logger_enabled: bool,
         logging_mask: i32,
-        logger_filter: &LoggerFilter<'_>,
+        logger_limits: LoggerLimits,
+        explicit_wasm_log: bool,
+        logger_settings: &SharedLoggerSettings,
+        log_capture: LogCapture,
         module_name: String,
     ) -> Self {
         if !logger_enabled {
             return self;
         }
 
-        if let Some(level_filter) = logger_filter.module_level(&module_name) {
-            let log_level = level_filter.to_level();
-            let log_level_str = match log_level {
-                Some(log_level) => log_level.to_string(),
-                None => String::from("off"),
-            };
+        logger_settings.set_logging_mask(&module_name, logging_mask);
+        logger_settings.add_module(&module_name, explicit_wasm_log);
 
-            // overwrite possibly installed log variable in config
-            self.config
-                .wasi_parameters
-                .envs
-                .insert(WASM_LOG_ENV_NAME.to_string(), log_level_str);
-        }
+        // records are filtered by the logger settings, which could be changed at runtime,
+        // WASM_LOG set explicitly in the wasi config takes precedence over this one
+        self.config.wasi_parameters.envs.insert(
+            WASM_LOG_ENV_NAME.to_string(),
+            LevelFilter::max().to_string(),
+        );
 
+        let limiter = logger_settings.add_limiter(&module_name, logger_limits);
+        let logger_settings = logger_settings.clone();
         let creator = Arc::new(move |mut store: <WB as WasmBackend>::ContextMut<'_>| {
-            <WB as WasmBackend>::HostFunction::new_typed(
+            <WB as WasmBackend>::HostFunction::new_with_caller(
                 &mut store,
-                log_utf8_string_closure::<WB>(logging_mask, module_name.clone()),
+                log_utf8_string_signature(),
+                log_utf8_string_closure::<WB>(
+                    module_name.clone(),
+                    logger_settings.clone(),
+                    limiter.clone(),
+                    log_capture.clone(),
+                ),
             )
         });
 
@@ -192,6 +300,7 @@ impl<WB: WasmBackend> MModuleConfigBuilder<WB> {
 }
 
 /// Make Marine config from provided Marine config.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn make_marine_config<WB: WasmBackend>(
     module_name: String,
     marine_module_config: Option<MarineModuleConfig<WB>>,
@@ -199,7 +308,10 @@ pub(crate) fn make_marine_config<WB: WasmBackend>(
     call_parameters_v1: Arc<Mutex<marine_call_parameters_v1::CallParameters>>,
     call_parameters_v2: Arc<Mutex<marine_call_parameters_v2::CallParameters>>,
     call_parameters_v3: Arc<Mutex<marine_rs_sdk::CallParameters>>,
-    logger_filter: &LoggerFilter<'_>,
+    logger_settings: &SharedLoggerSettings,
+    log_capture: LogCapture,
+    host_import_providers: &HostImportProviders<WB>,
+    mounted_binaries_limiter: Option<&MountedBinariesLimiter>,
 ) -> MarineResult<MModuleConfig<WB>> {
     MModuleConfigBuilder::new().build(
         module_name,
@@ -208,6 +320,37 @@ pub(crate) fn make_marine_config<WB: WasmBackend>(
         call_parameters_v1,
         call_parameters_v2,
         call_parameters_v3,
-        logger_filter,
+        logger_settings,
+        log_capture,
+        host_import_providers,
+        mounted_binaries_limiter,
     )
 }
+
+#[cfg(all(test, feature = "default"))]
+mod tests {
+    use super::MModuleConfigBuilder;
+    use crate::host_imports::logger::WASM_LOG_ENV_NAME;
+    use crate::wasmtime::WasmBackend;
+    use crate::MarineWASIConfig;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn explicit_wasi_envs_take_precedence() {
+        let mut builder = MModuleConfigBuilder::<WasmBackend>::new();
+        let envs = &mut builder.config.wasi_parameters.envs;
+        envs.insert(WASM_LOG_ENV_NAME.to_string(), String::from("info"));
+        envs.insert(String::from("LOGGER_ONLY"), String::from("kept"));
+
+        let wasi = MarineWASIConfig {
+            envs: HashMap::from([(WASM_LOG_ENV_NAME.to_string(), String::from("trace"))]),
+            mapped_dirs: HashMap::new(),
+        };
+        let config = builder.populate_wasi(Some(wasi)).unwrap().into_config();
+
+        let envs = &config.wasi_parameters.envs;
+        assert_eq!(envs[WASM_LOG_ENV_NAME], "trace");
+        assert_eq!(envs["LOGGER_ONLY"], "kept");
+    }
+}
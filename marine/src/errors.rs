@@ -17,6 +17,8 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::module_loading::ModuleHash;
+
 use marine_core::MError;
 use marine_wasm_backend_traits::MemoryAllocationStats;
 use it_json_serde::ITJsonSeDeError;
@@ -42,6 +44,14 @@ pub enum MarineError {
         provided_modules: Vec<String>,
     },
 
+    /// A loaded module doesn't have the hash specified in config.
+    #[error("module {module_name} hash mismatch: expected {expected}, but it is {actual}")]
+    ModuleHashMismatch {
+        module_name: String,
+        expected: ModuleHash,
+        actual: ModuleHash,
+    },
+
     /// Various errors related to file i/o.
     #[error("IOError: {0}")]
     IOError(String),
@@ -74,6 +84,25 @@ pub enum MarineError {
         error: ITJsonSeDeError,
     },
 
+    /// Provided arguments in a binary encoding (MessagePack or CBOR)
+    /// aren't compatible with a called function signature.
+    #[error(r#"arguments from {encoding} deserialization error in module "{module_name}", function "{function_name}": {error}"#)]
+    ArgumentsDeserializationError {
+        module_name: String,
+        function_name: String,
+        encoding: &'static str,
+        error: ITJsonSeDeError,
+    },
+
+    /// Returned outputs can't be represented in a binary encoding (MessagePack or CBOR).
+    #[error(r#"output to {encoding} serialization error in module "{module_name}", function "{function_name}": {error}"#)]
+    OutputSerializationError {
+        module_name: String,
+        function_name: String,
+        encoding: &'static str,
+        error: ITJsonSeDeError,
+    },
+
     /// Errors related to invalid config.
     #[error("parsing config error: {0}")]
     ParseConfigError(#[from] toml::de::Error),
@@ -21,6 +21,8 @@ use crate::SecurityTetraplet;
 
 use marine_wasm_backend_traits::WasmBackend;
 use marine_core::generic::HostImportDescriptor;
+use marine_core::generic::HostImportFunc;
+use marine_core::HostImportError;
 
 use wasmer_it::IValue;
 use wasmer_it::IType;
@@ -41,16 +43,16 @@ pub(crate) fn create_call_parameters_import<WB: WasmBackend, CP: Serialize + Sen
         let result = {
             // a separate code block to unlock the mutex ASAP and to avoid double locking
             crate::to_interface_value(call_parameters.lock().deref())
-                .unwrap_or_else(|_| panic!("CallParameters should be convertible to IValue"))
+                .map_err(|e| HostImportError::HostClosureError(e.into()))?
         };
 
-        Some(result)
+        Ok(vec![result])
     };
 
     HostImportDescriptor {
-        host_exported_func: Box::new(call_parameters_closure),
+        host_exported_func: HostImportFunc::Sync(Box::new(call_parameters_closure)),
         argument_types: vec![],
-        output_type: Some(IType::Record(0)),
+        output_types: vec![IType::Record(0)],
         error_handler: None,
     }
 }
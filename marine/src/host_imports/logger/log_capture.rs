@@ -0,0 +1,78 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use parking_lot::Mutex;
+
+use std::sync::Arc;
+
+/// A log record emitted by a module with `log_utf8_string` during a call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleLogRecord {
+    pub level: log::Level,
+    /// Import name of the module emitted this record.
+    pub module: String,
+    /// Target mask supplied by the module, 0 means the default target.
+    pub target: i32,
+    pub message: String,
+}
+
+/// Collects log records emitted by all modules of a Marine instance,
+/// while capturing is enabled for the current call.
+#[derive(Clone, Default)]
+pub(crate) struct LogCapture {
+    records: Arc<Mutex<Option<Vec<ModuleLogRecord>>>>,
+}
+
+/// Stops capturing on drop, so records aren't collected after a cancelled call.
+pub(crate) struct LogCaptureGuard {
+    capture: LogCapture,
+}
+
+impl LogCapture {
+    pub(crate) fn start(&self) -> LogCaptureGuard {
+        *self.records.lock() = Some(Vec::new());
+
+        LogCaptureGuard {
+            capture: self.clone(),
+        }
+    }
+
+    /// Returns true if there is a call capturing logs right now.
+    pub(crate) fn is_active(&self) -> bool {
+        self.records.lock().is_some()
+    }
+
+    pub(crate) fn push(&self, record: ModuleLogRecord) {
+        if let Some(records) = self.records.lock().as_mut() {
+            records.push(record);
+        }
+    }
+}
+
+impl LogCaptureGuard {
+    pub(crate) fn finish(self) -> Vec<ModuleLogRecord> {
+        self.capture.records.lock().take().unwrap_or_default()
+    }
+}
+
+impl Drop for LogCaptureGuard {
+    fn drop(&mut self) {
+        self.capture.records.lock().take();
+    }
+}
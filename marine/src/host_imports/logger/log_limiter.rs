@@ -0,0 +1,219 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::config::LoggerLimits;
+
+use parking_lot::Mutex;
+
+use std::time::Duration;
+use std::time::Instant;
+
+/// Appended to messages truncated because of logger limits.
+pub const TRUNCATION_MARKER: &str = "...[truncated]";
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Numbers of messages of a module affected by its logger limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LogSuppressionStats {
+    pub dropped_messages: u64,
+    pub truncated_messages: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitDecision {
+    Log,
+    /// Only the given number of first bytes of the message should be logged.
+    Truncate(u64),
+    Drop,
+}
+
+/// Applies logger limits of a module to its messages, before they are read from the module memory.
+pub(crate) struct ModuleLogLimiter {
+    module_name: String,
+    limits: LoggerLimits,
+    state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+    window_start: Instant,
+    messages_in_window: u32,
+    dropped_in_window: u64,
+    bytes_in_call: u64,
+    stats: LogSuppressionStats,
+}
+
+impl ModuleLogLimiter {
+    pub(crate) fn new(module_name: String, limits: LoggerLimits) -> Self {
+        let state = LimiterState {
+            window_start: Instant::now(),
+            messages_in_window: 0,
+            dropped_in_window: 0,
+            bytes_in_call: 0,
+            stats: <_>::default(),
+        };
+
+        Self {
+            module_name,
+            limits,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Decides what to do with a message of the given size and accounts it.
+    pub(crate) fn check(&self, message_size: u64) -> LimitDecision {
+        let mut state = self.state.lock();
+
+        let now = Instant::now();
+        if now.duration_since(state.window_start) >= RATE_LIMIT_WINDOW {
+            if state.dropped_in_window != 0 {
+                log::warn!(
+                    "logger: {} messages of module {} were dropped because of logger limits",
+                    state.dropped_in_window,
+                    self.module_name
+                );
+            }
+            state.window_start = now;
+            state.messages_in_window = 0;
+            state.dropped_in_window = 0;
+        }
+
+        let decision = self.decide(&state, message_size);
+        match decision {
+            LimitDecision::Log => {
+                state.messages_in_window += 1;
+                state.bytes_in_call += message_size;
+            }
+            LimitDecision::Truncate(allowed_size) => {
+                state.messages_in_window += 1;
+                state.bytes_in_call += allowed_size;
+                state.stats.truncated_messages += 1;
+            }
+            LimitDecision::Drop => {
+                state.dropped_in_window += 1;
+                state.stats.dropped_messages += 1;
+            }
+        }
+
+        decision
+    }
+
+    /// Resets limits applied per call, called before every call to the module's service.
+    pub(crate) fn start_call(&self) {
+        self.state.lock().bytes_in_call = 0;
+    }
+
+    pub(crate) fn stats(&self) -> LogSuppressionStats {
+        self.state.lock().stats
+    }
+
+    fn decide(&self, state: &LimiterState, message_size: u64) -> LimitDecision {
+        let limits = &self.limits;
+
+        if let Some(max_messages) = limits.max_messages_per_second {
+            if state.messages_in_window >= max_messages {
+                return LimitDecision::Drop;
+            }
+        }
+
+        let call_budget = limits
+            .max_bytes_per_call
+            .map(|max_bytes| max_bytes.saturating_sub(state.bytes_in_call));
+        let allowed_size = [limits.max_message_size, call_budget]
+            .into_iter()
+            .flatten()
+            .min();
+
+        match allowed_size {
+            Some(allowed_size) if message_size > allowed_size => {
+                if limits.truncate && allowed_size != 0 {
+                    LimitDecision::Truncate(allowed_size)
+                } else {
+                    LimitDecision::Drop
+                }
+            }
+            _ => LimitDecision::Log,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limits: LoggerLimits) -> ModuleLogLimiter {
+        ModuleLogLimiter::new(String::from("module"), limits)
+    }
+
+    #[test]
+    fn messages_per_second() {
+        let limiter = limiter(LoggerLimits {
+            max_messages_per_second: Some(2),
+            ..<_>::default()
+        });
+
+        assert_eq!(limiter.check(10), LimitDecision::Log);
+        assert_eq!(limiter.check(10), LimitDecision::Log);
+        assert_eq!(limiter.check(10), LimitDecision::Drop);
+
+        std::thread::sleep(RATE_LIMIT_WINDOW);
+        assert_eq!(limiter.check(10), LimitDecision::Log);
+        assert_eq!(
+            limiter.stats(),
+            LogSuppressionStats {
+                dropped_messages: 1,
+                truncated_messages: 0
+            }
+        );
+    }
+
+    #[test]
+    fn message_size() {
+        let dropping = limiter(LoggerLimits {
+            max_message_size: Some(5),
+            ..<_>::default()
+        });
+        assert_eq!(dropping.check(5), LimitDecision::Log);
+        assert_eq!(dropping.check(6), LimitDecision::Drop);
+
+        let truncating = limiter(LoggerLimits {
+            max_message_size: Some(5),
+            truncate: true,
+            ..<_>::default()
+        });
+        assert_eq!(truncating.check(6), LimitDecision::Truncate(5));
+        assert_eq!(truncating.stats().truncated_messages, 1);
+    }
+
+    #[test]
+    fn bytes_per_call() {
+        let limiter = limiter(LoggerLimits {
+            max_bytes_per_call: Some(10),
+            truncate: true,
+            ..<_>::default()
+        });
+
+        assert_eq!(limiter.check(6), LimitDecision::Log);
+        assert_eq!(limiter.check(6), LimitDecision::Truncate(4));
+        assert_eq!(limiter.check(1), LimitDecision::Drop);
+
+        limiter.start_call();
+        assert_eq!(limiter.check(10), LimitDecision::Log);
+    }
+}
@@ -17,45 +17,115 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use super::LogCapture;
+use super::ModuleLogRecord;
+use super::SharedLoggerSettings;
+use super::LimitDecision;
+use super::ModuleLogLimiter;
+use super::TRUNCATION_MARKER;
+
+use marine_core::HostImportError;
 use marine_wasm_backend_traits::AsContextMut;
+use marine_wasm_backend_traits::FuncSig;
 use marine_wasm_backend_traits::ImportCallContext;
 use marine_wasm_backend_traits::WasmBackend;
+use marine_wasm_backend_traits::WType;
+use marine_wasm_backend_traits::WValue;
 
 use it_memory_traits::Memory;
 use it_memory_traits::MemoryReadable;
+use it_memory_traits::MemoryView;
+
+use std::sync::Arc;
+
+pub(crate) fn log_utf8_string_signature() -> FuncSig {
+    FuncSig::new(vec![WType::I32; 4], vec![])
+}
 
 pub(crate) fn log_utf8_string_closure<WB: WasmBackend>(
-    logging_mask: i32,
     module: String,
-) -> impl Fn(<WB as WasmBackend>::ImportCallContext<'_>, i32, i32, i32, i32) {
-    move |ctx, level, target, msg_offset, msg_size| {
-        if target == 0 || target & logging_mask != 0 {
-            log_utf8_string::<WB>(&module, ctx, level, msg_offset, msg_size)
+    logger_settings: SharedLoggerSettings,
+    limiter: Arc<ModuleLogLimiter>,
+    log_capture: LogCapture,
+) -> impl for<'c> Fn(
+    <WB as WasmBackend>::ImportCallContext<'c>,
+    &[WValue],
+) -> anyhow::Result<Vec<WValue>>
+       + Send
+       + Sync
+       + 'static {
+    move |ctx, args| {
+        let (level, target, msg_offset, msg_size) = match args {
+            [WValue::I32(level), WValue::I32(target), WValue::I32(msg_offset), WValue::I32(msg_size)] => {
+                (*level, *target, *msg_offset, *msg_size)
+            }
+            _ => return Err(HostImportError::MismatchWValuesCount.into()),
+        };
+
+        let level = level_from_i32(level);
+        if !logger_settings.is_enabled(&module, level, target) {
+            return Ok(vec![]);
         }
+
+        // limits are checked before reading the message, so a dropped one costs nothing
+        let (msg_size, truncated) = match limiter.check(msg_size as u32 as u64) {
+            LimitDecision::Log => (msg_size, false),
+            LimitDecision::Truncate(allowed_size) => (allowed_size as u32 as i32, true),
+            LimitDecision::Drop => return Ok(vec![]),
+        };
+
+        log_utf8_string::<WB>(
+            &module,
+            &log_capture,
+            ctx,
+            level,
+            target,
+            msg_offset,
+            msg_size,
+            truncated,
+        )?;
+
+        Ok(vec![])
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn log_utf8_string<WB: WasmBackend>(
     module: &str,
+    log_capture: &LogCapture,
     mut ctx: <WB as WasmBackend>::ImportCallContext<'_>,
-    level: i32,
+    level: log::Level,
+    target: i32,
     msg_offset: i32,
     msg_size: i32,
-) {
-    let level = level_from_i32(level);
-    let msg = read_string::<WB>(&mut ctx, msg_offset, msg_size);
+    truncated: bool,
+) -> Result<(), HostImportError> {
+    let msg = read_string::<WB>(&mut ctx, msg_offset, msg_size, truncated)?;
 
     match msg {
-        Some(msg) => log::logger().log(
-            &log::Record::builder()
-                .args(format_args!("{}", msg))
-                .level(level)
-                .module_path(module.into())
-                .target(module)
-                .build(),
-        ),
+        Some(msg) => {
+            if log_capture.is_active() {
+                log_capture.push(ModuleLogRecord {
+                    level,
+                    module: module.to_string(),
+                    target,
+                    message: msg.clone(),
+                });
+            }
+
+            log::logger().log(
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(level)
+                    .module_path(module.into())
+                    .target(module)
+                    .build(),
+            )
+        }
         None => log::warn!("logger: incorrect UTF8 string's been supplied to logger"),
     }
+
+    Ok(())
 }
 
 #[inline]
@@ -63,10 +133,31 @@ fn read_string<WB: WasmBackend>(
     ctx: &mut <WB as WasmBackend>::ImportCallContext<'_>,
     offset: i32,
     size: i32,
-) -> Option<String> {
-    let view = ctx.memory(0).unwrap().view(); // TODO handle error
+    truncated: bool,
+) -> Result<Option<String>, HostImportError> {
+    let view = ctx.memory(0).ok_or(HostImportError::MissingMemory)?.view();
+    view.check_bounds(&mut ctx.as_context_mut(), offset as u32, size as u32)
+        .map_err(|e| HostImportError::LifterError(e.into()))?;
+
     let bytes = view.read_vec(&mut ctx.as_context_mut(), offset as u32, size as u32);
-    String::from_utf8(bytes).ok()
+    if !truncated {
+        return Ok(String::from_utf8(bytes).ok());
+    }
+
+    Ok(truncated_string(bytes))
+}
+
+/// Makes a string from first bytes of a message, dropping a char cut in the middle.
+fn truncated_string(mut bytes: Vec<u8>) -> Option<String> {
+    match std::str::from_utf8(&bytes) {
+        Ok(_) => {}
+        Err(e) if e.error_len().is_none() => bytes.truncate(e.valid_up_to()),
+        Err(_) => return None,
+    }
+
+    let mut message = String::from_utf8(bytes).ok()?;
+    message.push_str(TRUNCATION_MARKER);
+    Some(message)
 }
 
 #[inline]
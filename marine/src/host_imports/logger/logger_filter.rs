@@ -25,14 +25,26 @@ use std::collections::HashMap;
 /// This struct can be used to determine whether or not
 /// a log record should be written to the output.
 #[derive(Clone, Default, Eq, PartialEq, Debug)]
-pub(crate) struct LoggerFilter<'env_string> {
+pub(crate) struct LoggerFilter {
     default_log_level: Option<LevelFilter>,
-    module_levels: HashMap<&'env_string str, LevelFilter>,
+    module_levels: HashMap<String, LevelFilter>,
 }
 
-impl<'env_string> LoggerFilter<'env_string> {
+impl LoggerFilter {
     /// Parses a content of supplied variable in form of "module_name_1=log_level,module_name_2".
-    pub(crate) fn from_env_string(env: &'env_string str) -> Self {
+    pub(crate) fn from_env_string(env: &str) -> Self {
+        let (filter, warnings) = Self::parse(env);
+        for warning in warnings {
+            eprintln!("logger warning: {}", warning);
+        }
+
+        filter
+    }
+
+    /// Parses directives like `from_env_string`, but returns problems with ignored directives
+    /// instead of printing them.
+    pub(crate) fn parse(env: &str) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
         let mut module_levels = HashMap::new();
         let mut default_log_level: Option<LevelFilter> = None;
 
@@ -45,10 +57,7 @@ impl<'env_string> LoggerFilter<'env_string> {
             let part_0 = module_log_parts.next();
             let part_1 = module_log_parts.next().map(|s| s.trim());
             if let Some(part_3) = module_log_parts.next() {
-                eprintln!(
-                    "logger warning: invalid directive '{}', ignoring it",
-                    part_3
-                );
+                warnings.push(format!("invalid directive '{}', ignoring it", part_3));
                 continue;
             }
             let (module_name, module_log_level) = match (part_0, part_1) {
@@ -64,37 +73,39 @@ impl<'env_string> LoggerFilter<'env_string> {
                 (Some(module_name), Some(log_level)) => match log_level.parse() {
                     Ok(log_level) => (Some(module_name), log_level),
                     Err(e) => {
-                        eprintln!(
-                            "logger warning: invalid directive '{}', error '{}', ignoring it",
+                        warnings.push(format!(
+                            "invalid directive '{}', error '{}', ignoring it",
                             log_level, e
-                        );
+                        ));
                         continue;
                     }
                 },
                 d => {
-                    eprintln!("logger warning: invalid directive '{:?}', ignoring it", d);
+                    warnings.push(format!("invalid directive '{:?}', ignoring it", d));
                     continue;
                 }
             };
 
             match (module_name, &mut default_log_level) {
                 (Some(module_name), _) => {
-                    module_levels.insert(module_name, module_log_level);
+                    module_levels.insert(module_name.to_string(), module_log_level);
                 }
                 (None, Some(_)) => {
-                    eprintln!(
-                        "logger warning: can't set default level twice, '{}' ignored",
+                    warnings.push(format!(
+                        "can't set default level twice, '{}' ignored",
                         module_log_level
-                    );
+                    ));
                 }
                 (None, w) => *w = Some(module_log_level),
             }
         }
 
-        Self {
+        let filter = Self {
             default_log_level,
             module_levels,
-        }
+        };
+
+        (filter, warnings)
     }
 
     pub(crate) fn module_level(&self, module_name: &str) -> Option<LevelFilter> {
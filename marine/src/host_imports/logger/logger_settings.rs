@@ -0,0 +1,153 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::LoggerFilter;
+use super::LogSuppressionStats;
+use super::ModuleLogLimiter;
+use super::WASM_LOG_ENV_NAME;
+use crate::config::LoggerLimits;
+
+use log::LevelFilter;
+use parking_lot::RwLock;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The level the SDK logger of a module uses if WASM_LOG isn't set.
+const MODULE_DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Logger settings of all modules of a Marine instance, shared with their `log_utf8_string`
+/// imports so that they could be changed at runtime.
+#[derive(Clone)]
+pub(crate) struct SharedLoggerSettings {
+    settings: Arc<RwLock<LoggerSettings>>,
+}
+
+struct LoggerSettings {
+    filter: LoggerFilter,
+    logging_masks: HashMap<String, i32>,
+    limiters: HashMap<String, Arc<ModuleLogLimiter>>,
+    /// Levels applied to modules without a matching directive.
+    default_levels: HashMap<String, LevelFilter>,
+}
+
+impl SharedLoggerSettings {
+    /// Creates settings from log directives in the WASM_LOG format,
+    /// falls back to the WASM_LOG env variable if there are no directives.
+    pub(crate) fn new(log_directives: Option<&str>) -> Self {
+        let settings = LoggerSettings {
+            filter: make_filter(log_directives),
+            logging_masks: HashMap::new(),
+            limiters: HashMap::new(),
+            default_levels: HashMap::new(),
+        };
+
+        Self {
+            settings: Arc::new(RwLock::new(settings)),
+        }
+    }
+
+    pub(crate) fn set_log_directives(&self, log_directives: Option<&str>) {
+        self.settings.write().filter = make_filter(log_directives);
+    }
+
+    pub(crate) fn set_logging_mask(&self, module_name: impl Into<String>, logging_mask: i32) {
+        self.settings
+            .write()
+            .logging_masks
+            .insert(module_name.into(), logging_mask);
+    }
+
+    pub(crate) fn add_limiter(
+        &self,
+        module_name: impl Into<String>,
+        limits: LoggerLimits,
+    ) -> Arc<ModuleLogLimiter> {
+        let module_name = module_name.into();
+        let limiter = Arc::new(ModuleLogLimiter::new(module_name.clone(), limits));
+        self.settings
+            .write()
+            .limiters
+            .insert(module_name, limiter.clone());
+
+        limiter
+    }
+
+    /// Resets limits applied per call for all modules.
+    pub(crate) fn start_call(&self) {
+        for limiter in self.settings.read().limiters.values() {
+            limiter.start_call();
+        }
+    }
+
+    pub(crate) fn suppression_stats(&self) -> HashMap<String, LogSuppressionStats> {
+        self.settings
+            .read()
+            .limiters
+            .iter()
+            .map(|(module_name, limiter)| (module_name.clone(), limiter.stats()))
+            .collect()
+    }
+
+    /// Modules log with the most verbose level and their records are filtered here,
+    /// so that directives could be changed at runtime in both directions. Records of a module
+    /// without a directive are filtered by the level it would use by itself:
+    /// the default one of the SDK, or any if WASM_LOG is set explicitly for the module.
+    pub(crate) fn add_module(&self, module_name: impl Into<String>, explicit_wasm_log: bool) {
+        let default_level = match explicit_wasm_log {
+            true => LevelFilter::max(),
+            false => MODULE_DEFAULT_LOG_LEVEL,
+        };
+
+        self.settings
+            .write()
+            .default_levels
+            .insert(module_name.into(), default_level);
+    }
+
+    /// Returns true if a record with the given level and target should be logged.
+    /// Records with the 0 target are logged regardless of the logging mask.
+    pub(crate) fn is_enabled(&self, module_name: &str, level: log::Level, target: i32) -> bool {
+        let settings = self.settings.read();
+
+        let target_enabled = target == 0
+            || settings
+                .logging_masks
+                .get(module_name)
+                .map_or(true, |mask| target & mask != 0);
+        let level_enabled = settings
+            .filter
+            .module_level(module_name)
+            .or_else(|| settings.default_levels.get(module_name).copied())
+            .map_or(true, |level_filter| level <= level_filter);
+
+        target_enabled && level_enabled
+    }
+}
+
+fn make_filter(log_directives: Option<&str>) -> LoggerFilter {
+    match log_directives {
+        Some(log_directives) => LoggerFilter::from_env_string(log_directives),
+        None => {
+            // LoggerFilter can be initialized with an empty string
+            let wasm_log_env = std::env::var(WASM_LOG_ENV_NAME).unwrap_or_default();
+            LoggerFilter::from_env_string(&wasm_log_env)
+        }
+    }
+}
@@ -18,9 +18,21 @@
  */
 
 mod logger_filter;
+mod logger_settings;
+mod log_capture;
+mod log_limiter;
 mod log_utf8_string_impl;
 
 pub use marine_rs_sdk_main::WASM_LOG_ENV_NAME;
 
+pub use log_capture::ModuleLogRecord;
+pub use log_limiter::LogSuppressionStats;
+pub use log_limiter::TRUNCATION_MARKER;
+
 pub(crate) use logger_filter::LoggerFilter;
+pub(crate) use logger_settings::SharedLoggerSettings;
+pub(crate) use log_capture::LogCapture;
+pub(crate) use log_limiter::LimitDecision;
+pub(crate) use log_limiter::ModuleLogLimiter;
 pub(crate) use log_utf8_string_impl::log_utf8_string_closure;
+pub(crate) use log_utf8_string_impl::log_utf8_string_signature;
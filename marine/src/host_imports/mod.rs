@@ -20,9 +20,16 @@
 pub(crate) mod logger;
 mod call_parameters;
 mod mounted_binaries;
+mod provider;
 
 pub(crate) use call_parameters::create_call_parameters_import;
 pub(crate) use call_parameters::call_parameters_v3_to_v0;
 pub(crate) use call_parameters::call_parameters_v3_to_v1;
 pub(crate) use call_parameters::call_parameters_v3_to_v2;
 pub(crate) use mounted_binaries::create_mounted_binary_import;
+pub use mounted_binaries::MountedBinariesLimiter;
+pub use mounted_binaries::DEFAULT_MAX_RUNNING_BINARIES;
+
+pub use provider::HostImportContext;
+pub use provider::HostImportProvider;
+pub use provider::HostImportProviders;
@@ -17,67 +17,179 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::MountedBinaryArgPolicy;
+use crate::MountedBinaryConfig;
+use crate::MountedBinaryRLimits;
+
 use marine_wasm_backend_traits::WasmBackend;
 
 use marine_core::generic::HostImportDescriptor;
+use marine_core::generic::HostImportFunc;
+use marine_core::HostImportError;
 use marine_rs_sdk::MountedBinaryResult;
 
+use futures::channel::oneshot;
+use futures::FutureExt;
+use tokio::sync::Semaphore;
+
 use wasmer_it::IValue;
 use wasmer_it::IType;
 
+use std::ffi::OsString;
+use std::io::Read;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::Child;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+const TERMINATED_BY_SIGNAL_CODE: i32 = 100000;
+const COMMAND_ERROR_CODE: i32 = 100001;
+const TIMEOUT_CODE: i32 = 100005;
+const OUTPUT_LIMIT_EXCEEDED_CODE: i32 = 100006;
+const RLIMIT_EXCEEDED_CODE: i32 = 100007;
+const ARGUMENTS_REJECTED_CODE: i32 = 100008;
+
+/// How often a running binary is checked for exceeding its limits.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How many mounted binaries of all services without their own limiter could run at the same time.
+pub const DEFAULT_MAX_RUNNING_BINARIES: usize = 64;
+
+/// Limits how many mounted binaries of a service could run at the same time,
+/// calls over the limit wait for running binaries to finish. Clones share the same limit.
+#[derive(Clone, Debug)]
+pub struct MountedBinariesLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl MountedBinariesLimiter {
+    pub fn new(max_running_binaries: NonZeroUsize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_running_binaries.get())),
+        }
+    }
+
+    /// The process-wide limiter used by services which don't have their own one,
+    /// so that a thread isn't started for every call without a bound.
+    fn shared_default() -> &'static Self {
+        static DEFAULT_LIMITER: OnceLock<MountedBinariesLimiter> = OnceLock::new();
+
+        DEFAULT_LIMITER.get_or_init(|| {
+            // the constant isn't zero
+            let max_running_binaries = NonZeroUsize::new(DEFAULT_MAX_RUNNING_BINARIES).unwrap();
+            Self::new(max_running_binaries)
+        })
+    }
+}
 
 pub(crate) fn create_mounted_binary_import<WB: WasmBackend>(
-    mounted_binary_path: PathBuf,
+    module_name: String,
+    mounted_binary: MountedBinaryConfig,
+    limiter: Option<MountedBinariesLimiter>,
 ) -> HostImportDescriptor<WB> {
-    let host_cmd_closure = move |_ctx: &mut <WB as WasmBackend>::ImportCallContext<'_>,
-                                 raw_args: Vec<IValue>| {
-        let result =
-            mounted_binary_import_impl(&mounted_binary_path, raw_args).unwrap_or_else(Into::into);
+    let module_name = Arc::new(module_name);
+    let mounted_binary = Arc::new(mounted_binary);
+
+    let host_cmd_closure = move |raw_args: Vec<IValue>| {
+        let module_name = module_name.clone();
+        let mounted_binary = mounted_binary.clone();
+        let limiter = limiter.clone();
 
-        let raw_result = crate::to_interface_value(&result).unwrap();
+        async move {
+            let result = run_on_dedicated_thread(limiter, move || {
+                mounted_binary_import_impl(&module_name, &mounted_binary, raw_args)
+                    .unwrap_or_else(Into::into)
+            })
+            .await;
 
-        Some(raw_result)
+            let raw_result = crate::to_interface_value(&result)
+                .map_err(|e| HostImportError::HostClosureError(e.into()))?;
+
+            Ok(vec![raw_result])
+        }
+        .boxed()
     };
 
     HostImportDescriptor {
-        host_exported_func: Box::new(host_cmd_closure),
+        host_exported_func: HostImportFunc::Async(Box::new(host_cmd_closure)),
         argument_types: vec![IType::Array(Box::new(IType::String))],
-        output_type: Some(IType::Record(0)),
+        output_types: vec![IType::Record(0)],
         error_handler: None,
     }
 }
 
+/// Runs a mounted binary on a separate thread, so that the executor isn't blocked while it runs.
+/// Without a limiter the shared default one is used, so the number of threads is bounded.
+async fn run_on_dedicated_thread(
+    limiter: Option<MountedBinariesLimiter>,
+    run: impl FnOnce() -> MountedBinaryResult + Send + 'static,
+) -> MountedBinaryResult {
+    let limiter = limiter.unwrap_or_else(|| MountedBinariesLimiter::shared_default().clone());
+    // the semaphore is never closed, so acquiring never fails
+    let permit = limiter.semaphore.acquire_owned().await.ok();
+
+    let (sender, receiver) = oneshot::channel();
+    let spawn_result = std::thread::Builder::new()
+        .name(String::from("mounted-binary"))
+        .spawn(move || {
+            // the permit is held until the binary finishes, even if the call is cancelled
+            let _permit = permit;
+            // the receiver could be dropped if the call is cancelled
+            let _ = sender.send(run());
+        });
+
+    if let Err(e) = spawn_result {
+        return MountedBinaryResult::from_error(
+            COMMAND_ERROR_CODE,
+            format!("failed to spawn a thread for the mounted binary: {}", e),
+        );
+    }
+
+    receiver.await.unwrap_or_else(|_| {
+        MountedBinaryResult::from_error(COMMAND_ERROR_CODE, "mounted binary thread panicked")
+    })
+}
+
 fn mounted_binary_import_impl(
-    mounted_binary_path: &Path,
+    module_name: &str,
+    mounted_binary: &MountedBinaryConfig,
     raw_args: Vec<IValue>,
 ) -> Result<MountedBinaryResult, MountedBinaryResult> {
     let args = parse_args(raw_args)?;
 
-    let result = std::process::Command::new(mounted_binary_path)
-        .args(&args)
-        .output();
+    if let Err(reason) = check_arg_policy(&mounted_binary.arg_policy, &args) {
+        log::warn!(
+            r#"module "{}" tried to run `{} {:?}`, but the arguments are rejected: {}"#,
+            module_name,
+            mounted_binary.path.display(),
+            args,
+            reason
+        );
 
-    let result = match result {
-        Ok(output) => {
-            const TERMINATED_BY_SIGNAL_CODE: i32 = 100000;
-            let ret_code = output.status.code().unwrap_or(TERMINATED_BY_SIGNAL_CODE);
+        return Err(MountedBinaryResult::from_error(
+            ARGUMENTS_REJECTED_CODE,
+            format!("arguments are rejected: {}", reason),
+        ));
+    }
 
-            MountedBinaryResult {
-                ret_code,
-                error: String::new(),
-                stdout: output.stdout,
-                stderr: output.stderr,
-            }
-        }
+    let result = match run_mounted_binary(mounted_binary, &args) {
+        Ok(result) => result,
         Err(e) => {
-            const COMMAND_ERROR_CODE: i32 = 100001;
             let error = format!("{}", e);
 
             log::error!(
                 "error occurred on `{} {:?}`: {} ",
-                mounted_binary_path.display(),
+                mounted_binary.path.display(),
                 args,
                 e
             );
@@ -94,6 +206,415 @@ fn mounted_binary_import_impl(
     Ok(result)
 }
 
+fn check_arg_policy(policy: &MountedBinaryArgPolicy, args: &[String]) -> Result<(), String> {
+    if let Some(max_args_count) = policy.max_args_count {
+        if args.len() > max_args_count {
+            return Err(format!(
+                "{} arguments are passed, but at most {} are allowed",
+                args.len(),
+                max_args_count
+            ));
+        }
+    }
+
+    let mut only_positional = false;
+    for arg in args {
+        if !only_positional && arg == "--" {
+            only_positional = true;
+        } else if !only_positional && arg.starts_with('-') && arg.len() > 1 {
+            check_flag(policy, arg)?;
+        } else {
+            check_positional(policy, arg)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_flag(policy: &MountedBinaryArgPolicy, arg: &str) -> Result<(), String> {
+    let flag = arg.split('=').next().unwrap_or(arg);
+    // a single dash could precede several one-letter flags
+    let short_flags = match flag.strip_prefix('-') {
+        Some(letters) if !letters.starts_with('-') && letters.chars().count() > 1 => letters
+            .chars()
+            .map(|letter| format!("-{}", letter))
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+
+    let is_listed = |flags: &[String], flag: &str| flags.iter().any(|listed| listed == flag);
+
+    if is_listed(&policy.forbidden_flags, flag)
+        || short_flags
+            .iter()
+            .any(|short_flag| is_listed(&policy.forbidden_flags, short_flag))
+    {
+        return Err(format!(r#"flag "{}" is forbidden"#, arg));
+    }
+
+    if let Some(allowed_flags) = &policy.allowed_flags {
+        let is_allowed = is_listed(allowed_flags, flag)
+            || (!short_flags.is_empty()
+                && short_flags
+                    .iter()
+                    .all(|short_flag| is_listed(allowed_flags, short_flag)));
+
+        if !is_allowed {
+            return Err(format!(r#"flag "{}" isn't allowed"#, arg));
+        }
+    }
+
+    // a value passed as `--flag=value` is checked like a separate one
+    match arg.split_once('=') {
+        Some((_, value)) => check_positional(policy, value),
+        None => Ok(()),
+    }
+}
+
+fn check_positional(policy: &MountedBinaryArgPolicy, arg: &str) -> Result<(), String> {
+    if policy.positional_patterns.is_empty()
+        || policy
+            .positional_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(arg))
+    {
+        return Ok(());
+    }
+
+    Err(format!(
+        r#"argument "{}" doesn't match any of the allowed patterns"#,
+        arg
+    ))
+}
+
+enum Termination {
+    Exited(ExitStatus),
+    TimedOut,
+    OutputLimitExceeded,
+}
+
+fn run_mounted_binary(
+    mounted_binary: &MountedBinaryConfig,
+    args: &[String],
+) -> std::io::Result<MountedBinaryResult> {
+    let host_env = |name: &str| std::env::var_os(name);
+    let mut child = make_command(mounted_binary, args, &host_env)?.spawn()?;
+
+    let output_limit_exceeded = Arc::new(AtomicBool::new(false));
+    let stdout = read_output(
+        child.stdout.take(),
+        mounted_binary.max_output_size,
+        output_limit_exceeded.clone(),
+    );
+    let stderr = read_output(
+        child.stderr.take(),
+        mounted_binary.max_output_size,
+        output_limit_exceeded.clone(),
+    );
+
+    let deadline = mounted_binary
+        .timeout
+        .map(|timeout| Instant::now() + timeout);
+    let termination = wait_with_limits(&mut child, deadline, &output_limit_exceeded)?;
+
+    // children left running in the background could hold the pipes open
+    if let Termination::Exited(_) = termination {
+        kill_process_group(&child);
+    }
+
+    // readers finish as soon as all processes holding the pipes are terminated,
+    // but a process could leave the group, so readers aren't waited after the deadline
+    let stdout = join_output(stdout, deadline);
+    let stderr = join_output(stderr, deadline);
+    let outputs_read = stdout.is_some() && stderr.is_some();
+    let stdout = stdout.unwrap_or_default();
+    let stderr = stderr.unwrap_or_default();
+
+    let termination = match termination {
+        // the binary could exit before its output is read completely
+        Termination::Exited(_) if output_limit_exceeded.load(Ordering::Acquire) => {
+            Termination::OutputLimitExceeded
+        }
+        Termination::Exited(_) if !outputs_read => Termination::TimedOut,
+        termination => termination,
+    };
+
+    let (ret_code, error) = match termination {
+        Termination::Exited(status) => match status.code() {
+            Some(code) => (code, String::new()),
+            None if killed_by_rlimit(&status) => (
+                RLIMIT_EXCEEDED_CODE,
+                String::from("mounted binary exceeded its resource limits"),
+            ),
+            None => (TERMINATED_BY_SIGNAL_CODE, String::new()),
+        },
+        Termination::TimedOut => (
+            TIMEOUT_CODE,
+            format!(
+                "mounted binary didn't finish in {}",
+                humantime::format_duration(mounted_binary.timeout.unwrap_or_default())
+            ),
+        ),
+        Termination::OutputLimitExceeded => (
+            OUTPUT_LIMIT_EXCEEDED_CODE,
+            format!(
+                "mounted binary output exceeded {} bytes",
+                mounted_binary.max_output_size.unwrap_or_default()
+            ),
+        ),
+    };
+
+    Ok(MountedBinaryResult {
+        ret_code,
+        error,
+        stdout,
+        stderr,
+    })
+}
+
+/// Makes a command to run the binary, host environment variables are taken from `host_env`.
+fn make_command(
+    mounted_binary: &MountedBinaryConfig,
+    args: &[String],
+    host_env: &dyn Fn(&str) -> Option<OsString>,
+) -> std::io::Result<Command> {
+    let mut command = Command::new(&mounted_binary.path);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    if let Some(env_allowlist) = &mounted_binary.env_allowlist {
+        command.env_clear();
+        for name in env_allowlist {
+            if let Some(value) = host_env(name) {
+                command.env(name, value);
+            }
+        }
+    }
+    command.envs(&mounted_binary.envs);
+
+    if let Some(working_dir) = &mounted_binary.working_dir {
+        command.current_dir(resolve_working_dir(
+            working_dir,
+            mounted_binary.root_dir.as_deref(),
+        )?);
+    }
+
+    set_process_group(&mut command);
+    set_rlimits(&mut command, &mounted_binary.rlimits);
+
+    Ok(command)
+}
+
+/// Resolves the working dir against the root dir, following symlinks,
+/// and checks that it doesn't point outside of the root dir.
+fn resolve_working_dir(working_dir: &Path, root_dir: Option<&Path>) -> std::io::Result<PathBuf> {
+    let root_dir = match root_dir {
+        Some(root_dir) => root_dir.canonicalize()?,
+        None => std::env::current_dir()?.canonicalize()?,
+    };
+    let working_dir = root_dir.join(working_dir).canonicalize()?;
+
+    if !working_dir.starts_with(&root_dir) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!(
+                "working dir {} is outside of {}",
+                working_dir.display(),
+                root_dir.display()
+            ),
+        ));
+    }
+
+    Ok(working_dir)
+}
+
+/// Reads an output of the binary on a separate thread, so that the binary
+/// isn't blocked on a full pipe. Stops reading when the limit is exceeded.
+fn read_output(
+    output: Option<impl Read + Send + 'static>,
+    max_output_size: Option<u64>,
+    limit_exceeded: Arc<AtomicBool>,
+) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut output = match output {
+            Some(output) => output,
+            None => return Vec::new(),
+        };
+
+        let mut buffer = Vec::new();
+        let result = match max_output_size {
+            // read one byte more to find out whether the limit is exceeded
+            Some(max_size) => (&mut output)
+                .take(max_size.saturating_add(1))
+                .read_to_end(&mut buffer),
+            None => output.read_to_end(&mut buffer),
+        };
+        if let Err(e) = result {
+            log::warn!("failed to read mounted binary output: {}", e);
+        }
+
+        if let Some(max_size) = max_output_size {
+            if buffer.len() as u64 > max_size {
+                buffer.truncate(max_size as usize);
+                limit_exceeded.store(true, Ordering::Release);
+            }
+        }
+
+        buffer
+    })
+}
+
+/// Waits for a reader of the binary output, returns `None` if it doesn't finish before the deadline.
+fn join_output(reader: JoinHandle<Vec<u8>>, deadline: Option<Instant>) -> Option<Vec<u8>> {
+    while !reader.is_finished() {
+        if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            // the reader finishes by itself when the pipe is closed
+            return None;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    Some(reader.join().unwrap_or_default())
+}
+
+fn wait_with_limits(
+    child: &mut Child,
+    deadline: Option<Instant>,
+    output_limit_exceeded: &AtomicBool,
+) -> std::io::Result<Termination> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Termination::Exited(status));
+        }
+
+        let termination = if output_limit_exceeded.load(Ordering::Acquire) {
+            Termination::OutputLimitExceeded
+        } else if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            Termination::TimedOut
+        } else {
+            std::thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        kill(child);
+        child.wait()?;
+
+        return Ok(termination);
+    }
+}
+
+/// Runs the binary in its own process group, so that it could be killed with all its children.
+#[cfg(unix)]
+fn set_process_group(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+}
+
+#[cfg(not(unix))]
+fn set_process_group(_command: &mut Command) {}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    // the child isn't waited yet, so its pid is still reserved for its process group
+    if !kill_process_group(child) {
+        // the child could exit by itself in the meantime
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    // the child could exit by itself in the meantime, so the error is ignored
+    let _ = child.kill();
+}
+
+/// Kills all processes of the binary's process group, returns false if there are none.
+/// The group id isn't reused while the group has members, so it's safe to call
+/// after the child has been waited.
+#[cfg(unix)]
+fn kill_process_group(child: &Child) -> bool {
+    // SAFETY: kill doesn't access memory, the group was created for the child by `set_process_group`
+    unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) == 0 }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_child: &Child) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_rlimits(command: &mut Command, rlimits: &MountedBinaryRLimits) {
+    use std::os::unix::process::CommandExt;
+
+    let limits = [
+        (libc::RLIMIT_CPU, rlimits.cpu_time),
+        (libc::RLIMIT_AS, rlimits.address_space),
+        (libc::RLIMIT_FSIZE, rlimits.file_size),
+        (libc::RLIMIT_NOFILE, rlimits.open_files),
+    ]
+    .into_iter()
+    .filter_map(|(resource, limit)| limit.map(|limit| (resource, limit as libc::rlim_t)))
+    .collect::<Vec<_>>();
+
+    if limits.is_empty() {
+        return;
+    }
+
+    // SAFETY: the closure runs in the forked child before exec,
+    // it doesn't allocate and calls only async-signal-safe setrlimit.
+    unsafe {
+        command.pre_exec(move || {
+            for (resource, limit) in &limits {
+                // the hard CPU limit is a second greater, so SIGXCPU is sent before SIGKILL
+                let hard_limit = match *resource {
+                    libc::RLIMIT_CPU => limit.saturating_add(1),
+                    _ => *limit,
+                };
+                let rlimit = libc::rlimit {
+                    rlim_cur: *limit,
+                    rlim_max: hard_limit,
+                };
+                if libc::setrlimit(*resource, &rlimit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn set_rlimits(_command: &mut Command, rlimits: &MountedBinaryRLimits) {
+    let MountedBinaryRLimits {
+        cpu_time,
+        address_space,
+        file_size,
+        open_files,
+    } = rlimits;
+
+    if cpu_time.is_some() || address_space.is_some() || file_size.is_some() || open_files.is_some()
+    {
+        log::warn!("resource limits of mounted binaries are supported only on unix");
+    }
+}
+
+#[cfg(unix)]
+fn killed_by_rlimit(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    matches!(status.signal(), Some(libc::SIGXCPU) | Some(libc::SIGXFSZ))
+}
+
+#[cfg(not(unix))]
+fn killed_by_rlimit(_status: &ExitStatus) -> bool {
+    false
+}
+
 fn parse_args(mut raw_args: Vec<IValue>) -> Result<Vec<String>, MountedBinaryResult> {
     if raw_args.len() != 1 {
         return Err(MountedBinaryResult::from_error(100002, "internal error is encountered while passing arguments to a mounted binary closure, probably you use a not suitable version of rust-sdk"));
@@ -121,13 +642,286 @@ fn parse_args(mut raw_args: Vec<IValue>) -> Result<Vec<String>, MountedBinaryRes
 
 #[cfg(test)]
 mod tests {
+    use super::make_command;
     use super::mounted_binary_import_impl;
+    use super::run_on_dedicated_thread;
+    use super::MountedBinariesLimiter;
+    use crate::MountedBinaryArgPolicy;
+    use crate::MountedBinaryConfig;
+    use crate::MountedBinaryRLimits;
+
+    use marine_rs_sdk::MountedBinaryResult;
+    use wasmer_it::IValue;
+
+    use futures::future::Either;
+
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    fn run_shell(script: &str, config: MountedBinaryConfig) -> MountedBinaryResult {
+        let config = MountedBinaryConfig {
+            path: "/bin/sh".into(),
+            ..config
+        };
+        let args = IValue::Array(vec![
+            IValue::String("-c".to_string()),
+            IValue::String(script.to_string()),
+        ]);
+
+        mounted_binary_import_impl("module", &config, vec![args]).unwrap()
+    }
+
+    fn run_echo(args: &[&str], arg_policy: MountedBinaryArgPolicy) -> MountedBinaryResult {
+        let config = MountedBinaryConfig {
+            path: "/bin/echo".into(),
+            arg_policy,
+            ..<_>::default()
+        };
+        let args = args
+            .iter()
+            .map(|arg| IValue::String(arg.to_string()))
+            .collect();
+
+        mounted_binary_import_impl("module", &config, vec![IValue::Array(args)])
+            .unwrap_or_else(|result| result)
+    }
 
     #[test]
     fn call_non_existent_binary() {
-        let path = std::path::Path::new("____non_existent_path____");
-        let actual = mounted_binary_import_impl(path, vec![]).unwrap_err();
+        let config = MountedBinaryConfig {
+            path: "____non_existent_path____".into(),
+            ..<_>::default()
+        };
+        let actual = mounted_binary_import_impl("module", &config, vec![]).unwrap_err();
 
         assert_eq!(actual.ret_code, 100002);
     }
+
+    #[test]
+    fn binary_timeout() {
+        let config = MountedBinaryConfig {
+            timeout: Some(Duration::from_millis(100)),
+            ..<_>::default()
+        };
+        let actual = run_shell("sleep 10", config);
+
+        assert_eq!(actual.ret_code, 100005);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn background_children_are_killed() {
+        fn is_running(pid: &str) -> bool {
+            // a killed process could stay a zombie until it's reaped
+            std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .map_or(false, |stat| !stat.contains(") Z "))
+        }
+
+        let config = MountedBinaryConfig {
+            timeout: Some(Duration::from_secs(30)),
+            ..<_>::default()
+        };
+        let start = Instant::now();
+        let actual = run_shell("sleep 600 & echo $!", config);
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(actual.ret_code, 0);
+
+        let pid = String::from_utf8(actual.stdout).unwrap();
+        let pid = pid.trim();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while is_running(pid) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(
+            !is_running(pid),
+            "background child {} is still running",
+            pid
+        );
+    }
+
+    #[test]
+    fn binary_output_limit() {
+        let config = MountedBinaryConfig {
+            max_output_size: Some(4),
+            ..<_>::default()
+        };
+        let actual = run_shell("echo 123456789", config);
+
+        assert_eq!(actual.ret_code, 100006);
+        assert_eq!(actual.stdout, b"1234");
+    }
+
+    #[test]
+    fn binary_envs() {
+        let host_env = |name: &str| match name {
+            "MOUNTED_BINARY_TEST_ALLOWED" => Some(OsString::from("allowed")),
+            "MOUNTED_BINARY_TEST_DENIED" => Some(OsString::from("denied")),
+            _ => None,
+        };
+
+        let config = MountedBinaryConfig {
+            path: "/bin/sh".into(),
+            env_allowlist: Some(vec!["MOUNTED_BINARY_TEST_ALLOWED".to_string()]),
+            envs: HashMap::from([("OVERRIDDEN".to_string(), "value".to_string())]),
+            ..<_>::default()
+        };
+        let args = [
+            "-c".to_string(),
+            "echo $MOUNTED_BINARY_TEST_ALLOWED $MOUNTED_BINARY_TEST_DENIED $OVERRIDDEN".to_string(),
+        ];
+        let output = make_command(&config, &args, &host_env)
+            .unwrap()
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "allowed value\n");
+    }
+
+    #[test]
+    fn binary_working_dir() {
+        let root_dir = std::env::temp_dir().canonicalize().unwrap();
+        let working_dir = root_dir.join("mounted_binary_working_dir_test");
+        std::fs::create_dir_all(&working_dir).unwrap();
+
+        let config = MountedBinaryConfig {
+            working_dir: Some("mounted_binary_working_dir_test".into()),
+            root_dir: Some(root_dir.clone()),
+            ..<_>::default()
+        };
+        let actual = run_shell("pwd", config.clone());
+
+        assert_eq!(actual.ret_code, 0);
+        assert_eq!(
+            String::from_utf8_lossy(&actual.stdout).trim_end(),
+            working_dir.to_string_lossy()
+        );
+
+        // configs from TOML can't contain such paths, but they could be set directly
+        for escaping_dir in ["..", "/"] {
+            let config = MountedBinaryConfig {
+                working_dir: Some(escaping_dir.into()),
+                ..config.clone()
+            };
+            let actual = run_shell("pwd", config);
+
+            assert_eq!(actual.ret_code, 100001, "{} must be rejected", escaping_dir);
+            assert!(actual.error.contains("outside of"), "{}", actual.error);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn binary_rlimits() {
+        let config = MountedBinaryConfig {
+            rlimits: MountedBinaryRLimits {
+                file_size: Some(4),
+                ..<_>::default()
+            },
+            ..<_>::default()
+        };
+        let file = std::env::temp_dir().join("mounted_binary_rlimits_test");
+        let actual = run_shell(&format!("echo 123456789 > {}", file.display()), config);
+        let _ = std::fs::remove_file(file);
+
+        assert_eq!(actual.ret_code, 100007);
+    }
+
+    #[test]
+    fn forbidden_flags() {
+        let policy = MountedBinaryArgPolicy {
+            forbidden_flags: vec!["-o".to_string(), "--output".to_string()],
+            ..<_>::default()
+        };
+
+        for args in [
+            &["-o", "file"][..],
+            &["--output=file"],
+            &["-so"],
+            &["-ofile"],
+        ] {
+            let actual = run_echo(args, policy.clone());
+            assert_eq!(actual.ret_code, 100008, "{:?} must be rejected", args);
+        }
+
+        let actual = run_echo(&["-s", "--", "-o"], policy);
+        assert_eq!(actual.ret_code, 0);
+    }
+
+    #[test]
+    fn allowed_flags() {
+        let policy = MountedBinaryArgPolicy {
+            allowed_flags: Some(vec!["-n".to_string(), "-e".to_string()]),
+            ..<_>::default()
+        };
+
+        assert_eq!(run_echo(&["-ne", "text"], policy.clone()).ret_code, 0);
+        assert_eq!(run_echo(&["-E", "text"], policy).ret_code, 100008);
+    }
+
+    #[test]
+    fn positional_patterns() {
+        let policy = MountedBinaryArgPolicy {
+            positional_patterns: vec![regex::Regex::new("^(?:https://.*)$").unwrap()],
+            ..<_>::default()
+        };
+
+        assert_eq!(
+            run_echo(&["https://fluence.network"], policy.clone()).ret_code,
+            0
+        );
+        assert_eq!(
+            run_echo(&["--url=https://fluence.network"], policy.clone()).ret_code,
+            0
+        );
+        assert_eq!(
+            run_echo(&["file:///etc/passwd"], policy.clone()).ret_code,
+            100008
+        );
+        assert_eq!(
+            run_echo(&["--url=file:///etc/passwd"], policy).ret_code,
+            100008
+        );
+    }
+
+    #[test]
+    fn max_args_count() {
+        let policy = MountedBinaryArgPolicy {
+            max_args_count: Some(2),
+            ..<_>::default()
+        };
+
+        assert_eq!(run_echo(&["1", "2"], policy.clone()).ret_code, 0);
+        assert_eq!(run_echo(&["1", "2", "3"], policy).ret_code, 100008);
+    }
+
+    #[tokio::test]
+    async fn binary_doesnt_block_executor() {
+        let binary = run_on_dedicated_thread(None, || run_shell("sleep 1", <_>::default()));
+        let other_task = futures::future::ready(());
+
+        let result = futures::future::select(Box::pin(binary), other_task).await;
+        assert!(matches!(result, Either::Right(_)));
+    }
+
+    #[tokio::test]
+    async fn concurrent_binaries_limit() {
+        let limiter = MountedBinariesLimiter::new(NonZeroUsize::new(1).unwrap());
+        let run_sleep = || {
+            run_on_dedicated_thread(Some(limiter.clone()), || {
+                run_shell("sleep 0.2", <_>::default())
+            })
+        };
+
+        let start = Instant::now();
+        let (first, second) = futures::future::join(run_sleep(), run_sleep()).await;
+
+        assert_eq!(first.ret_code, 0);
+        assert_eq!(second.ret_code, 0);
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
 }
@@ -0,0 +1,72 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine_wasm_backend_traits::WasmBackend;
+use marine_core::generic::HostImportDescriptor;
+use marine_rs_sdk::CallParameters;
+
+use parking_lot::Mutex;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Providers registered on Marine by their names,
+/// modules enable them with `host_imports = ["name", ...]` in their configs.
+pub type HostImportProviders<WB> = HashMap<String, Arc<dyn HostImportProvider<WB>>>;
+
+/// A source of host imports, registered once on Marine and enabled per module.
+pub trait HostImportProvider<WB: WasmBackend>: Send + Sync {
+    /// Creates host imports for a module, keyed by their import names.
+    /// Called once for every supported host API version of every module the provider is enabled for.
+    fn host_imports(
+        &self,
+        context: &HostImportContext,
+    ) -> HashMap<String, HostImportDescriptor<WB>>;
+}
+
+/// Context available to host imports created by a [`HostImportProvider`].
+#[derive(Clone)]
+pub struct HostImportContext {
+    module_name: String,
+    call_parameters: Arc<Mutex<CallParameters>>,
+}
+
+impl HostImportContext {
+    pub(crate) fn new(module_name: String, call_parameters: Arc<Mutex<CallParameters>>) -> Self {
+        Self {
+            module_name,
+            call_parameters,
+        }
+    }
+
+    /// Name of the module the imports are created for.
+    pub fn module_name(&self) -> &str {
+        &self.module_name
+    }
+
+    /// Parameters of the current call, they are updated before each call to the service.
+    pub fn call_parameters(&self) -> CallParameters {
+        self.call_parameters.lock().clone()
+    }
+
+    /// Id of the service which is being called.
+    pub fn service_id(&self) -> String {
+        self.call_parameters.lock().service_id.clone()
+    }
+}
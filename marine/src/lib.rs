@@ -41,16 +41,39 @@ pub use marine_interface::MarineInterface;
 pub use config::ConfigContext;
 pub use config::WithContext;
 pub use config::MarineWASIConfig;
+pub use config::MountedBinaryArgPolicy;
+pub use config::MountedBinaryConfig;
+pub use config::MountedBinaryRLimits;
+pub use config::LoggerLimits;
 
 pub use config::TomlMarineConfig;
 pub use config::TomlMarineModuleConfig;
 pub use config::TomlMarineNamedModuleConfig;
 pub use config::TomlWASIConfig;
+pub use config::TomlMountedBinary;
+pub use config::TomlMountedBinaryArgPolicy;
+pub use config::TomlMountedBinaryConfig;
+pub use config::TomlMountedBinaryRLimits;
+pub use config::TomlLoggerLimits;
 pub use config::TomlValue;
 pub use config::TomlValueTable;
+pub use config::check_config;
+pub use config::resolve_toml_config;
+pub use config::module_path;
 
 pub use errors::MarineError;
 
+pub use module_loading::ModuleHash;
+pub use module_loading::ModuleHashParseError;
+
+pub use host_imports::HostImportContext;
+pub use host_imports::HostImportProvider;
+pub use host_imports::MountedBinariesLimiter;
+pub use host_imports::DEFAULT_MAX_RUNNING_BINARIES;
+pub use host_imports::logger::ModuleLogRecord;
+pub use host_imports::logger::LogSuppressionStats;
+pub use host_imports::logger::TRUNCATION_MARKER;
+
 // Re-exports from Marine
 pub use marine_core::IValue;
 pub use marine_core::IRecordType;
@@ -63,12 +86,21 @@ pub use marine_core::MemoryStats;
 pub use marine_core::ModuleMemoryStat;
 pub use marine_core::MRecordTypes;
 pub use marine_core::HostImportError;
+pub use marine_core::AsyncHostExportedFunc;
 pub use marine_core::to_interface_value;
 pub use marine_core::from_interface_values;
 pub use marine_core::ne_vec;
+pub use marine_core::ModuleSigner;
+pub use marine_core::EffectsPolicy;
+pub use marine_core::EffectKind;
+pub use marine_core::WasmEffect;
 
 pub use marine_module_interface::interface::itype_text_view;
 
+pub use it_json_serde::BytesRepresentation;
+pub use it_json_serde::Int64Representation;
+pub use it_json_serde::JsonOptions;
+
 pub use marine_rs_sdk::CallParameters;
 pub use marine_rs_sdk::ParticleParameters;
 pub use marine_rs_sdk::SecurityTetraplet;
@@ -78,6 +110,7 @@ pub mod generic {
     pub use crate::config::MarineModuleConfig;
     pub use crate::config::ModuleDescriptor;
     pub use crate::config::MarineConfig;
+    pub use crate::host_imports::HostImportProviders;
 
     pub use marine_core::generic::*;
 }
@@ -90,8 +123,11 @@ pub mod wasmtime {
     pub type MarineModuleConfig = crate::config::MarineModuleConfig<WasmBackend>;
     pub type ModuleDescriptor = crate::config::ModuleDescriptor<WasmBackend>;
     pub type MarineConfig = crate::config::MarineConfig<WasmBackend>;
+    pub type HostImportProviders = crate::host_imports::HostImportProviders<WasmBackend>;
 
     pub use marine_core::wasmtime::HostExportedFunc;
+    pub use marine_core::wasmtime::HostImportFunc;
+    pub use marine_core::wasmtime::MCompiledModule;
     pub use marine_core::wasmtime::HostImportDescriptor;
 }
 
@@ -18,6 +18,7 @@
  */
 
 use crate::config::MarineConfig;
+use crate::config::MarineModuleConfig;
 use crate::marine_interface::MarineInterface;
 use crate::MarineError;
 use crate::MarineResult;
@@ -25,11 +26,18 @@ use crate::IValue;
 use crate::IType;
 use crate::MemoryStats;
 use crate::module_loading::load_modules_from_fs;
-use crate::host_imports::logger::LoggerFilter;
-use crate::host_imports::logger::WASM_LOG_ENV_NAME;
+use crate::module_loading::check_module_hash;
+use crate::host_imports::logger::LogCapture;
+use crate::host_imports::logger::LogSuppressionStats;
+use crate::host_imports::logger::SharedLoggerSettings;
+use crate::host_imports::logger::ModuleLogRecord;
 use crate::host_imports::call_parameters_v3_to_v0;
 use crate::host_imports::call_parameters_v3_to_v1;
 use crate::host_imports::call_parameters_v3_to_v2;
+#[cfg(feature = "raw-module-api")]
+use crate::host_imports::HostImportProviders;
+#[cfg(feature = "raw-module-api")]
+use crate::host_imports::MountedBinariesLimiter;
 use crate::json_to_marine_err;
 
 use marine_wasm_backend_traits::WasmBackend;
@@ -38,10 +46,12 @@ use marine_wasm_backend_traits::WasiState;
 
 use marine_core::MError;
 use marine_core::generic::MarineCore;
+use marine_core::generic::MCompiledModule;
 use marine_core::IFunctionArg;
 use marine_core::MarineCoreConfig;
 use marine_core::MRecordTypes;
 use marine_utils::SharedString;
+use it_json_serde::JsonOptions;
 use marine_rs_sdk::CallParameters;
 
 use parking_lot::Mutex;
@@ -74,8 +84,22 @@ pub struct Marine<WB: WasmBackend> {
     /// Parameters of call accessible by Wasm modules.
     call_parameters_v3: Arc<Mutex<CallParameters>>,
 
+    /// Log levels and logging masks of modules, could be changed at runtime.
+    logger_settings: SharedLoggerSettings,
+
+    /// Collects log records emitted by modules during calls capturing logs.
+    log_capture: LogCapture,
+
     /// Cached module interfaces by names.
     module_interfaces_cache: HashMap<String, ModuleInterface>,
+
+    /// Host import providers which modules could enable in their configs.
+    #[cfg(feature = "raw-module-api")]
+    host_import_providers: HostImportProviders<WB>,
+
+    /// Limits mounted binaries running at the same time.
+    #[cfg(feature = "raw-module-api")]
+    mounted_binaries_limiter: Option<MountedBinariesLimiter>,
 }
 
 impl<WB: WasmBackend> Marine<WB> {
@@ -86,21 +110,82 @@ impl<WB: WasmBackend> Marine<WB> {
         MarineError: From<C::Error>,
     {
         let config = config.try_into()?;
+        let modules = Self::compile_modules(&backend, &config)?;
+
+        Self::with_compiled_modules::<MarineConfig<WB>>(backend, &modules, config).await
+    }
+
+    /// Loads modules specified in the config from filesystem and compiles them,
+    /// so that several Marine instances could be created from them without compiling them again.
+    pub fn compile_modules(
+        backend: &WB,
+        config: &MarineConfig<WB>,
+    ) -> MarineResult<HashMap<String, MCompiledModule<WB>>> {
         let modules = config
             .modules_config
             .iter()
             .map(|m| -> MarineResult<(String, PathBuf)> {
-                Ok((m.import_name.clone(), m.get_path(&config.modules_dir)?))
+                Ok((
+                    m.import_name.clone(),
+                    m.get_path(&config.modules_dir, &config.module_store)?,
+                ))
             })
             .collect::<MarineResult<HashMap<String, PathBuf>>>()?;
 
-        Self::with_module_names::<MarineConfig<WB>>(backend, &modules, config).await
+        load_modules_from_fs(&modules)?
+            .into_iter()
+            .map(|(name, wasm_bytes)| {
+                check_module_hash(&name, config.module_hash(&name), &wasm_bytes)?;
+                let module = MCompiledModule::new(
+                    backend,
+                    &name,
+                    &wasm_bytes,
+                    config.trusted_signers.as_deref(),
+                )?;
+                Ok((name, module))
+            })
+            .collect()
     }
 
     /// Creates Marine with given modules.
     pub async fn with_modules<C>(
         backend: WB,
-        mut modules: HashMap<String, Vec<u8>>,
+        modules: HashMap<String, Vec<u8>>,
+        config: C,
+    ) -> MarineResult<Self>
+    where
+        C: TryInto<MarineConfig<WB>>,
+        MarineError: From<C::Error>,
+    {
+        let config = config.try_into()?;
+        // modules not mentioned in the config aren't loaded, so there is no need to compile them
+        let modules = modules
+            .into_iter()
+            .filter(|(name, _)| {
+                config
+                    .modules_config
+                    .iter()
+                    .any(|module| &module.import_name == name)
+            })
+            .map(|(name, wasm_bytes)| {
+                check_module_hash(&name, config.module_hash(&name), &wasm_bytes)?;
+                let module = MCompiledModule::new(
+                    &backend,
+                    &name,
+                    &wasm_bytes,
+                    config.trusted_signers.as_deref(),
+                )?;
+                Ok((name, module))
+            })
+            .collect::<MarineResult<HashMap<_, _>>>()?;
+
+        Self::with_compiled_modules::<MarineConfig<WB>>(backend, &modules, config).await
+    }
+
+    /// Creates Marine with given modules compiled with the same backend beforehand.
+    pub async fn with_compiled_modules<C>(
+        backend: WB,
+        modules: &HashMap<String, MCompiledModule<WB>>,
         config: C,
     ) -> MarineResult<Self>
     where
@@ -108,27 +193,31 @@ impl<WB: WasmBackend> Marine<WB> {
         MarineError: From<C::Error>,
     {
         let config = config.try_into()?;
-        let core_config = MarineCoreConfig::new(backend, config.total_memory_limit);
+        let core_config = MarineCoreConfig::new(backend, config.total_memory_limit)
+            .with_trusted_signers(config.trusted_signers)
+            .with_effects_policy(config.effects_policy);
         let mut marine = MarineCore::new(core_config)?;
         let call_parameters_v0 = Arc::<Mutex<marine_call_parameters_v0::CallParameters>>::default();
         let call_parameters_v1 = Arc::<Mutex<marine_call_parameters_v1::CallParameters>>::default();
         let call_parameters_v2 = Arc::<Mutex<marine_call_parameters_v2::CallParameters>>::default();
         let call_parameters_v3 = Arc::<Mutex<CallParameters>>::default();
+        let log_capture = LogCapture::default();
 
         let modules_dir = config.modules_dir;
+        let host_import_providers = config.host_import_providers;
+        let mounted_binaries_limiter = config.mounted_binaries_limiter;
 
-        // LoggerFilter can be initialized with an empty string
-        let wasm_log_env = std::env::var(WASM_LOG_ENV_NAME).unwrap_or_default();
-        let logger_filter = LoggerFilter::from_env_string(&wasm_log_env);
+        let logger_settings = SharedLoggerSettings::new(config.log_directives.as_deref());
 
-        for module in config.modules_config {
-            let module_bytes = modules.remove(&module.import_name).ok_or_else(|| {
+        for mut module in config.modules_config {
+            let compiled_module = modules.get(&module.import_name).ok_or_else(|| {
                 MarineError::InstantiationError {
                     module_import_name: module.import_name.clone(),
                     modules_dir: modules_dir.clone(),
                     provided_modules: modules.keys().cloned().collect::<Vec<_>>(),
                 }
             })?;
+            apply_log_targets(&module.import_name, &mut module.config, compiled_module)?;
 
             let marine_module_config = crate::config::make_marine_config(
                 module.import_name.clone(),
@@ -137,11 +226,14 @@ impl<WB: WasmBackend> Marine<WB> {
                 call_parameters_v1.clone(),
                 call_parameters_v2.clone(),
                 call_parameters_v3.clone(),
-                &logger_filter,
+                &logger_settings,
+                log_capture.clone(),
+                &host_import_providers,
+                mounted_binaries_limiter.as_ref(),
             )?;
 
             marine
-                .load_module(module.import_name, &module_bytes, marine_module_config)
+                .load_compiled_module(module.import_name, compiled_module, marine_module_config)
                 .await
                 .map_err(|e| check_for_oom_and_convert_error(&marine, e))?;
         }
@@ -152,7 +244,13 @@ impl<WB: WasmBackend> Marine<WB> {
             call_parameters_v1,
             call_parameters_v2,
             call_parameters_v3,
+            logger_settings,
+            log_capture,
             module_interfaces_cache: HashMap::new(),
+            #[cfg(feature = "raw-module-api")]
+            host_import_providers,
+            #[cfg(feature = "raw-module-api")]
+            mounted_binaries_limiter,
         })
     }
 
@@ -181,6 +279,7 @@ impl<WB: WasmBackend> Marine<WB> {
         call_parameters: marine_rs_sdk::CallParameters,
     ) -> MarineResult<Vec<IValue>> {
         self.update_call_parameters(call_parameters);
+        self.logger_settings.start_call();
 
         let result = self
             .core
@@ -201,8 +300,28 @@ impl<WB: WasmBackend> Marine<WB> {
         json_args: JValue,
         call_parameters: marine_rs_sdk::CallParameters,
     ) -> MarineResult<JValue> {
-        use it_json_serde::json_to_ivalues;
-        use it_json_serde::ivalues_to_json;
+        self.call_with_json_options_async(
+            module_name,
+            func_name,
+            json_args,
+            &JsonOptions::default(),
+            call_parameters,
+        )
+        .await
+    }
+
+    /// Call a specified function like [`Self::call_with_json_async`], with byte arrays and 64-bit
+    /// integers of arguments and results represented according to the supplied options.
+    pub async fn call_with_json_options_async(
+        &mut self,
+        module_name: impl AsRef<str>,
+        func_name: impl AsRef<str>,
+        json_args: JValue,
+        json_options: &JsonOptions,
+        call_parameters: marine_rs_sdk::CallParameters,
+    ) -> MarineResult<JValue> {
+        use it_json_serde::json_to_ivalues_with_options;
+        use it_json_serde::ivalues_to_json_with_options;
 
         let module_name = module_name.as_ref();
         let func_name = func_name.as_ref();
@@ -210,16 +329,18 @@ impl<WB: WasmBackend> Marine<WB> {
         let (func_signature, output_types, record_types) =
             self.lookup_module_interface(module_name, func_name)?;
         let iargs = json_to_marine_err!(
-            json_to_ivalues(
+            json_to_ivalues_with_options(
                 json_args,
                 func_signature.iter().map(|arg| (&arg.name, &arg.ty)),
                 &record_types,
+                json_options,
             ),
             module_name.to_string(),
             func_name.to_string()
         )?;
 
         self.update_call_parameters(call_parameters);
+        self.logger_settings.start_call();
 
         let result = self
             .core
@@ -230,12 +351,99 @@ impl<WB: WasmBackend> Marine<WB> {
         self.core.clear_allocation_stats();
 
         json_to_marine_err!(
-            ivalues_to_json(result, &output_types, &record_types),
+            ivalues_to_json_with_options(result, &output_types, &record_types, json_options),
             module_name.to_string(),
             func_name.to_string()
         )
     }
 
+    /// Call a specified function like [`Self::call_with_json_async`] and return
+    /// the log records emitted by all modules during this call next to its result.
+    pub async fn call_with_json_capturing_logs_async(
+        &mut self,
+        module_name: impl AsRef<str>,
+        func_name: impl AsRef<str>,
+        json_args: JValue,
+        call_parameters: marine_rs_sdk::CallParameters,
+    ) -> (MarineResult<JValue>, Vec<ModuleLogRecord>) {
+        let capture = self.log_capture.start();
+        let result = self
+            .call_with_json_async(module_name, func_name, json_args, call_parameters)
+            .await;
+
+        (result, capture.finish())
+    }
+
+    /// Call a specified function with arguments encoded in MessagePack and return its results
+    /// in MessagePack. Arguments and results are represented the same way as json ones,
+    /// except byte arrays which are encoded as binary, and 64-bit integers keep their precision.
+    pub async fn call_with_msgpack_async(
+        &mut self,
+        module_name: impl AsRef<str>,
+        func_name: impl AsRef<str>,
+        args: &[u8],
+        call_parameters: marine_rs_sdk::CallParameters,
+    ) -> MarineResult<Vec<u8>> {
+        self.call_with_encoded_async(
+            module_name.as_ref(),
+            func_name.as_ref(),
+            args,
+            BinaryEncoding::MessagePack,
+            call_parameters,
+        )
+        .await
+    }
+
+    /// Call a specified function like [`Self::call_with_msgpack_async`], but with CBOR encoded
+    /// arguments and results.
+    pub async fn call_with_cbor_async(
+        &mut self,
+        module_name: impl AsRef<str>,
+        func_name: impl AsRef<str>,
+        args: &[u8],
+        call_parameters: marine_rs_sdk::CallParameters,
+    ) -> MarineResult<Vec<u8>> {
+        self.call_with_encoded_async(
+            module_name.as_ref(),
+            func_name.as_ref(),
+            args,
+            BinaryEncoding::Cbor,
+            call_parameters,
+        )
+        .await
+    }
+
+    /// Change log directives of all modules, they have the same format as the WASM_LOG env variable
+    /// (e.g. "info,module_name=debug"), which is used instead if directives are None.
+    ///
+    /// Modules log with the most verbose level and records are filtered by these directives,
+    /// so levels could be raised or lowered at runtime. The exception is a module with WASM_LOG
+    /// set explicitly in its wasi config: it filters records by that level itself.
+    pub fn set_log_directives(&mut self, log_directives: Option<&str>) {
+        self.logger_settings.set_log_directives(log_directives);
+    }
+
+    /// Change the mask of logging targets enabled for a module.
+    pub fn set_logging_mask(
+        &mut self,
+        module_name: impl AsRef<str>,
+        logging_mask: i32,
+    ) -> MarineResult<()> {
+        let module_name = module_name.as_ref();
+        if self.core.module_interface(module_name).is_none() {
+            return Err(MarineError::NoSuchModule(module_name.to_string()));
+        }
+
+        self.logger_settings
+            .set_logging_mask(module_name, logging_mask);
+        Ok(())
+    }
+
+    /// Return numbers of log messages dropped or truncated because of logger limits by module names.
+    pub fn log_suppression_stats(&self) -> HashMap<String, LogSuppressionStats> {
+        self.logger_settings.suppression_stats()
+    }
+
     /// Return all export functions (name and signatures) of loaded modules.
     pub fn get_interface(&self) -> MarineInterface<'_> {
         let modules = self.core.interface().collect();
@@ -248,6 +456,42 @@ impl<WB: WasmBackend> Marine<WB> {
         self.core.module_memory_stats()
     }
 
+    /// Decode arguments of a function from the binary encoding, call it
+    /// and encode its results back into the same encoding.
+    async fn call_with_encoded_async(
+        &mut self,
+        module_name: &str,
+        func_name: &str,
+        args: &[u8],
+        encoding: BinaryEncoding,
+        call_parameters: marine_rs_sdk::CallParameters,
+    ) -> MarineResult<Vec<u8>> {
+        let (func_signature, output_types, record_types) =
+            self.lookup_module_interface(module_name, func_name)?;
+        let arg_types = func_signature.iter().map(|arg| (&arg.name, &arg.ty));
+        let iargs = encoding
+            .decode(args, arg_types, &record_types)
+            .map_err(|error| MarineError::ArgumentsDeserializationError {
+                module_name: module_name.to_string(),
+                function_name: func_name.to_string(),
+                encoding: encoding.name(),
+                error,
+            })?;
+
+        let result = self
+            .call_with_ivalues_async(module_name, func_name, &iargs, call_parameters)
+            .await?;
+
+        encoding
+            .encode(result, &output_types, &record_types)
+            .map_err(|error| MarineError::OutputSerializationError {
+                module_name: module_name.to_string(),
+                function_name: func_name.to_string(),
+                encoding: encoding.name(),
+                error,
+            })
+    }
+
     /// At first, tries to find function signature and record types in module_interface_cache,
     /// if there is no them, tries to look
     fn lookup_module_interface(
@@ -342,12 +586,16 @@ impl<WB: WasmBackend> Marine<WB> {
         C: TryInto<crate::generic::MarineModuleConfig<WB>>,
         MarineError: From<C::Error>,
     {
-        let config = config.map(|c| c.try_into()).transpose()?;
+        let mut config = config.map(|c| c.try_into()).transpose()?;
         let name = name.into();
 
-        // LoggerFilter can be initialized with an empty string
-        let wasm_log_env = std::env::var(WASM_LOG_ENV_NAME).unwrap_or_default();
-        let logger_filter = LoggerFilter::from_env_string(&wasm_log_env);
+        let compiled_module = self
+            .core
+            .compile_module(&name, wasm_bytes)
+            .map_err(|e| check_for_oom_and_convert_error(&self.core, e))?;
+        if let Some(config) = &mut config {
+            apply_log_targets(&name, config, &compiled_module)?;
+        }
 
         let marine_module_config = crate::config::make_marine_config(
             name.clone(),
@@ -356,10 +604,13 @@ impl<WB: WasmBackend> Marine<WB> {
             self.call_parameters_v1.clone(),
             self.call_parameters_v2.clone(),
             self.call_parameters_v3.clone(),
-            &logger_filter,
+            &self.logger_settings,
+            self.log_capture.clone(),
+            &self.host_import_providers,
+            self.mounted_binaries_limiter.as_ref(),
         )?;
         self.core
-            .load_module(name, wasm_bytes, marine_module_config)
+            .load_compiled_module(name, &compiled_module, marine_module_config)
             .await
             .map_err(|e| check_for_oom_and_convert_error(&self.core, e))
     }
@@ -380,6 +631,78 @@ impl<WB: WasmBackend> Marine<WB> {
     }
 }
 
+/// Turns names of logging targets enabled in a module config into its logging mask.
+fn apply_log_targets<WB: WasmBackend>(
+    module_name: &str,
+    config: &mut MarineModuleConfig<WB>,
+    compiled_module: &MCompiledModule<WB>,
+) -> MarineResult<()> {
+    let enabled_targets = match &config.log_targets {
+        Some(enabled_targets) => enabled_targets,
+        None => return Ok(()),
+    };
+
+    let declared_targets = compiled_module.log_targets()?;
+    let mut logging_mask = 0;
+    for target in enabled_targets {
+        let target_mask = declared_targets.get(target).ok_or_else(|| {
+            MarineError::InvalidConfig(format!(
+                r#"module "{}" enables log target "{}", but doesn't declare it"#,
+                module_name, target
+            ))
+        })?;
+        logging_mask |= target_mask;
+    }
+
+    config.logging_mask = logging_mask;
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum BinaryEncoding {
+    MessagePack,
+    Cbor,
+}
+
+impl BinaryEncoding {
+    fn name(self) -> &'static str {
+        match self {
+            BinaryEncoding::MessagePack => "MessagePack",
+            BinaryEncoding::Cbor => "CBOR",
+        }
+    }
+
+    fn decode<'a>(
+        self,
+        args: &[u8],
+        arg_types: impl Iterator<Item = (&'a String, &'a IType)> + ExactSizeIterator,
+        record_types: &MRecordTypes,
+    ) -> it_json_serde::JsonResult<Vec<IValue>> {
+        match self {
+            BinaryEncoding::MessagePack => {
+                it_json_serde::msgpack_to_ivalues(args, arg_types, record_types)
+            }
+            BinaryEncoding::Cbor => it_json_serde::cbor_to_ivalues(args, arg_types, record_types),
+        }
+    }
+
+    fn encode(
+        self,
+        ivalues: Vec<IValue>,
+        output_types: &[IType],
+        record_types: &MRecordTypes,
+    ) -> it_json_serde::JsonResult<Vec<u8>> {
+        match self {
+            BinaryEncoding::MessagePack => {
+                it_json_serde::ivalues_to_msgpack(ivalues, output_types, record_types)
+            }
+            BinaryEncoding::Cbor => {
+                it_json_serde::ivalues_to_cbor(ivalues, output_types, record_types)
+            }
+        }
+    }
+}
+
 fn check_for_oom_and_convert_error<WB: WasmBackend>(
     core: &MarineCore<WB>,
     error: MError,
@@ -105,16 +105,12 @@ fn print_functions_sign<'r>(
             let outputs = &function_signature.outputs;
             if outputs.is_empty() {
                 writeln!(f, "{})", args)?;
-            } else if outputs.len() == 1 {
-                writeln!(
-                    f,
-                    "{}) -> {}",
-                    args,
-                    itype_text_view(&outputs[0], module_interface.record_types)
-                )?;
             } else {
-                // At now, multi values aren't supported - only one output type is possible
-                unimplemented!()
+                let outputs = outputs
+                    .iter()
+                    .map(|output| itype_text_view(output, module_interface.record_types))
+                    .join(", ");
+                writeln!(f, "{}) -> {}", args, outputs)?;
             }
         }
     }
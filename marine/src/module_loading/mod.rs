@@ -17,6 +17,11 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+mod module_hash;
+
+pub use module_hash::ModuleHash;
+pub use module_hash::ModuleHashParseError;
+
 use crate::MarineError;
 use crate::MarineResult;
 
@@ -45,3 +50,26 @@ pub(crate) fn load_modules_from_fs(
 
     Ok(loaded)
 }
+
+/// Checks that module bytes have the hash specified in config, if any.
+pub(crate) fn check_module_hash(
+    import_name: &str,
+    expected: Option<&ModuleHash>,
+    wasm_bytes: &[u8],
+) -> MarineResult<()> {
+    let expected = match expected {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    let actual = expected.compute_same(wasm_bytes);
+    if &actual != expected {
+        return Err(MarineError::ModuleHashMismatch {
+            module_name: import_name.to_string(),
+            expected: *expected,
+            actual,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use sha2::Digest;
+use thiserror::Error;
+
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const SHA256_PREFIX: &str = "sha256";
+const BLAKE3_PREFIX: &str = "blake3";
+const DIGEST_SIZE: usize = 32;
+
+/// Content hash of a module in the `<algorithm>:<hex digest>` form,
+/// e.g. `sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModuleHash {
+    Sha256([u8; DIGEST_SIZE]),
+    Blake3([u8; DIGEST_SIZE]),
+}
+
+#[derive(Debug, Error)]
+pub enum ModuleHashParseError {
+    /// The hash doesn't have the `<algorithm>:<hex digest>` form.
+    #[error(r#"hash "{0}" should have the "<algorithm>:<hex digest>" form"#)]
+    InvalidFormat(String),
+
+    /// The algorithm isn't supported.
+    #[error(r#"unsupported hash algorithm "{0}", expected sha256 or blake3"#)]
+    UnsupportedAlgorithm(String),
+
+    /// The digest isn't a valid hex string of the expected size.
+    #[error("invalid {algorithm} digest: {error}")]
+    InvalidDigest {
+        algorithm: &'static str,
+        error: hex::FromHexError,
+    },
+}
+
+impl ModuleHash {
+    /// Computes a hash of the same algorithm as this one over the provided bytes.
+    pub fn compute_same(&self, wasm_bytes: &[u8]) -> Self {
+        match self {
+            Self::Sha256(_) => Self::sha256(wasm_bytes),
+            Self::Blake3(_) => Self::blake3(wasm_bytes),
+        }
+    }
+
+    pub fn sha256(wasm_bytes: &[u8]) -> Self {
+        Self::Sha256(sha2::Sha256::digest(wasm_bytes).into())
+    }
+
+    pub fn blake3(wasm_bytes: &[u8]) -> Self {
+        Self::Blake3(blake3::hash(wasm_bytes).into())
+    }
+
+    /// Returns true if provided bytes have this hash.
+    pub fn matches(&self, wasm_bytes: &[u8]) -> bool {
+        &self.compute_same(wasm_bytes) == self
+    }
+
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Self::Sha256(_) => SHA256_PREFIX,
+            Self::Blake3(_) => BLAKE3_PREFIX,
+        }
+    }
+
+    pub fn digest(&self) -> &[u8; DIGEST_SIZE] {
+        match self {
+            Self::Sha256(digest) | Self::Blake3(digest) => digest,
+        }
+    }
+
+    /// Path of a module with this hash inside a content-addressed module store,
+    /// which is `<store>/<algorithm>/<hex digest>.wasm`.
+    pub fn store_path(&self, module_store: &Path) -> PathBuf {
+        module_store
+            .join(self.algorithm())
+            .join(format!("{}.wasm", hex::encode(self.digest())))
+    }
+}
+
+impl FromStr for ModuleHash {
+    type Err = ModuleHashParseError;
+
+    fn from_str(hash: &str) -> Result<Self, Self::Err> {
+        let (algorithm, digest) = hash
+            .split_once(':')
+            .ok_or_else(|| ModuleHashParseError::InvalidFormat(hash.to_string()))?;
+
+        let (algorithm, constructor): (&'static str, fn([u8; DIGEST_SIZE]) -> Self) =
+            match algorithm {
+                SHA256_PREFIX => (SHA256_PREFIX, Self::Sha256),
+                BLAKE3_PREFIX => (BLAKE3_PREFIX, Self::Blake3),
+                _ => {
+                    return Err(ModuleHashParseError::UnsupportedAlgorithm(
+                        algorithm.to_string(),
+                    ))
+                }
+            };
+
+        let mut bytes = [0u8; DIGEST_SIZE];
+        hex::decode_to_slice(digest, &mut bytes)
+            .map_err(|error| ModuleHashParseError::InvalidDigest { algorithm, error })?;
+
+        Ok(constructor(bytes))
+    }
+}
+
+impl fmt::Display for ModuleHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm(), hex::encode(self.digest()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModuleHash;
+    use super::ModuleHashParseError;
+
+    use std::path::Path;
+
+    const EMPTY_SHA256: &str =
+        "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const EMPTY_BLAKE3: &str =
+        "blake3:af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+
+    #[test]
+    fn parse_and_display() {
+        for hash in [EMPTY_SHA256, EMPTY_BLAKE3] {
+            let parsed = hash.parse::<ModuleHash>().unwrap();
+            assert_eq!(parsed.to_string(), hash);
+            assert!(parsed.matches(&[]));
+            assert!(!parsed.matches(b"module"));
+        }
+
+        assert_eq!(ModuleHash::sha256(&[]).to_string(), EMPTY_SHA256);
+        assert_eq!(ModuleHash::blake3(&[]).to_string(), EMPTY_BLAKE3);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            "e3b0c442".parse::<ModuleHash>(),
+            Err(ModuleHashParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            "md5:d41d8cd98f00b204e9800998ecf8427e".parse::<ModuleHash>(),
+            Err(ModuleHashParseError::UnsupportedAlgorithm(_))
+        ));
+        assert!(matches!(
+            "sha256:e3b0c442".parse::<ModuleHash>(),
+            Err(ModuleHashParseError::InvalidDigest { .. })
+        ));
+    }
+
+    #[test]
+    fn store_path() {
+        let hash = EMPTY_BLAKE3.parse::<ModuleHash>().unwrap();
+        assert_eq!(
+            hash.store_path(Path::new("/store")),
+            Path::new(
+                "/store/blake3/af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262.wasm"
+            )
+        );
+    }
+}
@@ -20,6 +20,7 @@
 extern crate core;
 
 use marine::Marine;
+use marine::MarineConfig;
 use marine::TomlMarineConfig;
 use marine_wasmtime_backend::WasmtimeWasmBackend;
 use marine_wasm_backend_traits::WasmBackend;
@@ -27,6 +28,9 @@ use marine_wasm_backend_traits::WasmBackend;
 use serde_json::json;
 use serde_json::Value;
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 #[tokio::test]
 async fn load_from_modules_dir() {
     let config_path = "tests/config_tests/ModulesDirConfig.toml";
@@ -97,3 +101,51 @@ async fn mapping_from_absolute_path_in_wasi_allowed() {
         .await
         .expect("Module should be loaded successfully");
 }
+
+#[test]
+fn mounted_binary_settings() {
+    let raw_config: TomlMarineConfig = toml::from_str(
+        r#"
+        total_memory_limit = "infinity"
+
+        [[module]]
+            name = "module"
+
+            [module.mounted_binaries]
+            echo = "/bin/echo"
+
+            [module.mounted_binaries.curl]
+            path = "/usr/bin/curl"
+            timeout = "1m 30s"
+            max_output_size = "1 KiB"
+            env_allowlist = ["PATH"]
+            envs = { "HOME" = "/tmp" }
+            working_dir = "curl"
+            rlimits = { cpu_time = 5, address_space = "1 MiB" }
+            arg_policy = { forbidden_flags = ["-o"], positional_patterns = ["https://.*"], max_args_count = 4 }
+        "#,
+    )
+    .expect("Config must be parsed");
+    let config: MarineConfig = raw_config.try_into().expect("Config must be converted");
+
+    let mounted_binaries = &config.modules_config[0].config.mounted_binaries;
+    let echo = &mounted_binaries["echo"];
+    assert_eq!(echo.path, PathBuf::from("/bin/echo"));
+    assert_eq!(echo.timeout, None);
+
+    let curl = &mounted_binaries["curl"];
+    assert_eq!(curl.path, PathBuf::from("/usr/bin/curl"));
+    assert_eq!(curl.timeout, Some(Duration::from_secs(90)));
+    assert_eq!(curl.max_output_size, Some(1024));
+    assert_eq!(curl.env_allowlist, Some(vec![String::from("PATH")]));
+    assert_eq!(curl.envs["HOME"], "/tmp");
+    assert_eq!(curl.working_dir, Some(PathBuf::from("curl")));
+    assert_eq!(curl.rlimits.cpu_time, Some(5));
+    assert_eq!(curl.rlimits.address_space, Some(1024 * 1024));
+    assert_eq!(curl.rlimits.file_size, None);
+    assert_eq!(curl.arg_policy.allowed_flags, None);
+    assert_eq!(curl.arg_policy.forbidden_flags, vec![String::from("-o")]);
+    assert_eq!(curl.arg_policy.max_args_count, Some(4));
+    assert!(curl.arg_policy.positional_patterns[0].is_match("https://fluence.network"));
+    assert!(!curl.arg_policy.positional_patterns[0].is_match("file:///https://"));
+}
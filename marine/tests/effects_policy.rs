@@ -0,0 +1,92 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine::Marine;
+use marine::MarineError;
+use marine::MError;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+use marine_wasm_backend_traits::WasmBackend;
+
+use std::collections::HashMap;
+
+async fn load_greeting(
+    allowed_effects: Option<&[&str]>,
+    denied_effects: Option<&[&str]>,
+) -> Result<Marine, MarineError> {
+    let to_strings = |kinds: &[&str]| kinds.iter().map(ToString::to_string).collect();
+    let config = marine::TomlMarineConfig {
+        module: vec![marine::TomlMarineNamedModuleConfig {
+            name: String::from("greeting"),
+            ..<_>::default()
+        }],
+        allowed_effects: allowed_effects.map(to_strings),
+        denied_effects: denied_effects.map(to_strings),
+        ..<_>::default()
+    };
+
+    let greeting = std::fs::read("../examples/greeting/artifacts/greeting.wasm").unwrap();
+    let modules = HashMap::from([(String::from("greeting"), greeting)]);
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    Marine::with_modules(backend, modules, config).await
+}
+
+#[tokio::test]
+pub async fn allowed_effects() {
+    load_greeting(Some(&["stdio", "environment_read", "proc_exit"]), None)
+        .await
+        .unwrap_or_else(|e| panic!("can't load module with allowed effects: {}", e));
+
+    let result = load_greeting(Some(&["logger", "call_parameters"]), None).await;
+    let error = match result {
+        Err(MarineError::EngineError(MError::PrepareError(error))) => error.to_string(),
+        Err(e) => panic!("expected a prepare error, got {}", e),
+        Ok(_) => panic!("module with forbidden effects is loaded"),
+    };
+    assert_eq!(
+        error,
+        "module with name 'greeting' has effects forbidden by the policy: \
+         stdio: fd_write (wasi_snapshot_preview1.fd_write), \
+         environment read: environ_get (wasi_snapshot_preview1.environ_get), \
+         environment read: environ_sizes_get (wasi_snapshot_preview1.environ_sizes_get), \
+         process exit (wasi_snapshot_preview1.proc_exit)"
+    );
+}
+
+#[tokio::test]
+pub async fn denied_effects() {
+    load_greeting(None, Some(&["mounted_binary", "filesystem"]))
+        .await
+        .unwrap_or_else(|e| panic!("can't load module without denied effects: {}", e));
+
+    // denied effects take precedence over allowed ones
+    let result = load_greeting(
+        Some(&["stdio", "environment_read", "proc_exit"]),
+        Some(&["proc_exit"]),
+    )
+    .await;
+    assert!(result.err().unwrap().to_string().ends_with(
+        "has effects forbidden by the policy: process exit (wasi_snapshot_preview1.proc_exit)"
+    ));
+}
+
+#[tokio::test]
+pub async fn unknown_effect_kind() {
+    let result = load_greeting(None, Some(&["network"])).await;
+    assert!(matches!(result, Err(MarineError::InvalidConfig(_))));
+}
@@ -0,0 +1,197 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine::Marine;
+use marine::MarineConfig;
+use marine::MarineError;
+use marine::MError;
+use marine::HostImportContext;
+use marine::HostImportDescriptor;
+use marine::HostImportError;
+use marine::HostImportFunc;
+use marine::HostImportProvider;
+use marine::IType;
+use marine::IValue;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+use marine_wasm_backend_traits::WasmBackend;
+
+use pretty_assertions::assert_eq;
+use futures::FutureExt;
+use once_cell::sync::Lazy;
+use serde_json::json;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+static CONFIG: Lazy<marine::TomlMarineConfig> = Lazy::new(|| {
+    marine::TomlMarineConfig::load("./tests/wasm_tests/host_imports_failures/Config.toml")
+        .expect("toml marine config should be created")
+});
+
+static PROVIDER_CONFIG: Lazy<marine::TomlMarineConfig> = Lazy::new(|| {
+    marine::TomlMarineConfig::load("./tests/wasm_tests/host_import_provider/Config.toml")
+        .expect("toml marine config should be created")
+});
+
+struct KVProvider;
+
+impl HostImportProvider<WasmtimeWasmBackend> for KVProvider {
+    fn host_imports(&self, context: &HostImportContext) -> HashMap<String, HostImportDescriptor> {
+        let context = context.clone();
+        let kv_get = move |args: Vec<IValue>| {
+            // call parameters are read in place, so the context is not moved into the future
+            let prefix = format!("{}/{}", context.service_id(), context.module_name());
+
+            async move {
+                let key = match args.as_slice() {
+                    [IValue::String(key)] => key.clone(),
+                    _ => return Err(HostImportError::MismatchWValuesCount),
+                };
+
+                Ok(vec![IValue::String(format!("{}/{}", prefix, key))])
+            }
+            .boxed()
+        };
+
+        let descriptor = HostImportDescriptor {
+            host_exported_func: HostImportFunc::Async(Box::new(kv_get)),
+            argument_types: vec![IType::String],
+            output_types: vec![IType::String],
+            error_handler: None,
+        };
+
+        HashMap::from([(String::from("kv_get"), descriptor)])
+    }
+}
+
+async fn call_malformed_and_greet(function_name: &str) -> MarineError {
+    let mut marine =
+        Marine::with_raw_config(WasmtimeWasmBackend::new_async().unwrap(), CONFIG.clone())
+            .await
+            .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    let error = marine
+        .call_with_ivalues_async("host_imports_failures", function_name, &[], <_>::default())
+        .await
+        .expect_err("malformed host import call should trap");
+
+    // the host and the module are still usable after the trap
+    let result = marine
+        .call_with_ivalues_async(
+            "host_imports_failures",
+            "greeting",
+            &[IValue::String(String::from("Fluence"))],
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke greeting: {:?}", e));
+    assert_eq!(result, vec![IValue::String(String::from("Hi, Fluence"))]);
+
+    error
+}
+
+#[tokio::test]
+pub async fn out_of_bounds_mounted_binary_args() {
+    let error = call_malformed_and_greet("out_of_bounds_mounted_binary_args").await;
+
+    assert!(
+        matches!(
+            error,
+            MarineError::EngineError(MError::HostImportError(HostImportError::LifterError(_)))
+        ),
+        "unexpected error: {:?}",
+        error
+    );
+}
+
+#[tokio::test]
+pub async fn out_of_bounds_mounted_binary_arg() {
+    let error = call_malformed_and_greet("out_of_bounds_mounted_binary_arg").await;
+
+    assert!(
+        matches!(
+            error,
+            MarineError::EngineError(MError::HostImportError(HostImportError::LifterError(_)))
+        ),
+        "unexpected error: {:?}",
+        error
+    );
+}
+
+#[tokio::test]
+pub async fn out_of_bounds_log_message() {
+    let error = call_malformed_and_greet("out_of_bounds_log_message").await;
+
+    assert!(
+        matches!(
+            error,
+            MarineError::EngineError(MError::HostImportError(HostImportError::LifterError(_)))
+        ),
+        "unexpected error: {:?}",
+        error
+    );
+}
+
+#[tokio::test]
+pub async fn host_import_provider() {
+    let mut config: MarineConfig = PROVIDER_CONFIG.clone().try_into().unwrap();
+    config
+        .host_import_providers
+        .insert(String::from("kv"), Arc::new(KVProvider));
+
+    let mut marine = Marine::with_raw_config(WasmtimeWasmBackend::new_async().unwrap(), config)
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    for service_id in ["first_service", "second_service"] {
+        let call_parameters = marine::CallParameters {
+            service_id: service_id.to_string(),
+            ..<_>::default()
+        };
+        let result = marine
+            .call_with_json_async(
+                "host_import_provider",
+                "get",
+                json!(["key"]),
+                call_parameters,
+            )
+            .await
+            .unwrap_or_else(|e| panic!("can't invoke get: {:?}", e));
+
+        assert_eq!(
+            result,
+            json!(format!("{}/host_import_provider/key", service_id))
+        );
+    }
+}
+
+#[tokio::test]
+pub async fn unregistered_host_import_provider() {
+    let result = Marine::with_raw_config(
+        WasmtimeWasmBackend::new_async().unwrap(),
+        PROVIDER_CONFIG.clone(),
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(MarineError::InvalidConfig(_))),
+        "unexpected result: {:?}",
+        result.err()
+    );
+}
@@ -0,0 +1,303 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine::Marine;
+use marine::ModuleLogRecord;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+use marine_wasm_backend_traits::WasmBackend;
+
+use pretty_assertions::assert_eq;
+use once_cell::sync::Lazy;
+use serde_json::json;
+
+static CONFIG: Lazy<marine::TomlMarineConfig> = Lazy::new(|| {
+    marine::TomlMarineConfig::load("./tests/wasm_tests/logging/Config.toml")
+        .expect("toml marine config should be created")
+});
+
+fn record(level: log::Level, target: i32, message: &str) -> ModuleLogRecord {
+    ModuleLogRecord {
+        level,
+        module: String::from("logging"),
+        target,
+        message: message.to_string(),
+    }
+}
+
+#[tokio::test]
+pub async fn call_capturing_logs() {
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let mut marine = Marine::with_raw_config(backend, CONFIG.clone())
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    let (result, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    result.unwrap_or_else(|e| panic!("can't invoke log_levels: {:?}", e));
+
+    use log::Level::*;
+    let expected_logs = [Error, Warn, Info, Debug, Trace]
+        .into_iter()
+        .map(|level| record(level, 0, "msg"))
+        .collect::<Vec<_>>();
+    assert_eq!(logs, expected_logs);
+
+    let (result, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_targets",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    result.unwrap_or_else(|e| panic!("can't invoke log_targets: {:?}", e));
+    assert_eq!(
+        logs,
+        vec![
+            record(Info, 1, "msg"),
+            record(Info, 2, "msg"),
+            record(Info, 0, "msg")
+        ]
+    );
+
+    // records aren't collected by calls that don't capture logs
+    marine
+        .call_with_json_async("logging", "log_targets", json!(["msg"]), <_>::default())
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke log_targets: {:?}", e));
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async("logging", "log_many", json!([1, 3]), <_>::default())
+        .await;
+    assert_eq!(logs, vec![record(Info, 0, "aaa")]);
+}
+
+#[tokio::test]
+pub async fn service_logger_settings() {
+    let mut config = CONFIG.clone();
+    config.log_directives = Some(String::from("trace,logging=info"));
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let mut marine = Marine::with_raw_config(backend, config)
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    use log::Level::*;
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(
+        logs,
+        vec![
+            record(Error, 0, "msg"),
+            record(Warn, 0, "msg"),
+            record(Info, 0, "msg")
+        ]
+    );
+
+    marine.set_log_directives(Some("logging=warn"));
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(logs, vec![record(Error, 0, "msg"), record(Warn, 0, "msg")]);
+
+    marine.set_log_directives(Some("info"));
+    marine
+        .set_logging_mask("logging", 2)
+        .unwrap_or_else(|e| panic!("can't set logging mask: {}", e));
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_targets",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(logs, vec![record(Info, 2, "msg"), record(Info, 0, "msg")]);
+
+    let result = marine.set_logging_mask("unknown", 1);
+    assert!(matches!(result, Err(marine::MarineError::NoSuchModule(_))));
+}
+
+#[tokio::test]
+pub async fn log_level_could_be_raised_at_runtime() {
+    let mut config = CONFIG.clone();
+    // without WASM_LOG in the wasi config the module level is managed by the log directives
+    config.module[0].config.wasi = None;
+    config.log_directives = Some(String::from("logging=warn"));
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let mut marine = Marine::with_raw_config(backend, config)
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    use log::Level::*;
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(logs, vec![record(Error, 0, "msg"), record(Warn, 0, "msg")]);
+
+    marine.set_log_directives(Some("logging=trace"));
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    let expected_logs = [Error, Warn, Info, Debug, Trace]
+        .into_iter()
+        .map(|level| record(level, 0, "msg"))
+        .collect::<Vec<_>>();
+    assert_eq!(logs, expected_logs);
+
+    // records of modules without a directive are filtered by the default level of the SDK
+    marine.set_log_directives(Some("other_module=trace"));
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_levels",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(
+        logs,
+        vec![
+            record(Error, 0, "msg"),
+            record(Warn, 0, "msg"),
+            record(Info, 0, "msg")
+        ]
+    );
+}
+
+async fn marine_with_limits(limits: marine::TomlLoggerLimits) -> Marine {
+    let mut config = CONFIG.clone();
+    config.module[0].config.logger_limits = Some(limits);
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    Marine::with_raw_config(backend, config)
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e))
+}
+
+#[tokio::test]
+pub async fn logger_limits() {
+    use log::Level::Info;
+    use marine::TRUNCATION_MARKER;
+
+    let mut marine = marine_with_limits(marine::TomlLoggerLimits {
+        max_message_size: Some(bytesize::ByteSize::b(8)),
+        max_bytes_per_call: Some(bytesize::ByteSize::b(20)),
+        truncate: Some(true),
+        ..<_>::default()
+    })
+    .await;
+
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async("logging", "log_many", json!([4, 10]), <_>::default())
+        .await;
+    let truncated = |size| format!("{}{}", "a".repeat(size), TRUNCATION_MARKER);
+    assert_eq!(
+        logs,
+        vec![
+            record(Info, 0, &truncated(8)),
+            record(Info, 0, &truncated(8)),
+            record(Info, 0, &truncated(4)),
+        ]
+    );
+
+    // the budget of bytes is renewed for every call
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async("logging", "log_many", json!([1, 5]), <_>::default())
+        .await;
+    assert_eq!(logs, vec![record(Info, 0, "aaaaa")]);
+
+    let stats = marine.log_suppression_stats()["logging"];
+    assert_eq!(stats.truncated_messages, 3);
+    assert_eq!(stats.dropped_messages, 1);
+
+    let mut marine = marine_with_limits(marine::TomlLoggerLimits {
+        max_messages_per_second: Some(2),
+        ..<_>::default()
+    })
+    .await;
+
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async("logging", "log_many", json!([5, 1]), <_>::default())
+        .await;
+    assert_eq!(logs, vec![record(Info, 0, "a"), record(Info, 0, "a")]);
+    assert_eq!(
+        marine.log_suppression_stats()["logging"].dropped_messages,
+        3
+    );
+}
+
+#[tokio::test]
+pub async fn named_log_targets() {
+    let mut config = CONFIG.clone();
+    config.module[0].config.log_targets = Some(vec![String::from("http")]);
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let mut marine = Marine::with_raw_config(backend.clone(), config.clone())
+        .await
+        .unwrap_or_else(|e| panic!("can't create Marine instance: {}", e));
+
+    use log::Level::Info;
+    let (_, logs) = marine
+        .call_with_json_capturing_logs_async(
+            "logging",
+            "log_targets",
+            json!(["msg"]),
+            <_>::default(),
+        )
+        .await;
+    assert_eq!(logs, vec![record(Info, 2, "msg"), record(Info, 0, "msg")]);
+
+    config.module[0].config.log_targets = Some(vec![String::from("cache")]);
+    let result = Marine::with_raw_config(backend.clone(), config.clone()).await;
+    assert!(matches!(result, Err(marine::MarineError::InvalidConfig(_))));
+
+    // the logging mask is made from the targets, so an explicit one would be lost
+    config.module[0].config.log_targets = Some(vec![String::from("http")]);
+    config.module[0].config.logging_mask = Some(1);
+    let result = Marine::with_raw_config(backend, config).await;
+    assert!(matches!(result, Err(marine::MarineError::InvalidConfig(_))));
+}
@@ -0,0 +1,125 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine::Marine;
+use marine::MarineError;
+use marine::ModuleHash;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+use marine_wasm_backend_traits::WasmBackend;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const GREETING_PATH: &str = "../examples/greeting/artifacts/greeting.wasm";
+
+fn greeting_config(hash: &ModuleHash) -> marine::TomlMarineConfig {
+    marine::TomlMarineConfig {
+        module: vec![marine::TomlMarineNamedModuleConfig {
+            name: String::from("greeting"),
+            hash: Some(hash.to_string()),
+            ..<_>::default()
+        }],
+        ..<_>::default()
+    }
+}
+
+async fn call_greeting(marine: &mut Marine) {
+    let result = marine
+        .call_with_json_async(
+            "greeting",
+            "greeting",
+            serde_json::json!(["Fluence"]),
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke greeting: {:?}", e));
+    assert_eq!(result, serde_json::json!("Hi, Fluence"));
+}
+
+#[tokio::test]
+pub async fn matching_hash() {
+    let greeting = std::fs::read(GREETING_PATH).unwrap();
+
+    for hash in [ModuleHash::sha256(&greeting), ModuleHash::blake3(&greeting)] {
+        let modules = HashMap::from([(String::from("greeting"), greeting.clone())]);
+        let backend = WasmtimeWasmBackend::new_async().unwrap();
+        let mut marine = Marine::with_modules(backend, modules, greeting_config(&hash))
+            .await
+            .unwrap_or_else(|e| panic!("can't load module with {} hash: {}", hash, e));
+        call_greeting(&mut marine).await;
+    }
+}
+
+#[tokio::test]
+pub async fn hash_mismatch() {
+    let greeting = std::fs::read(GREETING_PATH).unwrap();
+    let hash = ModuleHash::sha256(b"another module");
+
+    let modules = HashMap::from([(String::from("greeting"), greeting.clone())]);
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let result = Marine::with_modules(backend, modules, greeting_config(&hash)).await;
+
+    match result {
+        Err(MarineError::ModuleHashMismatch {
+            module_name,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(module_name, "greeting");
+            assert_eq!(expected, hash);
+            assert_eq!(actual, ModuleHash::sha256(&greeting));
+        }
+        Err(e) => panic!("expected hash mismatch, got {}", e),
+        Ok(_) => panic!("module with a wrong hash is loaded"),
+    }
+}
+
+#[tokio::test]
+pub async fn invalid_hash() {
+    let mut config = greeting_config(&ModuleHash::sha256(&[]));
+    config.module[0].hash = Some(String::from("md5:d41d8cd98f00b204e9800998ecf8427e"));
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let result = Marine::with_modules(backend, HashMap::new(), config).await;
+    assert!(matches!(result, Err(MarineError::InvalidConfig(_))));
+}
+
+#[tokio::test]
+pub async fn load_from_module_store() {
+    let greeting = std::fs::read(GREETING_PATH).unwrap();
+    let hash = ModuleHash::blake3(&greeting);
+
+    let module_store =
+        std::env::temp_dir().join(format!("marine_module_store_{}", std::process::id()));
+    let module_path = hash.store_path(&module_store);
+    std::fs::create_dir_all(module_path.parent().unwrap()).unwrap();
+    std::fs::write(&module_path, &greeting).unwrap();
+
+    let mut config = greeting_config(&hash);
+    config.module_store = Some(module_store.clone());
+    // modules_dir isn't used for modules found in the store
+    config.modules_dir = Some(PathBuf::from("/nonexistent"));
+
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    let result = Marine::with_raw_config(backend, config).await;
+    std::fs::remove_dir_all(&module_store).unwrap();
+
+    let mut marine = result.unwrap_or_else(|e| panic!("can't load module from store: {}", e));
+    call_greeting(&mut marine).await;
+}
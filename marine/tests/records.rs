@@ -171,6 +171,152 @@ pub async fn records() {
     assert_eq!(result5, expected_result);
 }
 
+#[tokio::test]
+async fn records_json_options() {
+    let records_config_raw = std::fs::read("../examples/records/Config.toml")
+        .expect("../examples/records/Config.toml should presence");
+
+    let mut records_config: marine::TomlMarineConfig =
+        toml::from_slice(&records_config_raw).expect("records config should be well-formed");
+    records_config.modules_dir = Some(PathBuf::from("../examples/records/artifacts/"));
+
+    let mut marine =
+        Marine::with_raw_config(WasmtimeWasmBackend::new_async().unwrap(), records_config)
+            .await
+            .unwrap_or_else(|e| panic!("can't create Fluence FaaS instance: {}", e));
+
+    let json_options = marine::JsonOptions {
+        bytes: marine::BytesRepresentation::Base64,
+        int64: marine::Int64Representation::String,
+    };
+    let result = marine
+        .call_with_json_options_async(
+            "records_effector",
+            "mutate_struct",
+            json!({
+                "test_record": [false, 0, 0, 0, "-9223372036854775808", 0, 0, 0, "18446744073709551615", 0, 0, "", "AQ=="]
+            }),
+            &json_options,
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke mutate_struct: {:?}", e));
+
+    let expected_result = json!({
+        "field_0": true,
+        "field_1": 1,
+        "field_2": 2,
+        "field_3": 3,
+        "field_4": "4",
+        "field_5": 5,
+        "field_6": 6,
+        "field_7": 7,
+        "field_8": "8",
+        "field_9": 9.0,
+        "field_10": 10.0,
+        "field_11": "field_11",
+        "field_12": "Ezc=",
+    });
+
+    assert_eq!(result, expected_result);
+}
+
+#[tokio::test]
+async fn records_binary_encodings() {
+    let records_config_raw = std::fs::read("../examples/records/Config.toml")
+        .expect("../examples/records/Config.toml should presence");
+
+    let mut records_config: marine::TomlMarineConfig =
+        toml::from_slice(&records_config_raw).expect("records config should be well-formed");
+    records_config.modules_dir = Some(PathBuf::from("../examples/records/artifacts/"));
+
+    let mut marine =
+        Marine::with_raw_config(WasmtimeWasmBackend::new_async().unwrap(), records_config)
+            .await
+            .unwrap_or_else(|e| panic!("can't create Fluence FaaS instance: {}", e));
+
+    let test_record = rmpv::Value::Array(vec![
+        false.into(),
+        0.into(),
+        0.into(),
+        0.into(),
+        0.into(),
+        0.into(),
+        0.into(),
+        0.into(),
+        u64::MAX.into(),
+        0.into(),
+        0.into(),
+        "field".into(),
+        rmpv::Value::Binary(vec![1]),
+    ]);
+    let args = rmpv::Value::Map(vec![("test_record".into(), test_record)]);
+    let mut encoded_args = Vec::new();
+    rmpv::encode::write_value(&mut encoded_args, &args).unwrap();
+
+    let result = marine
+        .call_with_msgpack_async(
+            "records_effector",
+            "mutate_struct",
+            &encoded_args,
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke mutate_struct: {:?}", e));
+
+    let result = rmpv::decode::read_value(&mut result.as_slice()).unwrap();
+    assert_eq!(result["field_8"], rmpv::Value::from(8));
+    assert_eq!(result["field_11"], rmpv::Value::from("field_11"));
+    assert_eq!(result["field_12"], rmpv::Value::Binary(vec![0x13, 0x37]));
+
+    let args = ciborium::Value::Array(vec![ciborium::Value::Map(vec![
+        ("field_0".into(), false.into()),
+        ("field_1".into(), 0.into()),
+        ("field_2".into(), 0.into()),
+        ("field_3".into(), 0.into()),
+        ("field_4".into(), 0.into()),
+        ("field_5".into(), 0.into()),
+        ("field_6".into(), 0.into()),
+        ("field_7".into(), 0.into()),
+        ("field_8".into(), 0.into()),
+        ("field_9".into(), 0.0.into()),
+        ("field_10".into(), 0.0.into()),
+        ("field_11".into(), "".into()),
+        ("field_12".into(), ciborium::Value::Bytes(vec![1])),
+    ])]);
+    let mut encoded_args = Vec::new();
+    ciborium::ser::into_writer(&args, &mut encoded_args).unwrap();
+
+    let result = marine
+        .call_with_cbor_async(
+            "records_effector",
+            "mutate_struct",
+            &encoded_args,
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke mutate_struct: {:?}", e));
+
+    let result: ciborium::Value = ciborium::de::from_reader(result.as_slice()).unwrap();
+    let fields = result.as_map().unwrap();
+    assert_eq!(
+        fields.last().unwrap(),
+        &("field_12".into(), ciborium::Value::Bytes(vec![0x13, 0x37]))
+    );
+
+    let error = marine
+        .call_with_msgpack_async("records_effector", "mutate_struct", &[0xc1], <_>::default())
+        .await
+        .expect_err("arguments are malformed");
+    assert!(matches!(
+        error,
+        marine::MarineError::ArgumentsDeserializationError {
+            encoding: "MessagePack",
+            ..
+        }
+    ));
+}
+
 #[tokio::test]
 async fn records_passing() {
     let inner_records_config_raw = std::fs::read("./tests/wasm_tests/records_passing/Config.toml")
@@ -237,6 +383,56 @@ async fn records_passing() {
     run_test(&mut marine, "test_record_ref").await;
 }
 
+#[tokio::test]
+async fn records_arguments_error_path() {
+    let inner_records_config_raw = std::fs::read("./tests/wasm_tests/records_passing/Config.toml")
+        .expect("./tests/wasm_tests/records_passing/Config.toml should presence");
+
+    let mut records_passing_config: marine::TomlMarineConfig =
+        toml::from_slice(&inner_records_config_raw)
+            .expect("argument passing test config should be well-formed");
+
+    records_passing_config.modules_dir = Some(PathBuf::from(
+        "./tests/wasm_tests/records_passing/artifacts",
+    ));
+
+    let mut marine = Marine::with_raw_config(
+        WasmtimeWasmBackend::new_async().unwrap(),
+        records_passing_config,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("can't create Fluence FaaS instance: {}", e));
+
+    let error = marine
+        .call_with_json_async(
+            "records_passing_pure",
+            "test_record",
+            json!({
+                "test_record": {
+                    "test_record_0": {
+                        "field_0": 0
+                    },
+                    "test_record_1": {
+                        "field_0": 1,
+                        "field_1": 1,
+                        "field_2": vec![1],
+                        "test_record_0": {
+                            "field_0": 1
+                        }
+                    }
+                }
+            }),
+            <_>::default(),
+        )
+        .await
+        .expect_err("arguments shouldn't match the function signature");
+
+    assert_eq!(
+        error.to_string(),
+        r#"arguments from json deserialization error in module "records_passing_pure", function "test_record": at /test_record/test_record_1/field_1: expected string, got number"#
+    );
+}
+
 #[tokio::test]
 async fn records_destruction() {
     let inner_records_config_raw = std::fs::read("./tests/wasm_tests/records_passing/Config.toml")
@@ -0,0 +1,125 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine::Marine;
+use marine::MarineError;
+use marine::MError;
+use marine::ModuleSigner;
+use marine_module_info_parser::signature;
+use marine_wasmtime_backend::WasmtimeWasmBackend;
+use marine_wasm_backend_traits::WasmBackend;
+
+use std::collections::HashMap;
+
+const SIGNING_KEY: &str = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+const OTHER_SIGNING_KEY: &str = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=";
+
+fn signer(key: &str) -> ModuleSigner {
+    key.parse::<signature::SigningKey>().unwrap().signer()
+}
+
+async fn load_greeting(
+    wasm_bytes: Vec<u8>,
+    trusted_signers: Option<Vec<ModuleSigner>>,
+) -> Result<Marine, MarineError> {
+    let config = marine::TomlMarineConfig {
+        module: vec![marine::TomlMarineNamedModuleConfig {
+            name: String::from("greeting"),
+            ..<_>::default()
+        }],
+        trusted_signers: trusted_signers
+            .map(|signers| signers.iter().map(ToString::to_string).collect()),
+        ..<_>::default()
+    };
+
+    let modules = HashMap::from([(String::from("greeting"), wasm_bytes)]);
+    let backend = WasmtimeWasmBackend::new_async().unwrap();
+    Marine::with_modules(backend, modules, config).await
+}
+
+#[tokio::test]
+pub async fn trusted_signers() {
+    let greeting = std::fs::read("../examples/greeting/artifacts/greeting.wasm").unwrap();
+    let signing_key = SIGNING_KEY.parse::<signature::SigningKey>().unwrap();
+    let signed_greeting = signature::sign(&greeting, &signing_key).unwrap();
+
+    // signatures aren't checked without trusted signers
+    load_greeting(greeting.clone(), None)
+        .await
+        .unwrap_or_else(|e| panic!("can't load unsigned module: {}", e));
+
+    let mut marine = load_greeting(signed_greeting.clone(), Some(vec![signer(SIGNING_KEY)]))
+        .await
+        .unwrap_or_else(|e| panic!("can't load signed module: {}", e));
+    let result = marine
+        .call_with_json_async(
+            "greeting",
+            "greeting",
+            serde_json::json!(["Fluence"]),
+            <_>::default(),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("can't invoke greeting: {:?}", e));
+    assert_eq!(result, serde_json::json!("Hi, Fluence"));
+
+    let result = load_greeting(greeting, Some(vec![signer(SIGNING_KEY)])).await;
+    assert!(matches!(
+        result,
+        Err(MarineError::EngineError(MError::PrepareError(_)))
+    ));
+    assert!(result.err().unwrap().to_string().contains("isn't signed"));
+
+    let result = load_greeting(signed_greeting, Some(vec![signer(OTHER_SIGNING_KEY)])).await;
+    assert!(result
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("isn't a trusted signer"));
+}
+
+#[tokio::test]
+pub async fn tampered_module() {
+    let greeting = std::fs::read("../examples/greeting/artifacts/greeting.wasm").unwrap();
+    let signing_key = SIGNING_KEY.parse::<signature::SigningKey>().unwrap();
+    let mut signed_greeting = signature::sign(&greeting, &signing_key).unwrap();
+
+    // replace the greeting in the data section
+    let position = signed_greeting
+        .windows(b"Hi, ".len())
+        .position(|window| window == b"Hi, ")
+        .unwrap();
+    signed_greeting[position] = b'H';
+    signed_greeting[position + 1] = b'o';
+
+    let result = load_greeting(signed_greeting, Some(vec![signer(SIGNING_KEY)])).await;
+    assert!(result
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("has an invalid signature"));
+}
+
+#[tokio::test]
+pub async fn signature_is_checked_before_compilation() {
+    // an empty module would be rejected after compilation for the lack of the sdk version
+    let empty_module = b"\0asm\x01\0\0\0".to_vec();
+
+    let result = load_greeting(empty_module, Some(vec![signer(SIGNING_KEY)])).await;
+    assert!(result.err().unwrap().to_string().contains("isn't signed"));
+}
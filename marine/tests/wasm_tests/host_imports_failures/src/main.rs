@@ -0,0 +1,53 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine_rs_sdk::marine;
+
+pub fn main() {}
+
+// host imports are declared by hand to be able to pass malformed arguments to them
+#[link(wasm_import_module = "host")]
+extern "C" {
+    // mounted binary from the config, takes a pointer and a size of an array of strings
+    fn echo(args_offset: i32, args_size: i32);
+
+    fn log_utf8_string(level: i32, target: i32, msg_offset: i32, msg_size: i32);
+}
+
+#[marine]
+pub fn out_of_bounds_mounted_binary_args() {
+    unsafe { echo(i32::MAX, 16) }
+}
+
+#[marine]
+pub fn out_of_bounds_mounted_binary_arg() {
+    // an array with one string which points outside the memory
+    let raw_string = [i32::MAX as u32, 16u32];
+    unsafe { echo(raw_string.as_ptr() as _, 1) }
+}
+
+#[marine]
+pub fn out_of_bounds_log_message() {
+    unsafe { log_utf8_string(3, 0, i32::MAX, 16) }
+}
+
+#[marine]
+pub fn greeting(name: String) -> String {
+    format!("Hi, {}", name)
+}
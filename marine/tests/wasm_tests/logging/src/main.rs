@@ -0,0 +1,61 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine_rs_sdk::marine;
+use marine_rs_sdk::TargetMap;
+use marine_rs_sdk::WasmLoggerBuilder;
+
+// masks of the targets are the same as in the target map of the logger
+#[cfg(target_arch = "wasm32")]
+#[link_section = "__fluence_log_targets"]
+#[doc(hidden)]
+pub static LOG_TARGETS: [u8; 12] = *b"db=1\nhttp=2\n";
+
+pub fn main() {
+    let target_map: TargetMap = [("db", 1), ("http", 2)].into_iter().collect();
+
+    WasmLoggerBuilder::new()
+        .with_target_map(target_map)
+        .build()
+        .unwrap();
+}
+
+#[marine]
+pub fn log_levels(message: String) {
+    log::error!("{}", message);
+    log::warn!("{}", message);
+    log::info!("{}", message);
+    log::debug!("{}", message);
+    log::trace!("{}", message);
+}
+
+#[marine]
+pub fn log_targets(message: String) {
+    log::info!(target: "db", "{}", message);
+    log::info!(target: "http", "{}", message);
+    log::info!("{}", message);
+}
+
+#[marine]
+pub fn log_many(count: u32, size: u32) {
+    let message = "a".repeat(size as usize);
+    for _ in 0..count {
+        log::info!("{}", message);
+    }
+}
@@ -32,6 +32,16 @@ pub const SERVICE_NAME: &str = "service-name";
 pub const PROJECT_NAME: &str = "generate-project-name";
 pub const SHOULD_INIT_OPTION: &str = "should-init";
 pub const SERVICE_ID: &str = "service-id";
+pub const FUNCTION_NAME: &str = "function-name";
+pub const IN_PATH: &str = "in-path";
+pub const OUT_PATH: &str = "out-path";
+pub const CLIENT_LANGUAGE: &str = "client-language";
+pub const OLD_WASM_PATH: &str = "old-wasm-path";
+pub const NEW_WASM_PATH: &str = "new-wasm-path";
+pub const OUTPUT_FORMAT: &str = "output-format";
+pub const KEY_PATH: &str = "key-path";
+pub const DATA_DIR: &str = "data-dir";
+pub const BUNDLE_PATH: &str = "bundle-path";
 
 pub const SDK_VERSION: &str = "sdk-version";
 
@@ -56,6 +66,7 @@ pub fn aqua<'a, 'b>() -> App<'a, 'b> {
                 .short("i")
                 .long("id")
                 .help("optional service id"),
+            output_format(),
         ])
 }
 
@@ -148,22 +159,214 @@ pub fn show_wit<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("it")
         .about("Shows IT of the provided Wasm file")
         .setting(clap::AppSettings::ArgRequiredElseHelp)
-        .args(&[Arg::with_name(IN_WASM_PATH)
-            .required(true)
-            .takes_value(true)
-            .index(1)
-            .help("path to the Wasm file")])
+        .args(&[
+            Arg::with_name(IN_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file"),
+            output_format(),
+        ])
 }
 
 pub fn show_manifest<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("info")
         .about("Shows manifest and sdk version of the provided Wasm file")
         .setting(clap::AppSettings::ArgRequiredElseHelp)
-        .args(&[Arg::with_name(IN_WASM_PATH)
+        .args(&[
+            Arg::with_name(IN_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file"),
+            output_format(),
+        ])
+}
+
+pub fn effects<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("effects")
+        .about("Shows what the provided Wasm file could access outside of its memory: host imports, WASI functions and other modules")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(IN_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file"),
+            output_format(),
+        ])
+}
+
+pub fn schema<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("schema")
+        .about("Shows JSON Schemas of arguments and results of functions exported by the provided Wasm file")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(IN_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file"),
+            Arg::with_name(FUNCTION_NAME)
+                .required(false)
+                .takes_value(true)
+                .short("f")
+                .long("function")
+                .help("optional name of a function to show schemas only for it"),
+        ])
+}
+
+pub fn generate_client<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("generate-client")
+        .about("Generates a typed client for the provided Wasm file or the facade module of a service config")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(IN_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file or to the service TOML config"),
+            Arg::with_name(CLIENT_LANGUAGE)
+                .required(true)
+                .takes_value(true)
+                .short("l")
+                .long("lang")
+                .possible_values(&["ts", "rust"])
+                .help("a language of the client"),
+            Arg::with_name(SERVICE_NAME)
+                .required(false)
+                .takes_value(true)
+                .short("s")
+                .long("service")
+                .help("optional service name, the module name is used by default"),
+            Arg::with_name(OUT_PATH)
+                .required(false)
+                .takes_value(true)
+                .short("o")
+                .long("output")
+                .help("a path to the result file. If absent, prints the client to stdout."),
+        ])
+}
+
+pub fn diff<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("diff")
+        .about("Shows interface changes between two versions of a Wasm module, exits with code 2 if some of them are breaking")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(OLD_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the old version of the Wasm file"),
+            Arg::with_name(NEW_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(2)
+                .help("path to the new version of the Wasm file"),
+        ])
+}
+
+pub fn sign<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("sign")
+        .about("Signs the code and interface types of the provided Wasm file with an ed25519 key")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(IN_WASM_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the Wasm file"),
+            Arg::with_name(KEY_PATH)
+                .required(true)
+                .takes_value(true)
+                .short("k")
+                .long("key")
+                .help("a path to a file with a base64 encoded 32-byte ed25519 secret key"),
+            Arg::with_name(OUT_WASM_PATH)
+                .takes_value(true)
+                .short("o")
+                .long("output")
+                .help("A path to the result signed Wasm file. If absent, modifies input file."),
+        ])
+}
+
+pub fn check_config<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("check-config")
+        .about("Resolves includes and environment variables of the provided service config and reports all its errors")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[Arg::with_name(IN_PATH)
             .required(true)
             .takes_value(true)
             .index(1)
-            .help("path to the Wasm file")])
+            .help("path to the service TOML config")])
+}
+
+pub fn bundle<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("bundle")
+        .about("Packs a service into a single verifiable archive and inspects such archives")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .subcommand(bundle_create())
+        .subcommand(bundle_inspect())
+        .subcommand(bundle_verify())
+}
+
+fn bundle_create<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("create")
+        .about("Creates a bundle from the service TOML config, its modules and initial data")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(IN_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the service TOML config"),
+            Arg::with_name(DATA_DIR)
+                .takes_value(true)
+                .short("d")
+                .long("data")
+                .help("a path to a directory with initial data of the service"),
+            Arg::with_name(OUT_PATH)
+                .required(true)
+                .takes_value(true)
+                .short("o")
+                .long("output")
+                .help("a path to the result bundle"),
+        ])
+}
+
+fn bundle_inspect<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("inspect")
+        .about("Shows the manifest of the provided bundle")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[
+            Arg::with_name(BUNDLE_PATH)
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the bundle"),
+            output_format(),
+        ])
+}
+
+fn bundle_verify<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("verify")
+        .about("Checks that every file of the provided bundle matches its hash from the manifest")
+        .setting(clap::AppSettings::ArgRequiredElseHelp)
+        .args(&[Arg::with_name(BUNDLE_PATH)
+            .required(true)
+            .takes_value(true)
+            .index(1)
+            .help("path to the bundle")])
+}
+
+fn output_format<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(OUTPUT_FORMAT)
+        .required(false)
+        .takes_value(true)
+        .long("format")
+        .possible_values(&["text", "json"])
+        .default_value("text")
+        .help("an output format, json is intended for other tools")
 }
 
 pub fn repl<'a, 'b>() -> App<'a, 'b> {
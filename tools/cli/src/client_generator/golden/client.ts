@@ -0,0 +1,75 @@
+// Generated by `marine generate-client`, do not edit.
+
+// Compatible with marine-js `call_module`: takes JSON-encoded arguments
+// and returns a JSON-encoded result.
+export type CallModule = (
+  moduleName: string,
+  functionName: string,
+  args: string,
+  callParameters: unknown,
+) => string | Promise<string>;
+
+// Used when call parameters aren't passed, marine-js can't call a module without them.
+export const defaultCallParameters = {
+  particle: {
+    id: "",
+    init_peer_id: "",
+    timestamp: 0,
+    ttl: 0,
+    script: "",
+    signature: [],
+    token: "",
+  },
+  service_id: "",
+  service_creator_peer_id: "",
+  host_id: "",
+  worker_id: "",
+  tetraplets: [],
+};
+
+// 64-bit integers are passed through JSON as numbers,
+// so their values beyond Number.MAX_SAFE_INTEGER lose precision.
+
+export interface Item {
+  type: number;
+  data: number[];
+}
+
+export interface Batch {
+  self: Item;
+  items: Item[];
+}
+
+export class StorageClient {
+  constructor(
+    private readonly callModule: CallModule,
+    private readonly moduleName: string = "storage",
+  ) {}
+
+  async checksum(data: number[], callParameters: unknown = defaultCallParameters): Promise<[number, string]> {
+    const args = JSON.stringify({ "data": data });
+    const result = await this.callModule(this.moduleName, "checksum", args, callParameters);
+    return JSON.parse(result);
+  }
+
+  async constructor_(args_: string, callParameters: unknown = defaultCallParameters): Promise<void> {
+    const args = JSON.stringify({ "args": args_ });
+    await this.callModule(this.moduleName, "constructor", args, callParameters);
+  }
+
+  async delete(callParameters: unknown = defaultCallParameters): Promise<void> {
+    const args = JSON.stringify({});
+    await this.callModule(this.moduleName, "delete", args, callParameters);
+  }
+
+  async match(in_: Batch, callParameters: unknown = defaultCallParameters): Promise<Item> {
+    const args = JSON.stringify({ "in": in_ });
+    const result = await this.callModule(this.moduleName, "match", args, callParameters);
+    return JSON.parse(result);
+  }
+
+  async new(call_parameters: string, callParameters_: boolean, callParameters: unknown = defaultCallParameters): Promise<void> {
+    const args = JSON.stringify({ "call_parameters": call_parameters, "callParameters": callParameters_ });
+    await this.callModule(this.moduleName, "new", args, callParameters);
+  }
+}
@@ -0,0 +1,341 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+mod rust;
+mod typescript;
+
+use marine_module_interface::interface::it_to_module_interface;
+use marine_module_interface::it_interface::IFunctionSignature;
+use marine_module_interface::it_interface::IModuleInterface;
+use marine_module_interface::it_interface::IRecordTypes;
+
+use wasmer_it::IRecordType;
+
+use serde::Deserialize;
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClientLanguage {
+    TypeScript,
+    Rust,
+}
+
+impl FromStr for ClientLanguage {
+    type Err = anyhow::Error;
+
+    fn from_str(language: &str) -> Result<Self, Self::Err> {
+        match language {
+            "ts" | "typescript" => Ok(Self::TypeScript),
+            "rust" | "rs" => Ok(Self::Rust),
+            _ => Err(anyhow::Error::msg(format!(
+                "unsupported client language {}, expected ts or rust",
+                language
+            ))),
+        }
+    }
+}
+
+/// Generates a client for a module from a Wasm file, or for the facade module of a service
+/// from its TOML config.
+pub(crate) fn generate_client(
+    input_path: &Path,
+    language: ClientLanguage,
+    service_name: Option<&str>,
+) -> Result<String, anyhow::Error> {
+    use inflector::Inflector;
+
+    let (module_name, wasm_path) = resolve_module(input_path)?;
+    let service_name = service_name.unwrap_or(&module_name).to_pascal_case();
+
+    let module_interface = marine_it_parser::module_it_interface(wasm_path)?;
+    let client = ClientDescription::new(service_name, module_name, module_interface)?;
+
+    let code = match language {
+        ClientLanguage::TypeScript => typescript::generate(&client),
+        ClientLanguage::Rust => rust::generate(&client),
+    };
+
+    Ok(code)
+}
+
+/// Everything needed to generate a client in any language.
+struct ClientDescription {
+    service_name: String,
+    module_name: String,
+    /// Record types used by exported functions, topologically sorted.
+    records: Vec<Arc<IRecordType>>,
+    /// Exported functions sorted by name.
+    functions: Vec<IFunctionSignature>,
+    record_types: IRecordTypes,
+}
+
+impl ClientDescription {
+    fn new(
+        service_name: String,
+        module_name: String,
+        module_interface: IModuleInterface,
+    ) -> Result<Self, anyhow::Error> {
+        let record_types = module_interface.export_record_types.clone();
+        let mut functions = module_interface.function_signatures.clone();
+        functions.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+        // ModuleInterface keeps records in the topological order,
+        // so that every record is declared after records it refers to
+        let records = it_to_module_interface(module_interface)?
+            .record_types
+            .iter()
+            .filter_map(|record| record_types.get(&record.id).cloned())
+            .collect();
+
+        let client = Self {
+            service_name,
+            module_name,
+            records,
+            functions,
+            record_types,
+        };
+
+        Ok(client)
+    }
+
+    fn record_name(&self, record_id: u64) -> &str {
+        // record ids are guaranteed to be valid by the interface extraction
+        &self.record_types[&record_id].name
+    }
+}
+
+/// Appends underscores to identifiers colliding with reserved ones, which the generated client
+/// uses itself, until they don't collide with any other identifier either.
+fn deduplicate_identifiers(identifiers: Vec<String>, reserved: &[&str]) -> Vec<String> {
+    let mut deduplicated = Vec::<String>::with_capacity(identifiers.len());
+    for (position, identifier) in identifiers.iter().enumerate() {
+        let is_taken = |candidate: &str| {
+            reserved.contains(&candidate)
+                || deduplicated.iter().any(|taken| taken == candidate)
+                || identifiers
+                    .iter()
+                    .enumerate()
+                    .any(|(other_position, other)| other_position != position && other == candidate)
+        };
+
+        let mut identifier = identifier.clone();
+        while is_taken(&identifier) {
+            identifier.push('_');
+        }
+        deduplicated.push(identifier);
+    }
+
+    deduplicated
+}
+
+#[derive(Deserialize)]
+struct ServiceConfig {
+    modules_dir: Option<PathBuf>,
+    #[serde(default)]
+    module: Vec<ServiceModuleConfig>,
+}
+
+#[derive(Deserialize)]
+struct ServiceModuleConfig {
+    name: String,
+    load_from: Option<PathBuf>,
+    file_name: Option<String>,
+}
+
+/// Returns a module name and a path to its Wasm file. For a service config it's the facade module,
+/// which is the last one, paths are resolved the same way as Marine does it.
+fn resolve_module(input_path: &Path) -> Result<(String, PathBuf), anyhow::Error> {
+    if input_path
+        .extension()
+        .map_or(true, |extension| extension != "toml")
+    {
+        let module_name = input_path
+            .file_stem()
+            .ok_or_else(|| anyhow::Error::msg("provided path isn't a path to a file"))?
+            .to_string_lossy()
+            .into_owned();
+
+        return Ok((module_name, input_path.to_path_buf()));
+    }
+
+    let config = std::fs::read_to_string(input_path)?;
+    let config: ServiceConfig = toml::from_str(&config)?;
+    let base_path = input_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let facade = config
+        .module
+        .into_iter()
+        .last()
+        .ok_or_else(|| anyhow::Error::msg("service config doesn't contain any modules"))?;
+    let file_name = facade
+        .file_name
+        .unwrap_or_else(|| format!("{}.wasm", facade.name));
+
+    let wasm_path = match (facade.load_from, config.modules_dir) {
+        (Some(load_from), _) => {
+            let load_from = base_path.join(load_from);
+            if load_from.is_file() {
+                load_from
+            } else {
+                load_from.join(file_name)
+            }
+        }
+        (None, Some(modules_dir)) => base_path.join(modules_dir).join(file_name),
+        (None, None) => {
+            return Err(anyhow::Error::msg(format!(
+                r#""modules_dir" field is not defined, but it is required to load module "{}""#,
+                facade.name
+            )))
+        }
+    };
+
+    Ok((facade.name, wasm_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rust;
+    use super::typescript;
+    use super::ClientDescription;
+
+    use marine_module_interface::it_interface::IFunctionSignature;
+    use marine_module_interface::it_interface::IModuleInterface;
+    use marine_module_interface::it_interface::IRecordTypes;
+    use wasmer_it::ast::FunctionArg as IFunctionArg;
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::IRecordType;
+    use wasmer_it::IType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    fn record(name: &str, fields: Vec<(&str, IType)>) -> Arc<IRecordType> {
+        let fields = fields
+            .into_iter()
+            .map(|(name, ty)| IRecordFieldType {
+                name: name.to_string(),
+                ty,
+            })
+            .collect();
+
+        Arc::new(IRecordType {
+            name: name.to_string(),
+            fields: NEVec::new(fields).unwrap(),
+        })
+    }
+
+    fn function(
+        name: &str,
+        arguments: Vec<(&str, IType)>,
+        outputs: Vec<IType>,
+    ) -> IFunctionSignature {
+        let arguments = arguments
+            .into_iter()
+            .map(|(name, ty)| IFunctionArg {
+                name: name.to_string(),
+                ty,
+            })
+            .collect();
+
+        IFunctionSignature {
+            name: Arc::new(name.to_string()),
+            arguments: Arc::new(arguments),
+            outputs: Arc::new(outputs),
+            adapter_function_type: 0,
+        }
+    }
+
+    /// An interface with keyword named fields and functions, a record declared before
+    /// the record it refers to, multi-value outputs, byte arrays and names colliding
+    /// with identifiers of the generated clients.
+    fn fixture() -> ClientDescription {
+        let record_types = IRecordTypes::from([
+            (
+                0,
+                record(
+                    "Batch",
+                    vec![
+                        ("self", IType::Record(1)),
+                        ("items", IType::Array(Box::new(IType::Record(1)))),
+                    ],
+                ),
+            ),
+            (
+                1,
+                record(
+                    "Item",
+                    vec![("type", IType::U64), ("data", IType::ByteArray)],
+                ),
+            ),
+        ]);
+
+        let function_signatures = vec![
+            function(
+                "match",
+                vec![("in", IType::Record(0))],
+                vec![IType::Record(1)],
+            ),
+            function(
+                "checksum",
+                vec![("data", IType::ByteArray)],
+                vec![IType::U64, IType::String],
+            ),
+            function("delete", vec![], vec![]),
+            // collide with the constructors and the call parameters of the clients
+            function(
+                "new",
+                vec![
+                    ("call_parameters", IType::String),
+                    ("callParameters", IType::Boolean),
+                ],
+                vec![],
+            ),
+            function("constructor", vec![("args", IType::String)], vec![]),
+        ];
+
+        let module_interface = IModuleInterface {
+            export_record_types: record_types.clone(),
+            record_types,
+            function_signatures,
+        };
+
+        ClientDescription::new(
+            String::from("Storage"),
+            String::from("storage"),
+            module_interface,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn typescript_client() {
+        let code = typescript::generate(&fixture());
+        assert_eq!(code, include_str!("golden/client.ts"), "{}", code);
+    }
+
+    #[test]
+    fn rust_client() {
+        let code = rust::generate(&fixture());
+        assert_eq!(code, include_str!("golden/client.rs.golden"), "{}", code);
+    }
+}
@@ -0,0 +1,255 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::deduplicate_identifiers;
+use super::ClientDescription;
+
+use marine_module_interface::it_interface::IFunctionSignature;
+use wasmer_it::IType;
+
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// Keywords that can't be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+const CLIENT_ERROR: &str = r#"#[derive(Debug)]
+pub enum ClientError {
+    Service(AppServiceError),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Service(err) => write!(f, "{}", err),
+            ClientError::Json(err) => write!(f, "invalid service result: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<AppServiceError> for ClientError {
+    fn from(err: AppServiceError) -> Self {
+        ClientError::Service(err)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(err: serde_json::Error) -> Self {
+        ClientError::Json(err)
+    }
+}
+"#;
+
+/// Generates serde structs for records and a client which calls functions
+/// through `AppService::call_async`.
+pub(super) fn generate(client: &ClientDescription) -> String {
+    let mut code = String::new();
+    // writing to a String never fails
+    generate_code(client, &mut code).unwrap();
+    code
+}
+
+fn generate_code(client: &ClientDescription, code: &mut String) -> std::fmt::Result {
+    writeln!(
+        code,
+        "// Generated by `marine generate-client`, do not edit."
+    )?;
+    writeln!(code)?;
+    writeln!(code, "use fluence_app_service::AppService;")?;
+    writeln!(code, "use fluence_app_service::AppServiceError;")?;
+    writeln!(code, "use fluence_app_service::CallParameters;")?;
+    if !client.records.is_empty() {
+        writeln!(code)?;
+        writeln!(code, "use serde::Deserialize;")?;
+        writeln!(code, "use serde::Serialize;")?;
+    }
+
+    for record in client.records.iter() {
+        writeln!(code)?;
+        writeln!(
+            code,
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]"
+        )?;
+        writeln!(code, "pub struct {} {{", record.name)?;
+        for field in record.fields.iter() {
+            let field_name = identifier(&field.name);
+            if NON_RAW_KEYWORDS.contains(&field.name.as_str()) {
+                writeln!(code, "    #[serde(rename = {:?})]", field.name)?;
+            }
+            writeln!(
+                code,
+                "    pub {}: {},",
+                field_name,
+                type_name(&field.ty, client)
+            )?;
+        }
+        writeln!(code, "}}")?;
+    }
+
+    writeln!(code)?;
+    write!(code, "{}", CLIENT_ERROR)?;
+
+    let client_name = format!("{}Client", client.service_name);
+    writeln!(code)?;
+    writeln!(code, "pub struct {}<'s> {{", client_name)?;
+    writeln!(code, "    service: &'s mut AppService,")?;
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+    writeln!(code, "impl<'s> {}<'s> {{", client_name)?;
+    writeln!(
+        code,
+        "    pub fn new(service: &'s mut AppService) -> Self {{"
+    )?;
+    writeln!(code, "        Self {{ service }}")?;
+    writeln!(code, "    }}")?;
+
+    let method_names = client
+        .functions
+        .iter()
+        .map(|function| identifier(&function.name))
+        .collect();
+    let method_names = deduplicate_identifiers(method_names, &["new"]);
+    for (function, method_name) in client.functions.iter().zip(method_names) {
+        writeln!(code)?;
+        generate_function(function, &method_name, client, code)?;
+    }
+
+    writeln!(code, "}}")
+}
+
+fn generate_function(
+    function: &IFunctionSignature,
+    method_name: &str,
+    client: &ClientDescription,
+    code: &mut String,
+) -> std::fmt::Result {
+    let argument_names = function
+        .arguments
+        .iter()
+        .map(|arg| identifier(&arg.name))
+        .collect();
+    let argument_names = deduplicate_identifiers(argument_names, &["call_parameters"]);
+
+    let mut parameters = vec![String::from("&mut self")];
+    parameters.extend(
+        function
+            .arguments
+            .iter()
+            .zip(argument_names.iter())
+            .map(|(arg, name)| format!("{}: {}", name, type_name(&arg.ty, client))),
+    );
+    parameters.push(String::from("call_parameters: CallParameters"));
+
+    let arguments = function
+        .arguments
+        .iter()
+        .zip(argument_names.iter())
+        .map(|(arg, name)| format!("{:?}: {}", arg.name, name))
+        .collect::<Vec<_>>();
+
+    writeln!(code, "    pub async fn {}(", method_name)?;
+    for parameter in parameters {
+        writeln!(code, "        {},", parameter)?;
+    }
+    writeln!(
+        code,
+        "    ) -> Result<{}, ClientError> {{",
+        output_type_name(&function.outputs, client)
+    )?;
+    writeln!(
+        code,
+        "        let arguments = serde_json::json!({});",
+        json_object(&arguments)
+    )?;
+    let call = format!(
+        "self\n            .service\n            .call_async({:?}, arguments, call_parameters)\n            .await?",
+        function.name.as_str()
+    );
+    if function.outputs.is_empty() {
+        writeln!(code, "        {};", call)?;
+        writeln!(code, "        Ok(())")?;
+    } else {
+        writeln!(code, "        let result = {};", call)?;
+        writeln!(code, "        Ok(serde_json::from_value(result)?)")?;
+    }
+    writeln!(code, "    }}")
+}
+
+fn output_type_name(outputs: &[IType], client: &ClientDescription) -> String {
+    match outputs {
+        [] => String::from("()"),
+        [output] => type_name(output, client),
+        // multi-values are represented as an array, which is deserialized into a tuple
+        outputs => {
+            let types = outputs
+                .iter()
+                .map(|ty| type_name(ty, client))
+                .collect::<Vec<_>>();
+            format!("({})", types.join(", "))
+        }
+    }
+}
+
+fn type_name(ty: &IType, client: &ClientDescription) -> String {
+    match ty {
+        IType::Boolean => String::from("bool"),
+        IType::S8 => String::from("i8"),
+        IType::S16 => String::from("i16"),
+        IType::S32 | IType::I32 => String::from("i32"),
+        IType::S64 | IType::I64 => String::from("i64"),
+        IType::U8 => String::from("u8"),
+        IType::U16 => String::from("u16"),
+        IType::U32 => String::from("u32"),
+        IType::U64 => String::from("u64"),
+        IType::F32 => String::from("f32"),
+        IType::F64 => String::from("f64"),
+        IType::String => String::from("String"),
+        IType::ByteArray => String::from("Vec<u8>"),
+        IType::Array(ty) => format!("Vec<{}>", type_name(ty, client)),
+        IType::Record(record_id) => client.record_name(*record_id).to_string(),
+    }
+}
+
+fn json_object(fields: &[String]) -> String {
+    if fields.is_empty() {
+        String::from("{}")
+    } else {
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
+
+fn identifier(name: &str) -> String {
+    if NON_RAW_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
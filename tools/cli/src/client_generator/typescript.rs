@@ -0,0 +1,272 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::deduplicate_identifiers;
+use super::ClientDescription;
+
+use marine_module_interface::it_interface::IFunctionSignature;
+use wasmer_it::IType;
+
+use std::fmt::Write;
+
+const RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "await",
+    "arguments",
+    "eval",
+];
+
+const DEFAULT_CALL_PARAMETERS: &str = r#"export const defaultCallParameters = {
+  particle: {
+    id: "",
+    init_peer_id: "",
+    timestamp: 0,
+    ttl: 0,
+    script: "",
+    signature: [],
+    token: "",
+  },
+  service_id: "",
+  service_creator_peer_id: "",
+  host_id: "",
+  worker_id: "",
+  tetraplets: [],
+};
+"#;
+
+/// Generates TypeScript interfaces for records and a client class which calls functions
+/// through marine-js `call_module`.
+pub(super) fn generate(client: &ClientDescription) -> String {
+    let mut code = String::new();
+    // writing to a String never fails
+    generate_code(client, &mut code).unwrap();
+    code
+}
+
+fn generate_code(client: &ClientDescription, code: &mut String) -> std::fmt::Result {
+    writeln!(
+        code,
+        "// Generated by `marine generate-client`, do not edit."
+    )?;
+    writeln!(code)?;
+    writeln!(
+        code,
+        "// Compatible with marine-js `call_module`: takes JSON-encoded arguments\n\
+         // and returns a JSON-encoded result."
+    )?;
+    writeln!(code, "export type CallModule = (")?;
+    writeln!(code, "  moduleName: string,")?;
+    writeln!(code, "  functionName: string,")?;
+    writeln!(code, "  args: string,")?;
+    writeln!(code, "  callParameters: unknown,")?;
+    writeln!(code, ") => string | Promise<string>;")?;
+    writeln!(code)?;
+    writeln!(
+        code,
+        "// Used when call parameters aren't passed, marine-js can't call a module without them."
+    )?;
+    write!(code, "{}", DEFAULT_CALL_PARAMETERS)?;
+    writeln!(code)?;
+    writeln!(
+        code,
+        "// 64-bit integers are passed through JSON as numbers,\n\
+         // so their values beyond Number.MAX_SAFE_INTEGER lose precision."
+    )?;
+
+    for record in client.records.iter() {
+        writeln!(code)?;
+        writeln!(code, "export interface {} {{", record.name)?;
+        for field in record.fields.iter() {
+            writeln!(code, "  {}: {};", field.name, type_name(&field.ty, client))?;
+        }
+        writeln!(code, "}}")?;
+    }
+
+    writeln!(code)?;
+    writeln!(code, "export class {}Client {{", client.service_name)?;
+    writeln!(code, "  constructor(")?;
+    writeln!(code, "    private readonly callModule: CallModule,")?;
+    writeln!(
+        code,
+        "    private readonly moduleName: string = {:?},",
+        client.module_name
+    )?;
+    writeln!(code, "  ) {{}}")?;
+
+    let method_names = client
+        .functions
+        .iter()
+        .map(|function| function.name.to_string())
+        .collect();
+    let method_names =
+        deduplicate_identifiers(method_names, &["constructor", "callModule", "moduleName"]);
+    for (function, method_name) in client.functions.iter().zip(method_names) {
+        writeln!(code)?;
+        generate_function(function, &method_name, client, code)?;
+    }
+
+    writeln!(code, "}}")
+}
+
+fn generate_function(
+    function: &IFunctionSignature,
+    method_name: &str,
+    client: &ClientDescription,
+    code: &mut String,
+) -> std::fmt::Result {
+    // locals of the method can't have the same names as its parameters
+    let argument_names = function
+        .arguments
+        .iter()
+        .map(|arg| identifier(&arg.name))
+        .collect();
+    let argument_names =
+        deduplicate_identifiers(argument_names, &["callParameters", "args", "result"]);
+
+    let mut parameters = function
+        .arguments
+        .iter()
+        .zip(argument_names.iter())
+        .map(|(arg, name)| format!("{}: {}", name, type_name(&arg.ty, client)))
+        .collect::<Vec<_>>();
+    parameters.push(String::from(
+        "callParameters: unknown = defaultCallParameters",
+    ));
+
+    let arguments = function
+        .arguments
+        .iter()
+        .zip(argument_names.iter())
+        .map(|(arg, name)| format!("{:?}: {}", arg.name, name))
+        .collect::<Vec<_>>();
+
+    writeln!(
+        code,
+        "  async {}({}): Promise<{}> {{",
+        method_name,
+        parameters.join(", "),
+        output_type_name(&function.outputs, client)
+    )?;
+    let arguments = if arguments.is_empty() {
+        String::from("{}")
+    } else {
+        format!("{{ {} }}", arguments.join(", "))
+    };
+    writeln!(code, "    const args = JSON.stringify({});", arguments)?;
+    let call = format!(
+        "await this.callModule(this.moduleName, {:?}, args, callParameters)",
+        function.name.as_str()
+    );
+    if function.outputs.is_empty() {
+        writeln!(code, "    {};", call)?;
+    } else {
+        writeln!(code, "    const result = {};", call)?;
+        writeln!(code, "    return JSON.parse(result);")?;
+    }
+    writeln!(code, "  }}")
+}
+
+fn output_type_name(outputs: &[IType], client: &ClientDescription) -> String {
+    match outputs {
+        [] => String::from("void"),
+        [output] => type_name(output, client),
+        // multi-values are represented as an array
+        outputs => {
+            let types = outputs
+                .iter()
+                .map(|ty| type_name(ty, client))
+                .collect::<Vec<_>>();
+            format!("[{}]", types.join(", "))
+        }
+    }
+}
+
+fn type_name(ty: &IType, client: &ClientDescription) -> String {
+    match ty {
+        IType::Boolean => String::from("boolean"),
+        IType::S8
+        | IType::S16
+        | IType::S32
+        | IType::S64
+        | IType::U8
+        | IType::U16
+        | IType::U32
+        | IType::U64
+        | IType::F32
+        | IType::F64
+        | IType::I32
+        | IType::I64 => String::from("number"),
+        IType::String => String::from("string"),
+        IType::ByteArray => String::from("number[]"),
+        IType::Array(ty) => format!("{}[]", type_name(ty, client)),
+        IType::Record(record_id) => client.record_name(*record_id).to_string(),
+    }
+}
+
+fn identifier(name: &str) -> String {
+    if RESERVED_WORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use marine_module_info_parser::sdk_version;
+use marine_module_info_parser::ModuleInfoError;
+use marine_module_interface::interface_diff::diff_interfaces;
+
+use semver::Version;
+
+use std::path::Path;
+
+/// Exit code used when the new module version breaks existing callers.
+pub(crate) const BREAKING_CHANGES_EXIT_CODE: i32 = 2;
+
+/// Prints changes between two versions of a module, returns true if some of them are breaking.
+pub(crate) fn diff(old_wasm_path: &Path, new_wasm_path: &Path) -> Result<bool, anyhow::Error> {
+    let old_interface = marine_it_parser::module_it_interface(old_wasm_path)?;
+    let new_interface = marine_it_parser::module_it_interface(new_wasm_path)?;
+
+    let mut changes = diff_interfaces(&old_interface, &new_interface)?
+        .into_iter()
+        .map(|change| (change.is_breaking(), change.to_string()))
+        .collect::<Vec<_>>();
+
+    let old_module = walrus::ModuleConfig::new().parse_file(old_wasm_path)?;
+    let new_module = walrus::ModuleConfig::new().parse_file(new_wasm_path)?;
+
+    let old_it_version = marine_it_parser::extract_version_from_module(&old_module)?;
+    let new_it_version = marine_it_parser::extract_version_from_module(&new_module)?;
+    changes.extend(version_change(
+        "IT",
+        Some(old_it_version),
+        Some(new_it_version),
+    ));
+
+    let old_sdk_version = optional_sdk_version(&old_module)?;
+    let new_sdk_version = optional_sdk_version(&new_module)?;
+    changes.extend(version_change("sdk", old_sdk_version, new_sdk_version));
+
+    for (is_breaking, change) in changes.iter() {
+        let severity = if *is_breaking {
+            "breaking"
+        } else {
+            "compatible"
+        };
+        println!("{:<11} {}", format!("{}:", severity), change);
+    }
+
+    let breaking_count = changes
+        .iter()
+        .filter(|(is_breaking, _)| *is_breaking)
+        .count();
+    if changes.is_empty() {
+        println!("modules have the same interface");
+    } else {
+        println!(
+            "\n{} changes found, {} breaking",
+            changes.len(),
+            breaking_count
+        );
+    }
+
+    Ok(breaking_count != 0)
+}
+
+fn optional_sdk_version(module: &walrus::Module) -> Result<Option<Version>, anyhow::Error> {
+    match sdk_version::extract_from_module(module) {
+        Ok(version) => Ok(Some(version)),
+        Err(ModuleInfoError::NoCustomSection(_)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Versions are compatible by the semver rules used by Cargo: the first non-zero component
+/// can't change.
+fn version_change(
+    name: &str,
+    old_version: Option<Version>,
+    new_version: Option<Version>,
+) -> Option<(bool, String)> {
+    let view = |version: &Option<Version>| match version {
+        Some(version) => version.to_string(),
+        None => String::from("none"),
+    };
+
+    if old_version == new_version {
+        return None;
+    }
+
+    let is_breaking = match (&old_version, &new_version) {
+        (Some(old_version), Some(new_version)) => {
+            let compatible_part = |version: &Version| match (version.major, version.minor) {
+                (0, 0) => (0, 0, version.patch),
+                (0, minor) => (0, minor, 0),
+                (major, _) => (major, 0, 0),
+            };
+            compatible_part(old_version) != compatible_part(new_version)
+        }
+        // a module without an sdk version isn't comparable with another one
+        _ => true,
+    };
+
+    let change = format!(
+        "{} version changed from {} to {}",
+        name,
+        view(&old_version),
+        view(&new_version)
+    );
+    Some((is_breaking, change))
+}
@@ -0,0 +1,657 @@
+/*
+ * Marine WebAssembly runtime
+ *
+ * Copyright (C) 2024 Fluence DAO
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation version 3 of the
+ * License.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Structured output of `marine info`, `marine it`, `marine aqua`, `marine effects`
+//! and `marine bundle inspect`.
+//!
+//! These types are decoupled from the library ones on purpose: they define the JSON schema
+//! consumed by external tooling, so fields could only be added here, not renamed or removed.
+
+use marine_module_info_parser::effects::WasmEffect;
+use marine_module_info_parser::log_targets::LogTargets;
+use marine_module_info_parser::manifest::ModuleManifest;
+use marine_module_interface::interface::FunctionSignature;
+use marine_module_interface::interface::ModuleInterface;
+use marine_module_interface::interface::RecordType;
+use marine_module_interface::it_interface::IFunctionSignature;
+use marine_module_interface::it_interface::IModuleInterface;
+use marine_module_interface::it_interface::IRecordTypes;
+use marine_service_bundle::ServiceBundle;
+use wasmer_it::IRecordType;
+use wasmer_it::IType;
+
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Version of the JSON schema, bumped on incompatible changes.
+const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow::Error::msg(format!(
+                "unsupported output format {}, expected text or json",
+                format
+            ))),
+        }
+    }
+}
+
+/// Output of `marine info`.
+#[derive(Debug, Serialize)]
+pub(crate) struct ModuleInfoOutput {
+    schema_version: u32,
+    it_version: String,
+    sdk_version: Option<String>,
+    manifest: Option<ManifestOutput>,
+    effects: Vec<EffectOutput>,
+    log_targets: LogTargets,
+    interface: InterfaceOutput,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestOutput {
+    authors: String,
+    version: String,
+    description: String,
+    repository: String,
+    /// RFC 3339 timestamp.
+    build_time: String,
+    license: Option<String>,
+    homepage: Option<String>,
+    keywords: Vec<String>,
+    custom: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum EffectOutput {
+    Logger,
+    MountedBinary { name: String },
+    CallParameters,
+    Filesystem { function: String },
+    Stdio { function: String },
+    EnvironmentRead { function: String },
+    Clock { function: String },
+    Randomness,
+    ProcExit,
+    Wasi { function: String },
+    ModuleImport { module: String, function: String },
+}
+
+/// Output of `marine effects`.
+#[derive(Debug, Serialize)]
+pub(crate) struct EffectsOutput {
+    schema_version: u32,
+    effects: Vec<EffectOutput>,
+}
+
+/// Output of `marine bundle inspect`.
+#[derive(Debug, Serialize)]
+pub(crate) struct BundleOutput {
+    schema_version: u32,
+    bundle_version: u32,
+    config_hash: String,
+    modules: Vec<BundleFileOutput>,
+    data: Vec<BundleFileOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct BundleFileOutput {
+    /// Module name for modules and a path inside the bundle for data files.
+    name: String,
+    hash: String,
+    size: usize,
+}
+
+/// Output of `marine it`.
+#[derive(Debug, Serialize)]
+pub(crate) struct ITOutput {
+    schema_version: u32,
+    it_version: String,
+    /// Interface types in the text format, the same as printed by `marine it`.
+    it: String,
+    interface: IModuleInterfaceOutput,
+}
+
+/// `IModuleInterface` with records sorted by id to make the output deterministic.
+#[derive(Debug, Serialize)]
+struct IModuleInterfaceOutput {
+    export_record_types: BTreeMap<u64, IRecordTypeOutput>,
+    record_types: BTreeMap<u64, IRecordTypeOutput>,
+    function_signatures: Vec<IFunctionSignatureOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct IRecordTypeOutput {
+    name: String,
+    fields: Vec<IFieldOutput>,
+}
+
+/// A record field or a function argument.
+#[derive(Debug, Serialize)]
+struct IFieldOutput {
+    name: String,
+    ty: ITypeOutput,
+}
+
+#[derive(Debug, Serialize)]
+struct IFunctionSignatureOutput {
+    name: String,
+    arguments: Vec<IFieldOutput>,
+    outputs: Vec<ITypeOutput>,
+    adapter_function_type: u32,
+}
+
+#[derive(Debug, Serialize)]
+enum ITypeOutput {
+    Boolean,
+    S8,
+    S16,
+    S32,
+    S64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    String,
+    ByteArray,
+    Array(Box<ITypeOutput>),
+    I32,
+    I64,
+    /// Id of a record type.
+    Record(u64),
+}
+
+/// Output of `marine aqua`.
+#[derive(Debug, Serialize)]
+pub(crate) struct AquaOutput {
+    schema_version: u32,
+    service_name: String,
+    service_id: Option<String>,
+    interface: InterfaceOutput,
+}
+
+/// Module interface with types in the text form, records are in the topological order.
+#[derive(Debug, Serialize)]
+struct InterfaceOutput {
+    function_signatures: Vec<FunctionSignatureOutput>,
+    record_types: Vec<RecordTypeOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionSignatureOutput {
+    name: String,
+    /// Pairs of an argument name and its type.
+    arguments: Vec<(String, String)>,
+    output_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RecordTypeOutput {
+    name: String,
+    id: u64,
+    fields: Vec<RecordFieldOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct RecordFieldOutput {
+    name: String,
+    ty: String,
+}
+
+impl ModuleInfoOutput {
+    pub(crate) fn new(
+        it_version: &semver::Version,
+        sdk_version: Option<&semver::Version>,
+        manifest: Option<&ModuleManifest>,
+        effects: &[WasmEffect],
+        log_targets: LogTargets,
+        interface: ModuleInterface,
+    ) -> Self {
+        let manifest = manifest.map(|manifest| ManifestOutput {
+            authors: manifest.authors.clone(),
+            version: manifest.version.to_string(),
+            description: manifest.description.clone(),
+            repository: manifest.repository.clone(),
+            build_time: manifest.build_time.to_rfc3339(),
+            license: manifest.license.clone(),
+            homepage: manifest.homepage.clone(),
+            keywords: manifest.keywords.clone(),
+            custom: manifest.custom.clone(),
+        });
+
+        let effects = effects.iter().map(EffectOutput::from).collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            it_version: it_version.to_string(),
+            sdk_version: sdk_version.map(ToString::to_string),
+            manifest,
+            effects,
+            log_targets,
+            interface: InterfaceOutput::from(interface),
+        }
+    }
+}
+
+impl EffectsOutput {
+    pub(crate) fn new(effects: &[WasmEffect]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            effects: effects.iter().map(EffectOutput::from).collect(),
+        }
+    }
+}
+
+impl BundleOutput {
+    pub(crate) fn new(bundle: &ServiceBundle) -> Self {
+        let manifest = bundle.manifest();
+        let modules = manifest
+            .modules
+            .iter()
+            .map(|module| BundleFileOutput {
+                name: module.name.clone(),
+                hash: module.file.hash.clone(),
+                size: bundle.modules().get(&module.name).map_or(0, Vec::len),
+            })
+            .collect();
+        let data = manifest
+            .data
+            .iter()
+            .map(|file| BundleFileOutput {
+                name: file.path.clone(),
+                hash: file.hash.clone(),
+                size: bundle.data_file(file).map_or(0, <[u8]>::len),
+            })
+            .collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            bundle_version: manifest.version,
+            config_hash: manifest.config.hash.clone(),
+            modules,
+            data,
+        }
+    }
+}
+
+impl From<&WasmEffect> for EffectOutput {
+    fn from(effect: &WasmEffect) -> Self {
+        match effect {
+            WasmEffect::Logger => Self::Logger,
+            WasmEffect::MountedBinary(name) => Self::MountedBinary { name: name.clone() },
+            WasmEffect::CallParameters => Self::CallParameters,
+            WasmEffect::Filesystem(function) => Self::Filesystem {
+                function: function.clone(),
+            },
+            WasmEffect::Stdio(function) => Self::Stdio {
+                function: function.clone(),
+            },
+            WasmEffect::EnvironmentRead(function) => Self::EnvironmentRead {
+                function: function.clone(),
+            },
+            WasmEffect::Clock(function) => Self::Clock {
+                function: function.clone(),
+            },
+            WasmEffect::Randomness => Self::Randomness,
+            WasmEffect::ProcExit => Self::ProcExit,
+            WasmEffect::Wasi(function) => Self::Wasi {
+                function: function.clone(),
+            },
+            WasmEffect::ModuleImport { module, function } => Self::ModuleImport {
+                module: module.clone(),
+                function: function.clone(),
+            },
+        }
+    }
+}
+
+impl ITOutput {
+    pub(crate) fn new(
+        it_version: &semver::Version,
+        it: String,
+        interface: IModuleInterface,
+    ) -> Self {
+        let mut function_signatures = interface
+            .function_signatures
+            .iter()
+            .map(IFunctionSignatureOutput::from)
+            .collect::<Vec<_>>();
+        function_signatures.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+        let records = |record_types: IRecordTypes| {
+            record_types
+                .iter()
+                .map(|(id, record)| (*id, IRecordTypeOutput::from(record.as_ref())))
+                .collect()
+        };
+        let interface = IModuleInterfaceOutput {
+            export_record_types: records(interface.export_record_types),
+            record_types: records(interface.record_types),
+            function_signatures,
+        };
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            it_version: it_version.to_string(),
+            it,
+            interface,
+        }
+    }
+}
+
+impl AquaOutput {
+    pub(crate) fn new(
+        service_name: String,
+        service_id: Option<String>,
+        interface: ModuleInterface,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            service_name,
+            service_id,
+            interface: InterfaceOutput::from(interface),
+        }
+    }
+}
+
+impl From<ModuleInterface> for InterfaceOutput {
+    /// Sorts functions by name, records are left in the topological order.
+    fn from(mut interface: ModuleInterface) -> Self {
+        interface.function_signatures.sort();
+
+        Self {
+            function_signatures: interface
+                .function_signatures
+                .into_iter()
+                .map(FunctionSignatureOutput::from)
+                .collect(),
+            record_types: interface
+                .record_types
+                .into_iter()
+                .map(RecordTypeOutput::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<FunctionSignature> for FunctionSignatureOutput {
+    fn from(signature: FunctionSignature) -> Self {
+        Self {
+            name: signature.name,
+            arguments: signature.arguments,
+            output_types: signature.output_types,
+        }
+    }
+}
+
+impl From<RecordType> for RecordTypeOutput {
+    fn from(record: RecordType) -> Self {
+        let fields = record
+            .fields
+            .into_iter()
+            .map(|field| RecordFieldOutput {
+                name: field.name,
+                ty: field.ty,
+            })
+            .collect();
+
+        Self {
+            name: record.name,
+            id: record.id,
+            fields,
+        }
+    }
+}
+
+impl From<&IRecordType> for IRecordTypeOutput {
+    fn from(record: &IRecordType) -> Self {
+        let fields = record
+            .fields
+            .iter()
+            .map(|field| IFieldOutput {
+                name: field.name.clone(),
+                ty: ITypeOutput::from(&field.ty),
+            })
+            .collect();
+
+        Self {
+            name: record.name.clone(),
+            fields,
+        }
+    }
+}
+
+impl From<&IFunctionSignature> for IFunctionSignatureOutput {
+    fn from(signature: &IFunctionSignature) -> Self {
+        let arguments = signature
+            .arguments
+            .iter()
+            .map(|arg| IFieldOutput {
+                name: arg.name.clone(),
+                ty: ITypeOutput::from(&arg.ty),
+            })
+            .collect();
+
+        Self {
+            name: signature.name.to_string(),
+            arguments,
+            outputs: signature.outputs.iter().map(ITypeOutput::from).collect(),
+            adapter_function_type: signature.adapter_function_type,
+        }
+    }
+}
+
+impl From<&IType> for ITypeOutput {
+    fn from(ty: &IType) -> Self {
+        match ty {
+            IType::Boolean => Self::Boolean,
+            IType::S8 => Self::S8,
+            IType::S16 => Self::S16,
+            IType::S32 => Self::S32,
+            IType::S64 => Self::S64,
+            IType::U8 => Self::U8,
+            IType::U16 => Self::U16,
+            IType::U32 => Self::U32,
+            IType::U64 => Self::U64,
+            IType::F32 => Self::F32,
+            IType::F64 => Self::F64,
+            IType::String => Self::String,
+            IType::ByteArray => Self::ByteArray,
+            IType::Array(ty) => Self::Array(Box::new(Self::from(ty.as_ref()))),
+            IType::I32 => Self::I32,
+            IType::I64 => Self::I64,
+            IType::Record(id) => Self::Record(*id),
+        }
+    }
+}
+
+pub(crate) fn print_json<T: Serialize>(output: &T) -> Result<(), anyhow::Error> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use marine_module_info_parser::manifest::ModuleManifest;
+    use marine_module_interface::interface::it_to_module_interface;
+    use marine_module_interface::it_interface::IFunctionSignature;
+    use wasmer_it::ast::FunctionArg as IFunctionArg;
+    use wasmer_it::IRecordFieldType;
+    use wasmer_it::NEVec;
+
+    use std::sync::Arc;
+
+    fn it_interface() -> IModuleInterface {
+        let record = IRecordType {
+            name: String::from("Blob"),
+            fields: NEVec::new(vec![
+                IRecordFieldType {
+                    name: String::from("id"),
+                    ty: IType::U64,
+                },
+                IRecordFieldType {
+                    name: String::from("chunks"),
+                    ty: IType::Array(Box::new(IType::ByteArray)),
+                },
+            ])
+            .unwrap(),
+        };
+        let record_types = IRecordTypes::from([(0, Arc::new(record))]);
+
+        let function_signatures = vec![
+            IFunctionSignature {
+                name: Arc::new(String::from("store")),
+                arguments: Arc::new(vec![IFunctionArg {
+                    name: String::from("blob"),
+                    ty: IType::Record(0),
+                }]),
+                outputs: Arc::new(vec![IType::Boolean]),
+                adapter_function_type: 1,
+            },
+            IFunctionSignature {
+                name: Arc::new(String::from("load")),
+                arguments: Arc::new(vec![IFunctionArg {
+                    name: String::from("id"),
+                    ty: IType::U64,
+                }]),
+                outputs: Arc::new(vec![IType::Record(0)]),
+                adapter_function_type: 0,
+            },
+        ];
+
+        IModuleInterface {
+            export_record_types: record_types.clone(),
+            record_types,
+            function_signatures,
+        }
+    }
+
+    fn effects() -> Vec<WasmEffect> {
+        vec![
+            WasmEffect::Logger,
+            WasmEffect::MountedBinary(String::from("curl")),
+            WasmEffect::Clock(String::from("clock_time_get")),
+            WasmEffect::ModuleImport {
+                module: String::from("sqlite"),
+                function: String::from("exec"),
+            },
+        ]
+    }
+
+    fn check_golden<T: Serialize>(output: &T, golden: &str) {
+        let json = serde_json::to_string_pretty(output).unwrap();
+        assert_eq!(json, golden.trim_end(), "{}", json);
+    }
+
+    #[test]
+    fn info_schema() {
+        let manifest = ModuleManifest {
+            authors: String::from("Fluence DAO"),
+            version: semver::Version::new(0, 1, 0),
+            description: String::from("blob storage"),
+            repository: String::from("https://github.com/fluencelabs/marine"),
+            build_time: "2024-04-10T12:00:00+00:00".parse().unwrap(),
+            license: Some(String::from("AGPL-3.0-only")),
+            homepage: None,
+            keywords: vec![String::from("storage")],
+            custom: BTreeMap::from([(String::from("team"), String::from("marine"))]),
+        };
+        let log_targets = LogTargets::from([(String::from("storage"), 1)]);
+        let interface = it_to_module_interface(it_interface()).unwrap();
+
+        let output = ModuleInfoOutput::new(
+            &semver::Version::new(0, 26, 0),
+            Some(&semver::Version::new(0, 14, 0)),
+            Some(&manifest),
+            &effects(),
+            log_targets,
+            interface,
+        );
+        check_golden(&output, include_str!("golden/info.json"));
+    }
+
+    #[test]
+    fn effects_schema() {
+        let output = EffectsOutput::new(&effects());
+        check_golden(&output, include_str!("golden/effects.json"));
+    }
+
+    #[test]
+    fn it_schema() {
+        let output = ITOutput::new(
+            &semver::Version::new(0, 26, 0),
+            String::from("(@interface it_version \"0.26.0\")"),
+            it_interface(),
+        );
+        check_golden(&output, include_str!("golden/it.json"));
+    }
+
+    #[test]
+    fn aqua_schema() {
+        let interface = it_to_module_interface(it_interface()).unwrap();
+        let output = AquaOutput::new(
+            String::from("Storage"),
+            Some(String::from("storage")),
+            interface,
+        );
+        check_golden(&output, include_str!("golden/aqua.json"));
+    }
+
+    #[test]
+    fn bundle_schema() {
+        let dir = std::env::temp_dir().join(format!("marine-cli-bundle-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        std::fs::write(dir.join("storage.wasm"), b"\0asm\x01\0\0\0").unwrap();
+        std::fs::write(dir.join("data/seed.json"), b"{}").unwrap();
+        std::fs::write(
+            dir.join("Config.toml"),
+            r#"
+            modules_dir = "."
+            total_memory_limit = "Infinity"
+
+            [[module]]
+            name = "storage"
+            "#,
+        )
+        .unwrap();
+
+        let bundle = ServiceBundle::create(&dir.join("Config.toml"), Some(&dir.join("data")));
+        let output = BundleOutput::new(&bundle.unwrap());
+        check_golden(&output, include_str!("golden/bundle.json"));
+    }
+}
@@ -31,11 +31,21 @@
 use marine_module_info_parser::manifest;
 use marine_module_info_parser::ModuleInfoError;
 use marine_module_info_parser::sdk_version;
+use marine_module_info_parser::log_targets;
+use marine_module_info_parser::effects;
+use marine_module_info_parser::signature;
+
+use marine_service_bundle::ServiceBundle;
+
+use json_output::OutputFormat;
 
 mod args;
 mod build;
+mod client_generator;
+mod diff;
 mod errors;
 mod generate;
+mod json_output;
 mod utils;
 mod cargo_manifest;
 
@@ -52,6 +62,13 @@ pub fn main() -> Result<(), anyhow::Error> {
         .subcommand(args::set())
         .subcommand(args::show_manifest())
         .subcommand(args::show_wit())
+        .subcommand(args::effects())
+        .subcommand(args::schema())
+        .subcommand(args::generate_client())
+        .subcommand(args::diff())
+        .subcommand(args::sign())
+        .subcommand(args::bundle())
+        .subcommand(args::check_config())
         .subcommand(args::repl());
     let arg_matches = app.get_matches();
 
@@ -60,11 +77,39 @@ pub fn main() -> Result<(), anyhow::Error> {
             // avoid printing version
             return aqua(args);
         }
+        ("schema", Some(args)) => {
+            // avoid printing version into json output
+            return schema(args);
+        }
+        ("generate-client", Some(args)) => {
+            // avoid printing version into generated code
+            return generate_client(args);
+        }
+        ("diff", Some(args)) => {
+            // avoid printing version into output checked by CI
+            return diff(args);
+        }
+        ("it", Some(args)) if output_format(args)? == OutputFormat::Json => {
+            // avoid printing version into json output
+            return it(args);
+        }
+        ("info", Some(args)) if output_format(args)? == OutputFormat::Json => {
+            // avoid printing version into json output
+            return info(args);
+        }
+        ("effects", Some(args)) if output_format(args)? == OutputFormat::Json => {
+            // avoid printing version into json output
+            return effects(args);
+        }
         ("build", Some(args)) => build(args),
         ("generate", Some(args)) => generate(args),
         ("set", Some(args)) => set(args),
+        ("sign", Some(args)) => sign(args),
+        ("bundle", Some(args)) => bundle(args),
+        ("check-config", Some(args)) => check_config(args),
         ("it", Some(args)) => it(args),
         ("info", Some(args)) => info(args),
+        ("effects", Some(args)) => effects(args),
         ("repl", Some(args)) => repl(args),
         (c, _) => Err(crate::errors::CLIError::NoSuchCommand(c.to_string()).into()),
     }?;
@@ -107,6 +152,13 @@ fn aqua(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
     };
     let service_name = service_name.to_pascal_case();
 
+    if output_format(args)? == OutputFormat::Json {
+        let module_interface = marine_it_parser::module_interface(wasm_path)?;
+        let service_id = args.value_of(args::SERVICE_ID).map(ToString::to_string);
+        let output = json_output::AquaOutput::new(service_name, service_id, module_interface);
+        return json_output::print_json(&output);
+    }
+
     // this line allows exporting all the stuff from generated aqua module definition
     println!("aqua {} declares *\n", service_name);
 
@@ -185,11 +237,97 @@ fn set_version(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+fn sign(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let in_wasm_path = args.value_of(args::IN_WASM_PATH).unwrap();
+    let key_path = args.value_of(args::KEY_PATH).unwrap();
+    let out_wasm_path = match args.value_of(args::OUT_WASM_PATH) {
+        Some(path) => path,
+        None => in_wasm_path,
+    };
+
+    let key = std::fs::read_to_string(key_path)?;
+    let key: signature::SigningKey = key.parse()?;
+
+    let wasm_bytes = std::fs::read(in_wasm_path)?;
+    let signed_wasm_bytes = signature::sign(&wasm_bytes, &key)?;
+    std::fs::write(out_wasm_path, signed_wasm_bytes)?;
+
+    println!("the module was successfully signed by {}", key.signer());
+
+    Ok(())
+}
+
 fn it(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
     let wasm_path = args.value_of(args::IN_WASM_PATH).unwrap();
 
     let it = marine_it_parser::extract_text_it(wasm_path)?;
-    println!("{}", it);
+    if output_format(args)? == OutputFormat::Text {
+        println!("{}", it);
+        return Ok(());
+    }
+
+    let wasm_module = walrus::ModuleConfig::new().parse_file(wasm_path)?;
+    let it_version = marine_it_parser::extract_version_from_module(&wasm_module)?;
+    let module_interface = marine_it_parser::module_it_interface(wasm_path)?;
+
+    let output = json_output::ITOutput::new(&it_version, it, module_interface);
+    json_output::print_json(&output)
+}
+
+fn schema(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    use marine_module_interface::json_schema::module_json_schema;
+
+    let wasm_path = args.value_of(args::IN_WASM_PATH).unwrap();
+
+    let module_interface = marine_it_parser::module_it_interface(wasm_path)?;
+    let mut schemas = module_json_schema(&module_interface)?;
+    if let Some(function_name) = args.value_of(args::FUNCTION_NAME) {
+        schemas.retain(|schema| schema.name == function_name);
+        if schemas.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "module doesn't export function {}",
+                function_name
+            )));
+        }
+    }
+
+    println!("{}", serde_json::to_string_pretty(&schemas)?);
+
+    Ok(())
+}
+
+fn generate_client(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    use client_generator::ClientLanguage;
+
+    let input_path = args.value_of(args::IN_PATH).unwrap();
+    let language: ClientLanguage = args.value_of(args::CLIENT_LANGUAGE).unwrap().parse()?;
+    let service_name = args.value_of(args::SERVICE_NAME);
+
+    let client = client_generator::generate_client(
+        std::path::Path::new(input_path),
+        language,
+        service_name,
+    )?;
+
+    match args.value_of(args::OUT_PATH) {
+        Some(out_path) => std::fs::write(out_path, client)?,
+        None => print!("{}", client),
+    }
+
+    Ok(())
+}
+
+fn diff(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let old_wasm_path = args.value_of(args::OLD_WASM_PATH).unwrap();
+    let new_wasm_path = args.value_of(args::NEW_WASM_PATH).unwrap();
+
+    let has_breaking_changes = diff::diff(
+        std::path::Path::new(old_wasm_path),
+        std::path::Path::new(new_wasm_path),
+    )?;
+    if has_breaking_changes {
+        std::process::exit(diff::BREAKING_CHANGES_EXIT_CODE);
+    }
 
     Ok(())
 }
@@ -200,8 +338,26 @@ fn info(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
     let wasm_module = walrus::ModuleConfig::new().parse_file(wasm_path)?;
     let sdk_version = sdk_version::extract_from_module(&wasm_module);
     let module_manifest = manifest::extract_from_module(&wasm_module);
+    let log_targets = log_targets::extract_from_module(&wasm_module);
     let it_version = marine_it_parser::extract_version_from_module(&wasm_module)?;
 
+    if output_format(args)? == OutputFormat::Json {
+        let sdk_version = optional_section(sdk_version)?;
+        let module_manifest = optional_section(module_manifest)?;
+        let effects = effects::extract_from_module(&wasm_module)?;
+        let module_interface = marine_it_parser::module_interface(wasm_path)?;
+
+        let output = json_output::ModuleInfoOutput::new(
+            &it_version,
+            sdk_version.as_ref(),
+            module_manifest.as_ref(),
+            &effects,
+            log_targets?,
+            module_interface,
+        );
+        return json_output::print_json(&output);
+    }
+
     println!("it version:  {}", it_version);
     match sdk_version {
         Ok(sdk_version) => println!("sdk version: {}", sdk_version),
@@ -217,9 +373,136 @@ fn info(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
         Err(e) => return Err(e.into()),
     }
 
+    match log_targets {
+        Ok(log_targets) if log_targets.is_empty() => {
+            println!("module doesn't declare log targets")
+        }
+        Ok(log_targets) => {
+            let log_targets = log_targets
+                .iter()
+                .map(|(name, mask)| format!("{}={}", name, mask))
+                .collect::<Vec<_>>();
+            println!("log targets: {}", log_targets.join(", "));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    Ok(())
+}
+
+fn effects(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let wasm_path = args.value_of(args::IN_WASM_PATH).unwrap();
+    let effects = effects::extract_from_path(wasm_path)?;
+
+    if output_format(args)? == OutputFormat::Json {
+        return json_output::print_json(&json_output::EffectsOutput::new(&effects));
+    }
+
+    if effects.is_empty() {
+        println!("module doesn't have effects");
+    }
+    for effect in effects {
+        println!("{}", effect);
+    }
+
     Ok(())
 }
 
+fn check_config(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let config_path = args.value_of(args::IN_PATH).unwrap();
+
+    let errors = marine::check_config(config_path);
+    if errors.is_empty() {
+        println!("config is valid");
+        return Ok(());
+    }
+
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    Err(anyhow::Error::msg(format!(
+        "config has {} errors",
+        errors.len()
+    )))
+}
+
+fn bundle(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    match args.subcommand() {
+        ("create", Some(args)) => bundle_create(args),
+        ("inspect", Some(args)) => bundle_inspect(args),
+        ("verify", Some(args)) => bundle_verify(args),
+        (c, _) => Err(crate::errors::CLIError::NoSuchCommand(c.to_string()).into()),
+    }
+}
+
+fn bundle_create(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let config_path = std::path::Path::new(args.value_of(args::IN_PATH).unwrap());
+    let data_dir = args.value_of(args::DATA_DIR).map(std::path::Path::new);
+    let out_path = std::path::Path::new(args.value_of(args::OUT_PATH).unwrap());
+
+    let bundle = ServiceBundle::create(config_path, data_dir)?;
+    bundle.save(out_path)?;
+
+    println!(
+        "bundle with {} modules and {} data files was successfully created",
+        bundle.manifest().modules.len(),
+        bundle.manifest().data.len()
+    );
+
+    Ok(())
+}
+
+fn bundle_inspect(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let bundle_path = std::path::Path::new(args.value_of(args::BUNDLE_PATH).unwrap());
+    let bundle = ServiceBundle::load(bundle_path)?;
+
+    if output_format(args)? == OutputFormat::Json {
+        return json_output::print_json(&json_output::BundleOutput::new(&bundle));
+    }
+
+    let manifest = bundle.manifest();
+    println!("format version: {}", manifest.version);
+    println!("config: {}", manifest.config.hash);
+    println!("modules:");
+    for module in &manifest.modules {
+        let size = bundle.modules().get(&module.name).map_or(0, Vec::len);
+        println!("  {}: {} ({} bytes)", module.name, module.file.hash, size);
+    }
+    if !manifest.data.is_empty() {
+        println!("data:");
+    }
+    for file in &manifest.data {
+        let size = bundle.data_file(file).map_or(0, <[u8]>::len);
+        println!("  {}: {} ({} bytes)", file.path, file.hash, size);
+    }
+
+    Ok(())
+}
+
+fn bundle_verify(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
+    let bundle_path = std::path::Path::new(args.value_of(args::BUNDLE_PATH).unwrap());
+    let bundle = ServiceBundle::load(bundle_path)?;
+    bundle.verify()?;
+
+    println!("bundle is valid");
+
+    Ok(())
+}
+
+/// Treats an absent custom section as a missing optional value.
+fn optional_section<T>(section: Result<T, ModuleInfoError>) -> Result<Option<T>, ModuleInfoError> {
+    match section {
+        Ok(value) => Ok(Some(value)),
+        Err(ModuleInfoError::NoCustomSection(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn output_format(args: &clap::ArgMatches<'_>) -> Result<OutputFormat, anyhow::Error> {
+    args.value_of(args::OUTPUT_FORMAT).unwrap_or("text").parse()
+}
+
 fn repl(args: &clap::ArgMatches<'_>) -> Result<(), anyhow::Error> {
     use std::process::Command;
     use std::process::Stdio;
@@ -157,8 +157,12 @@ impl REPL {
         let config = MarineModuleConfig {
             logger_enabled: true,
             host_imports: Default::default(),
+            host_import_providers: Default::default(),
+            mounted_binaries: Default::default(),
             wasi: Default::default(),
             logging_mask: Default::default(),
+            log_targets: None,
+            logger_limits: Default::default(),
         };
         let result_msg = match self
             .app_service